@@ -0,0 +1,219 @@
+use crate::{LoyaltyProgram, Merchant, PointLot, SolcityError};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+/// Token accounts used when merchant collateral is held in an SPL token
+pub struct CollateralTokenAccounts<'a, 'info> {
+    /// Configured collateral mint (LoyaltyProgram.collateral_mint)
+    pub collateral_mint: &'a InterfaceAccount<'info, Mint>,
+
+    /// Merchant or destination side of the transfer
+    pub counterparty: &'a InterfaceAccount<'info, TokenAccount>,
+
+    /// Token account owned by the merchant escrow PDA
+    pub escrow: &'a InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program owning the collateral mint (SPL Token or Token-2022)
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+impl<'a, 'info> CollateralTokenAccounts<'a, 'info> {
+    /// Resolve the optional collateral token accounts for the program's collateral currency.
+    /// Returns None in lamport mode; errors if token-mode accounts are missing or mismatched.
+    pub fn resolve(
+        loyalty_program: &LoyaltyProgram,
+        merchant_escrow: Pubkey,
+        collateral_mint: Option<&'a InterfaceAccount<'info, Mint>>,
+        counterparty: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+        escrow: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+        token_program: Option<&'a Interface<'info, TokenInterface>>,
+    ) -> Result<Option<Self>> {
+        let Some(collateral_mint_key) = loyalty_program.collateral_mint else {
+            return Ok(None);
+        };
+
+        let (Some(collateral_mint), Some(counterparty), Some(escrow), Some(token_program)) =
+            (collateral_mint, counterparty, escrow, token_program)
+        else {
+            return err!(SolcityError::MissingCollateralTokenAccounts);
+        };
+
+        require_keys_eq!(
+            collateral_mint.key(),
+            collateral_mint_key,
+            SolcityError::InvalidCollateralMint
+        );
+        require_keys_eq!(
+            *collateral_mint.to_account_info().owner,
+            token_program.key(),
+            SolcityError::InvalidCollateralMint
+        );
+        require_keys_eq!(
+            counterparty.mint,
+            collateral_mint_key,
+            SolcityError::InvalidCollateralMint
+        );
+        require_keys_eq!(
+            escrow.mint,
+            collateral_mint_key,
+            SolcityError::InvalidCollateralMint
+        );
+        require_keys_eq!(escrow.owner, merchant_escrow, SolcityError::InvalidAccount);
+
+        Ok(Some(Self {
+            collateral_mint,
+            counterparty,
+            escrow,
+            token_program,
+        }))
+    }
+}
+
+/// Lamports an escrow needs before it can hold collateral: its rent-exempt minimum while it is
+/// still empty (funded on first use and not counted as collateral)
+pub fn escrow_rent_due(merchant_escrow: &SystemAccount) -> Result<u64> {
+    if merchant_escrow.lamports() == 0 {
+        Ok(Rent::get()?.minimum_balance(0))
+    } else {
+        Ok(0)
+    }
+}
+
+/// Move collateral from the merchant into its escrow, in lamports or the collateral token
+pub fn deposit_to_escrow<'info>(
+    amount: u64,
+    payer: &Signer<'info>,
+    merchant_escrow: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+    collateral_tokens: Option<&CollateralTokenAccounts<'_, 'info>>,
+) -> Result<()> {
+    match collateral_tokens {
+        None => {
+            let rent_due = escrow_rent_due(merchant_escrow)?;
+
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: payer.to_account_info(),
+                        to: merchant_escrow.to_account_info(),
+                    },
+                ),
+                amount.checked_add(rent_due).ok_or(SolcityError::Overflow)?,
+            )
+        }
+        Some(tokens) => token_interface::transfer_checked(
+            CpiContext::new(
+                tokens.token_program.to_account_info(),
+                token_interface::TransferChecked {
+                    from: tokens.counterparty.to_account_info(),
+                    mint: tokens.collateral_mint.to_account_info(),
+                    to: tokens.escrow.to_account_info(),
+                    authority: payer.to_account_info(),
+                },
+            ),
+            amount,
+            tokens.collateral_mint.decimals,
+        ),
+    }
+}
+
+/// Pay out of a merchant escrow PDA, in lamports (to `destination`) or the collateral token
+/// (to the counterparty token account)
+pub fn pay_from_escrow<'info>(
+    merchant: Pubkey,
+    escrow_bump: u8,
+    amount: u64,
+    merchant_escrow: &SystemAccount<'info>,
+    destination: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    collateral_tokens: Option<&CollateralTokenAccounts<'_, 'info>>,
+) -> Result<()> {
+    let escrow_seeds = &[
+        Merchant::ESCROW_SEED_PREFIX,
+        merchant.as_ref(),
+        &[escrow_bump],
+    ];
+    let signer_seeds = &[&escrow_seeds[..]];
+
+    match collateral_tokens {
+        None => system_program::transfer(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: merchant_escrow.to_account_info(),
+                    to: destination.clone(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        ),
+        Some(tokens) => token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                tokens.token_program.to_account_info(),
+                token_interface::TransferChecked {
+                    from: tokens.escrow.to_account_info(),
+                    mint: tokens.collateral_mint.to_account_info(),
+                    to: tokens.counterparty.to_account_info(),
+                    authority: merchant_escrow.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            tokens.collateral_mint.decimals,
+        ),
+    }
+}
+
+/// Release issuer liability for lots that left circulation without being redeemed.
+/// The Merchant PDA of each issuer must be passed in `merchants`; closed merchants are skipped.
+pub fn release_lots<'info>(
+    lots: &[PointLot],
+    loyalty_program: Pubkey,
+    merchants: &[AccountInfo<'info>],
+) -> Result<()> {
+    // Total points per issuing merchant (lots from before issuers were tracked have none)
+    let mut released_by_issuer: Vec<(Pubkey, u64)> = Vec::new();
+    for lot in lots.iter().filter(|lot| lot.merchant != Pubkey::default()) {
+        match released_by_issuer
+            .iter_mut()
+            .find(|(issuer, _)| *issuer == lot.merchant)
+        {
+            Some((_, points)) => {
+                *points = points
+                    .checked_add(lot.amount)
+                    .ok_or(SolcityError::Overflow)?
+            }
+            None => released_by_issuer.push((lot.merchant, lot.amount)),
+        }
+    }
+
+    for (issuer, points) in released_by_issuer {
+        let merchant_info = merchants
+            .iter()
+            .find(|account| account.key() == issuer)
+            .ok_or(SolcityError::MissingMerchantAccount)?;
+
+        if merchant_info.data_is_empty() {
+            continue;
+        }
+
+        require_keys_eq!(
+            *merchant_info.owner,
+            crate::ID,
+            SolcityError::InvalidAccount
+        );
+        let mut data = merchant_info.try_borrow_mut_data()?;
+        let mut merchant = Merchant::try_deserialize(&mut &data[..])?;
+        require_keys_eq!(
+            merchant.loyalty_program,
+            loyalty_program,
+            SolcityError::InvalidAccount
+        );
+        merchant.release_points(points);
+        merchant.try_serialize(&mut &mut data[..])?;
+    }
+
+    Ok(())
+}
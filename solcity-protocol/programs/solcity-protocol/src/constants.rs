@@ -1,23 +1,78 @@
-/// Tier thresholds (in tokens earned)
+/// Default tier thresholds (in tokens earned), used to seed TierConfig
 pub const BRONZE_THRESHOLD: u64 = 0;
 pub const SILVER_THRESHOLD: u64 = 1_000;
 pub const GOLD_THRESHOLD: u64 = 10_000;
 pub const PLATINUM_THRESHOLD: u64 = 50_000;
 
-/// Tier multipliers (percentage-based, 100 = 1.0x, divide by PERCENTAGE_DIVISOR)
+/// Default tier multipliers (percentage-based, 100 = 1.0x, divide by PERCENTAGE_DIVISOR)
 pub const BRONZE_MULTIPLIER: u64 = 100; // 1.0x
 pub const SILVER_MULTIPLIER: u64 = 125; // 1.25x
 pub const GOLD_MULTIPLIER: u64 = 150; // 1.5x
 pub const PLATINUM_MULTIPLIER: u64 = 200; // 2.0x
 
+/// Maximum number of configurable tiers (one per CustomerTier variant)
+pub const MAX_TIERS: usize = 4;
+
 /// Token configuration
 pub const TOKEN_DECIMALS: u8 = 6;
+pub const MAX_TOKEN_SYMBOL_LENGTH: usize = 10;
+pub const MAX_TOKEN_URI_LENGTH: usize = 200;
 pub const DEFAULT_INTEREST_RATE: i16 = 500; // 5% APY (in basis points)
+pub const MAX_INTEREST_RATE: i16 = 10_000; // 100% APY (in basis points)
+
+/// Point expiry
+pub const SECONDS_PER_DAY: i64 = 86_400;
+pub const DEFAULT_POINTS_EXPIRY_PERIOD: i64 = 365 * SECONDS_PER_DAY; // 1 year
+pub const MAX_POINT_LOTS: usize = 32;
+
+/// Default streak lengths (in days) that emit StreakMilestoneEvent
+pub const MAX_STREAK_MILESTONES: usize = 4;
+pub const DEFAULT_STREAK_MILESTONES: [u16; MAX_STREAK_MILESTONES] = [7, 30, 0, 0];
+
+/// Default referral program settings (bonuses in points)
+pub const DEFAULT_REFERRER_BONUS: u64 = 100;
+pub const DEFAULT_REFEREE_BONUS: u64 = 50;
+pub const DEFAULT_MAX_REFERRALS_PER_REFERRER: u32 = 50;
+
+/// Maximum number of reward rules evaluated per issue_rewards call
+pub const MAX_RULES_PER_ISSUANCE: usize = 5;
+
+/// Merchant issuance caps: alert when usage reaches these percentages of a cap
+pub const ISSUANCE_ALERT_THRESHOLDS: [u8; 2] = [80, 100];
+
+/// Maximum number of fee revenue recipients
+pub const MAX_FEE_RECIPIENTS: usize = 5;
+
+/// Maximum number of volume-based issuance fee tiers
+pub const MAX_VOLUME_FEE_TIERS: usize = 4;
 
 /// Basis points divisor
 pub const BASIS_POINTS: u64 = 10_000;
 pub const PERCENTAGE_DIVISOR: u64 = 100;
 
-/// Platform fees (in lamports)
-pub const MERCHANT_REGISTRATION_FEE: u64 = 10_000_000; // 0.01 SOL
-pub const ISSUANCE_FEE_PER_TOKEN: u64 = 100; // 0.0000001 SOL per token (very small for demo)
+/// Pause flags (bitmask on LoyaltyProgram.paused)
+pub const PAUSE_ISSUANCE: u8 = 1 << 0; // issue_rewards
+pub const PAUSE_REDEMPTION: u8 = 1 << 1; // redeem_rewards, gift_points, expire_points
+pub const PAUSE_REGISTRATION: u8 = 1 << 2; // register_merchant, register_customer
+pub const PAUSE_VOUCHER_USE: u8 = 1 << 3; // use_voucher
+pub const PAUSE_MERCHANT_OPS: u8 = 1 << 4; // reward rules, offers, merchant settings, collateral, settle
+pub const PAUSE_FEE_CLAIMS: u8 = 1 << 5; // claim_fees
+pub const PAUSE_ALL: u8 = PAUSE_ISSUANCE
+    | PAUSE_REDEMPTION
+    | PAUSE_REGISTRATION
+    | PAUSE_VOUCHER_USE
+    | PAUSE_MERCHANT_OPS
+    | PAUSE_FEE_CLAIMS;
+
+/// Default platform fees (in lamports), stored on LoyaltyProgram at initialization
+pub const DEFAULT_MERCHANT_REGISTRATION_FEE: u64 = 10_000_000; // 0.01 SOL
+pub const DEFAULT_ISSUANCE_FEE_PER_TOKEN: u64 = 100; // 0.0000001 SOL per token (very small for demo)
+
+/// TransactionRecord.transaction_type for customer-to-customer gifts
+pub const TRANSACTION_TYPE_GIFT: u8 = 2;
+
+/// Account layout version written to every state account (bump when a layout changes)
+/// 0: original layout without a version byte (upgraded by migrate_account);
+/// 1: version byte and reserved padding; 2: PointLot records its issuing merchant and
+/// TransactionRecord keeps a gift's other customer in counterparty instead of merchant
+pub const CURRENT_ACCOUNT_VERSION: u8 = 2;
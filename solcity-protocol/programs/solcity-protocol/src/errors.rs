@@ -5,7 +5,7 @@ pub enum SolcityError {
     #[msg("Insufficient token balance for redemption")]
     InsufficientBalance,
 
-    #[msg("Merchant account is not active")]
+    #[msg("Merchant account is not approved or has been suspended")]
     MerchantNotActive,
 
     #[msg("Invalid reward amount")]
@@ -17,6 +17,9 @@ pub enum SolcityError {
     #[msg("Invalid customer tier")]
     InvalidTier,
 
+    #[msg("Invalid tier schedule: thresholds must start at 0 and increase, multipliers must be at least 1x")]
+    InvalidTierConfig,
+
     #[msg("Reward rule is not active")]
     RuleNotActive,
 
@@ -55,4 +58,139 @@ pub enum SolcityError {
 
     #[msg("Invalid account provided")]
     InvalidAccount,
+
+    #[msg("Merchant status does not allow this transition")]
+    InvalidMerchantStatus,
+
+    #[msg("Invalid volume fee schedule: volume thresholds must be strictly increasing")]
+    InvalidVolumeFeeTiers,
+
+    #[msg("Fee token accounts are required when fees are paid in an SPL token")]
+    MissingFeeTokenAccounts,
+
+    #[msg("Fee token account or mint does not match the configured fee mint")]
+    InvalidFeeMint,
+
+    #[msg("Outstanding fees must be settled before changing the fee currency")]
+    FeesOutstanding,
+
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+
+    #[msg("Invalid new authority")]
+    InvalidAuthority,
+
+    #[msg("This operation is currently paused")]
+    ProgramPaused,
+
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+
+    #[msg("Withdrawal amount exceeds available treasury balance")]
+    InsufficientTreasuryBalance,
+
+    #[msg("Invalid fee split: shares must be non-zero, unique and sum to 10000 basis points")]
+    InvalidFeeSplit,
+
+    #[msg("Fee recipient still has unclaimed fees")]
+    FeeRecipientHasUnclaimedFees,
+
+    #[msg("Signer is not a fee recipient")]
+    NotFeeRecipient,
+
+    #[msg("No fees available to claim")]
+    NothingToClaim,
+
+    #[msg("Account is already at the current layout version")]
+    AccountAlreadyMigrated,
+
+    #[msg("Token symbol must be 1-10 characters and URI at most 200 characters")]
+    InvalidTokenMetadata,
+
+    #[msg("Transfer hook accounts are required for RegisteredCustomersOnly mode")]
+    MissingTransferHookAccounts,
+
+    #[msg("Points expiry period cannot be negative")]
+    InvalidExpiryPeriod,
+
+    #[msg("Customer account is frozen")]
+    CustomerFrozen,
+
+    #[msg("Customer account is not frozen")]
+    CustomerNotFrozen,
+
+    #[msg("Points cannot be transferred in this program")]
+    TransfersDisabled,
+
+    #[msg("Cannot gift points to yourself")]
+    CannotGiftSelf,
+
+    #[msg("Daily gifting limit exceeded for this tier")]
+    GiftLimitExceeded,
+
+    #[msg("At most one gift limit per tier")]
+    InvalidGiftLimits,
+
+    #[msg("Settlement ledger for a redeemed merchant pair was not provided")]
+    MissingSettlementLedger,
+
+    #[msg("Collateral amount must be greater than zero")]
+    InvalidCollateralAmount,
+
+    #[msg("Point value is not configured for this program")]
+    CollateralNotConfigured,
+
+    #[msg("Merchant escrow balance is too low to settle")]
+    InsufficientEscrowBalance,
+
+    #[msg("Merchant collateral does not cover the value of the points")]
+    InsufficientCollateral,
+
+    #[msg("Collateral token accounts are required when collateral is held in an SPL token")]
+    MissingCollateralTokenAccounts,
+
+    #[msg("Collateral mint does not match the program's collateral currency")]
+    InvalidCollateralMint,
+
+    #[msg("Collateral must be withdrawn before this change")]
+    CollateralOutstanding,
+
+    #[msg("Issuing merchant account was not provided")]
+    MissingMerchantAccount,
+
+    #[msg("Amount exceeds the merchant's per-transaction issuance cap")]
+    TransactionIssuanceCapExceeded,
+
+    #[msg("Amount exceeds the merchant's rolling 24h issuance cap")]
+    DailyIssuanceCapExceeded,
+
+    #[msg("Amount exceeds the merchant's lifetime issuance budget")]
+    IssuanceBudgetExhausted,
+
+    #[msg("Too many reward rules for one issuance")]
+    TooManyRewardRules,
+
+    #[msg("Reward rule is invalid or belongs to another merchant")]
+    InvalidRewardRule,
+
+    #[msg("Reward rule passed more than once")]
+    DuplicateRewardRule,
+
+    #[msg("Reward rule parameters do not match its rule type")]
+    InvalidRuleParameters,
+
+    #[msg("Streak milestones must be non-zero and ascending")]
+    InvalidStreakMilestones,
+
+    #[msg("Customers cannot refer themselves")]
+    SelfReferral,
+
+    #[msg("Referrer has reached the maximum number of referrals")]
+    ReferralLimitReached,
+
+    #[msg("Referral accounts are missing or do not match the customer's referral")]
+    MissingReferralAccounts,
+
+    #[msg("TierConfig and treasury accounts are required to migrate a loyalty program")]
+    MissingMigrationAccounts,
 }
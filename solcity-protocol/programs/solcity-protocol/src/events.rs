@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::state::{CustomerTier, RedemptionType};
+use crate::state::{
+    CustomerTier, FeeRule, IssuanceCapKind, IssuanceCaps, MerchantStatus, RedemptionType,
+    RuleCombination, RuleEvaluation, TierLevel, VolumeFeeTier,
+};
 
 /// Event emitted when a merchant registers
 #[event]
@@ -30,12 +33,17 @@ pub struct RewardsIssuedEvent {
     pub purchase_amount: u64, // in cents
     pub base_reward: u64,
     pub tier_multiplier: u64,
-    pub rule_multiplier: u64,
+    pub rule_multiplier: u64, // combined multiplier of every applied rule
+    pub rule_bonus: u64,      // points the applied rules added (flat bonuses included, after caps)
     pub rule_applied: bool,
-    pub rule_name: Option<String>,
+    pub rule_name: Option<String>, // first applied rule
+    pub rule_combination: RuleCombination,
+    pub rules_evaluated: Vec<RuleEvaluation>,
     pub final_reward: u64,
     pub customer_tier: CustomerTier,
     pub platform_fee: u64,
+    pub fee_per_token: u64,
+    pub fee_rule: FeeRule,
     pub timestamp: i64,
 }
 
@@ -113,3 +121,357 @@ pub struct VoucherUsedEvent {
     pub redemption_code: String,
     pub timestamp: i64,
 }
+
+/// Event emitted when the loyalty program configuration is updated
+#[event]
+pub struct ProgramConfigUpdatedEvent {
+    pub loyalty_program: Pubkey,
+    pub authority: Pubkey,
+    pub old_merchant_registration_fee: u64,
+    pub new_merchant_registration_fee: u64,
+    pub old_issuance_fee_per_token: u64,
+    pub new_issuance_fee_per_token: u64,
+    pub old_points_expiry_period: i64,
+    pub new_points_expiry_period: i64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a new program authority is proposed
+#[event]
+pub struct AuthorityTransferProposedEvent {
+    pub loyalty_program: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a pending authority transfer is accepted
+#[event]
+pub struct AuthorityTransferredEvent {
+    pub loyalty_program: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a pending authority transfer is cancelled
+#[event]
+pub struct AuthorityTransferCancelledEvent {
+    pub loyalty_program: Pubkey,
+    pub authority: Pubkey,
+    pub cancelled_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the program pause state changes
+#[event]
+pub struct PauseStateChangedEvent {
+    pub loyalty_program: Pubkey,
+    pub authority: Pubkey,
+    pub old_paused: u8,
+    pub new_paused: u8,
+    pub timestamp: i64,
+}
+
+/// Event emitted when platform fees are withdrawn from the treasury
+#[event]
+pub struct FeesWithdrawnEvent {
+    pub loyalty_program: Pubkey,
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_fees_withdrawn: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the fee revenue split is changed
+#[event]
+pub struct FeeRecipientsUpdatedEvent {
+    pub loyalty_program: Pubkey,
+    pub authority: Pubkey,
+    pub recipients: Vec<Pubkey>,
+    pub shares_bps: Vec<u16>,
+    pub timestamp: i64,
+}
+
+/// Event emitted for each recipient share of a collected fee
+#[event]
+pub struct FeeDistributedEvent {
+    pub loyalty_program: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub total_fee: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a fee recipient claims accrued fees
+#[event]
+pub struct FeesClaimedEvent {
+    pub loyalty_program: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the tier schedule is changed
+#[event]
+pub struct TierConfigUpdatedEvent {
+    pub loyalty_program: Pubkey,
+    pub tiers: Vec<TierLevel>,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an admin changes a merchant's approval status
+#[event]
+pub struct MerchantStatusChangedEvent {
+    pub merchant: Pubkey,
+    pub merchant_authority: Pubkey,
+    pub authority: Pubkey,
+    pub old_status: MerchantStatus,
+    pub new_status: MerchantStatus,
+    pub fee_refunded: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the program authority sets or clears a merchant fee override
+#[event]
+pub struct MerchantFeeOverrideEvent {
+    pub merchant: Pubkey,
+    pub authority: Pubkey,
+    pub old_fee_override: Option<u64>,
+    pub new_fee_override: Option<u64>,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the volume-based issuance fee schedule is changed
+#[event]
+pub struct VolumeFeeTiersUpdatedEvent {
+    pub loyalty_program: Pubkey,
+    pub authority: Pubkey,
+    pub old_tiers: Vec<VolumeFeeTier>,
+    pub new_tiers: Vec<VolumeFeeTier>,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the platform fee currency is changed
+#[event]
+pub struct FeeCurrencyChangedEvent {
+    pub loyalty_program: Pubkey,
+    pub authority: Pubkey,
+    pub old_fee_mint: Option<Pubkey>,
+    pub new_fee_mint: Option<Pubkey>,
+    pub merchant_registration_fee: u64,
+    pub issuance_fee_per_token: u64,
+    pub volume_fee_tiers: Vec<VolumeFeeTier>,
+    pub fee_currency_epoch: u32,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an account is migrated to the current layout version
+#[event]
+pub struct AccountMigratedEvent {
+    pub account: Pubkey,
+    pub account_type: String,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub payer: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the mint interest rate is changed
+#[event]
+pub struct InterestRateUpdatedEvent {
+    pub loyalty_program: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub old_rate: i16,
+    pub new_rate: i16,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the mint's token metadata is changed
+#[event]
+pub struct TokenMetadataUpdatedEvent {
+    pub loyalty_program: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub timestamp: i64,
+}
+
+/// Event emitted on every expire_points crank run
+#[event]
+pub struct PointsExpiredEvent {
+    pub loyalty_program: Pubkey,
+    pub customer: Pubkey,
+    pub customer_wallet: Pubkey,
+    pub lots_expired: u8,
+    pub amount_expired: u64,
+    pub amount_burned: u64,
+    pub next_expiry_at: i64,
+    pub next_expiry_amount: u64,
+    pub cranker: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a customer's token account is frozen or thawed
+#[event]
+pub struct CustomerFreezeChangedEvent {
+    pub customer: Pubkey,
+    pub customer_wallet: Pubkey,
+    pub authority: Pubkey,
+    pub is_frozen: bool,
+    pub reason: u8,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a customer gifts points to another customer
+#[event]
+pub struct PointsGiftedEvent {
+    pub loyalty_program: Pubkey,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub sender_tier: CustomerTier,
+    pub gifted_today: u64,
+    pub daily_limit: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the per-tier daily gifting limits are changed
+#[event]
+pub struct GiftLimitsUpdatedEvent {
+    pub loyalty_program: Pubkey,
+    pub authority: Pubkey,
+    pub old_limits: [u64; 4],
+    pub new_limits: [u64; 4],
+    pub timestamp: i64,
+}
+
+/// Event emitted when the collateral value per point or collateral currency is changed
+#[event]
+pub struct CollateralConfigUpdatedEvent {
+    pub loyalty_program: Pubkey,
+    pub authority: Pubkey,
+    pub old_point_value: u64,
+    pub new_point_value: u64,
+    pub old_collateral_mint: Option<Pubkey>,
+    pub new_collateral_mint: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a merchant deposits collateral into its escrow
+#[event]
+pub struct CollateralDepositedEvent {
+    pub merchant: Pubkey,
+    pub merchant_authority: Pubkey,
+    pub amount: u64,
+    pub collateral_balance: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a merchant pair's settlement ledger is settled
+#[event]
+pub struct SettlementEvent {
+    pub settlement_ledger: Pubkey,
+    pub debtor: Pubkey,
+    pub creditor: Pubkey,
+    pub net_points: u64,
+    pub point_value: u64,
+    pub value: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a merchant withdraws excess collateral from its escrow
+#[event]
+pub struct CollateralWithdrawnEvent {
+    pub merchant: Pubkey,
+    pub merchant_authority: Pubkey,
+    pub amount: u64,
+    pub collateral_balance: u64,
+    pub outstanding_points: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a merchant's issuance caps or the authority's ceiling change
+#[event]
+pub struct IssuanceCapsUpdatedEvent {
+    pub merchant: Pubkey,
+    pub updated_by: Pubkey,
+    pub issuance_caps: IssuanceCaps,
+    pub issuance_ceiling: IssuanceCaps,
+    pub timestamp: i64,
+}
+
+/// Event emitted when issuance reaches 80% or 100% of a merchant cap
+#[event]
+pub struct IssuanceCapAlertEvent {
+    pub loyalty_program: Pubkey,
+    pub merchant: Pubkey,
+    pub kind: IssuanceCapKind,
+    pub threshold_percent: u8,
+    pub issued: u64,
+    pub cap: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a customer's activity streak reaches a configured milestone
+#[event]
+pub struct StreakMilestoneEvent {
+    pub loyalty_program: Pubkey,
+    pub customer: Pubkey,
+    pub customer_wallet: Pubkey,
+    pub streak_days: u16,
+    pub longest_streak: u16,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the streak milestone lengths are changed
+#[event]
+pub struct StreakMilestonesUpdatedEvent {
+    pub loyalty_program: Pubkey,
+    pub authority: Pubkey,
+    pub old_milestones: [u16; 4],
+    pub new_milestones: [u16; 4],
+    pub timestamp: i64,
+}
+
+/// Event emitted when a customer registers with a referrer
+#[event]
+pub struct ReferralRegisteredEvent {
+    pub loyalty_program: Pubkey,
+    pub referral: Pubkey,
+    pub referrer: Pubkey,
+    pub referee: Pubkey,
+    pub referral_count: u32,
+    pub timestamp: i64,
+}
+
+/// Event emitted when referral bonuses are paid on the referee's first qualifying purchase
+#[event]
+pub struct ReferralRewardedEvent {
+    pub loyalty_program: Pubkey,
+    pub referral: Pubkey,
+    pub referrer: Pubkey,
+    pub referee: Pubkey,
+    pub merchant: Pubkey,
+    pub purchase_amount: u64,
+    pub referrer_bonus: u64,
+    pub referee_bonus: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the referral program settings are changed
+#[event]
+pub struct ReferralConfigUpdatedEvent {
+    pub loyalty_program: Pubkey,
+    pub authority: Pubkey,
+    pub referrer_bonus: u64,
+    pub referee_bonus: u64,
+    pub referral_min_purchase: u64,
+    pub max_referrals_per_referrer: u32,
+    pub timestamp: i64,
+}
@@ -0,0 +1,164 @@
+use crate::{LoyaltyProgram, SolcityError};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+/// Token accounts used when platform fees are paid in an SPL token
+pub struct FeeTokenAccounts<'a, 'info> {
+    /// Configured fee mint (LoyaltyProgram.fee_mint)
+    pub fee_mint: &'a InterfaceAccount<'info, Mint>,
+
+    /// Merchant or recipient side of the transfer
+    pub counterparty: &'a InterfaceAccount<'info, TokenAccount>,
+
+    /// Token account owned by the treasury PDA
+    pub treasury: &'a InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program owning the fee mint (SPL Token or Token-2022)
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+impl<'a, 'info> FeeTokenAccounts<'a, 'info> {
+    /// Resolve the optional fee token accounts for the program's fee currency.
+    /// Returns None in lamport mode; errors if token-mode accounts are missing or mismatched.
+    pub fn resolve(
+        loyalty_program: &LoyaltyProgram,
+        fee_mint: Option<&'a InterfaceAccount<'info, Mint>>,
+        counterparty: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+        treasury: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+        token_program: Option<&'a Interface<'info, TokenInterface>>,
+    ) -> Result<Option<Self>> {
+        let Some(fee_mint_key) = loyalty_program.fee_mint else {
+            return Ok(None);
+        };
+
+        let (Some(fee_mint), Some(counterparty), Some(treasury), Some(token_program)) =
+            (fee_mint, counterparty, treasury, token_program)
+        else {
+            return err!(SolcityError::MissingFeeTokenAccounts);
+        };
+
+        require_keys_eq!(fee_mint.key(), fee_mint_key, SolcityError::InvalidFeeMint);
+        require_keys_eq!(
+            *fee_mint.to_account_info().owner,
+            token_program.key(),
+            SolcityError::InvalidFeeMint
+        );
+        require_keys_eq!(counterparty.mint, fee_mint_key, SolcityError::InvalidFeeMint);
+        require_keys_eq!(treasury.mint, fee_mint_key, SolcityError::InvalidFeeMint);
+        require_keys_eq!(
+            treasury.owner,
+            loyalty_program.treasury,
+            SolcityError::InvalidTreasury
+        );
+
+        Ok(Some(Self {
+            fee_mint,
+            counterparty,
+            treasury,
+            token_program,
+        }))
+    }
+}
+
+/// Transfer a platform fee from the payer into the treasury, in lamports or the fee token
+pub fn collect_fee<'info>(
+    amount: u64,
+    payer: &Signer<'info>,
+    platform_treasury: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+    fee_tokens: Option<&FeeTokenAccounts<'_, 'info>>,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    match fee_tokens {
+        None => system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: platform_treasury.to_account_info(),
+                },
+            ),
+            amount,
+        ),
+        Some(tokens) => token_interface::transfer_checked(
+            CpiContext::new(
+                tokens.token_program.to_account_info(),
+                token_interface::TransferChecked {
+                    from: tokens.counterparty.to_account_info(),
+                    mint: tokens.fee_mint.to_account_info(),
+                    to: tokens.treasury.to_account_info(),
+                    authority: payer.to_account_info(),
+                },
+            ),
+            amount,
+            tokens.fee_mint.decimals,
+        ),
+    }
+}
+
+/// Pay out of the treasury PDA, in lamports (to `destination`) or the fee token
+/// (to the counterparty token account)
+pub fn pay_from_treasury<'info>(
+    loyalty_program: &Account<'info, LoyaltyProgram>,
+    amount: u64,
+    platform_treasury: &SystemAccount<'info>,
+    destination: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    fee_tokens: Option<&FeeTokenAccounts<'_, 'info>>,
+) -> Result<()> {
+    let loyalty_program_key = loyalty_program.key();
+    let treasury_seeds = &[
+        LoyaltyProgram::TREASURY_SEED_PREFIX,
+        loyalty_program_key.as_ref(),
+        &[loyalty_program.treasury_bump],
+    ];
+    let signer_seeds = &[&treasury_seeds[..]];
+
+    match fee_tokens {
+        None => {
+            // Keep the treasury rent-exempt
+            let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+            let available = platform_treasury
+                .lamports()
+                .saturating_sub(rent_exempt_minimum);
+            require!(amount <= available, SolcityError::InsufficientTreasuryBalance);
+
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: platform_treasury.to_account_info(),
+                        to: destination.clone(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            )
+        }
+        Some(tokens) => {
+            require!(
+                amount <= tokens.treasury.amount,
+                SolcityError::InsufficientTreasuryBalance
+            );
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    tokens.token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: tokens.treasury.to_account_info(),
+                        mint: tokens.fee_mint.to_account_info(),
+                        to: tokens.counterparty.to_account_info(),
+                        authority: platform_treasury.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                tokens.fee_mint.decimals,
+            )
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{AuthorityTransferredEvent, LoyaltyProgram, SolcityError};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    /// Proposed authority accepting the transfer
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        constraint = loyalty_program.pending_authority.is_some() @ SolcityError::NoPendingAuthority,
+        constraint = loyalty_program.pending_authority == Some(new_authority.key()) @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+}
+
+pub fn handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    let old_authority = loyalty_program.authority;
+    let new_authority = ctx.accounts.new_authority.key();
+
+    // PDA derivation uses seed_authority, so existing accounts stay valid
+    loyalty_program.authority = new_authority;
+    loyalty_program.pending_authority = None;
+
+    emit!(AuthorityTransferredEvent {
+        loyalty_program: loyalty_program.key(),
+        old_authority,
+        new_authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Authority transferred from {} to {}", old_authority, new_authority);
+
+    Ok(())
+}
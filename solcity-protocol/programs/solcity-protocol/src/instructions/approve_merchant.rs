@@ -0,0 +1,77 @@
+use crate::{
+    FeeDistributedEvent, LoyaltyProgram, Merchant, MerchantStatus, MerchantStatusChangedEvent,
+    SolcityError,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ApproveMerchant<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Merchant::SEED_PREFIX,
+            merchant.authority.as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = merchant.bump,
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+}
+
+pub fn handler(ctx: Context<ApproveMerchant>) -> Result<()> {
+    let merchant = &mut ctx.accounts.merchant;
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    let old_status = merchant.status.clone();
+    require!(
+        old_status == MerchantStatus::Pending || old_status == MerchantStatus::Suspended,
+        SolcityError::InvalidMerchantStatus
+    );
+
+    // The held registration fee becomes platform revenue on first approval
+    let registration_fee = merchant.registration_fee_paid;
+    if registration_fee > 0 {
+        loyalty_program.pending_registration_fees = loyalty_program
+            .pending_registration_fees
+            .checked_sub(registration_fee)
+            .ok_or(SolcityError::Overflow)?;
+
+        for (recipient, amount) in loyalty_program.record_fee(registration_fee)? {
+            emit!(FeeDistributedEvent {
+                loyalty_program: loyalty_program.key(),
+                recipient,
+                amount,
+                total_fee: registration_fee,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+        merchant.registration_fee_paid = 0;
+    }
+
+    merchant.status = MerchantStatus::Approved;
+
+    emit!(MerchantStatusChangedEvent {
+        merchant: merchant.key(),
+        merchant_authority: merchant.authority,
+        authority: ctx.accounts.authority.key(),
+        old_status: old_status.clone(),
+        new_status: MerchantStatus::Approved,
+        fee_refunded: 0,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Merchant '{}' approved (was {:?})", merchant.name, old_status);
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::{AuthorityTransferCancelledEvent, LoyaltyProgram, SolcityError};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelAuthorityTransfer<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+}
+
+pub fn handler(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    let cancelled_authority = loyalty_program
+        .pending_authority
+        .take()
+        .ok_or(SolcityError::NoPendingAuthority)?;
+
+    emit!(AuthorityTransferCancelledEvent {
+        loyalty_program: loyalty_program.key(),
+        authority: ctx.accounts.authority.key(),
+        cancelled_authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Authority transfer to {} cancelled", cancelled_authority);
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use crate::{FeesClaimedEvent, LoyaltyProgram, SolcityError, PAUSE_FEE_CLAIMS};
+use crate::fees::{pay_from_treasury, FeeTokenAccounts};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ClaimFees<'info> {
+    /// Fee recipient claiming its accrued share
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+
+    /// Platform treasury PDA holding collected fees
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::TREASURY_SEED_PREFIX, loyalty_program.key().as_ref()],
+        bump = loyalty_program.treasury_bump,
+    )]
+    pub platform_treasury: SystemAccount<'info>,
+
+    /// Fee mint (required when fees are paid in an SPL token)
+    pub fee_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Recipient token account receiving the claim (required when fees are paid in an SPL token)
+    #[account(mut)]
+    pub recipient_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Treasury PDA token account holding fees (required when fees are paid in an SPL token)
+    #[account(mut)]
+    pub treasury_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub fee_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ClaimFees>) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_FEE_CLAIMS),
+        SolcityError::ProgramPaused
+    );
+
+    let recipient_key = ctx.accounts.recipient.key();
+    let loyalty_program = &ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    let index = loyalty_program
+        .fee_recipients
+        .iter()
+        .position(|r| r.recipient == recipient_key)
+        .ok_or(SolcityError::NotFeeRecipient)?;
+    let amount = loyalty_program.fee_recipients[index].accrued;
+    require!(amount > 0, SolcityError::NothingToClaim);
+
+    let fee_tokens = FeeTokenAccounts::resolve(
+        &ctx.accounts.loyalty_program,
+        ctx.accounts.fee_mint.as_deref(),
+        ctx.accounts.recipient_fee_account.as_deref(),
+        ctx.accounts.treasury_fee_account.as_deref(),
+        ctx.accounts.fee_token_program.as_ref(),
+    )?;
+
+    let loyalty_program_key = loyalty_program.key();
+    pay_from_treasury(
+        &ctx.accounts.loyalty_program,
+        amount,
+        &ctx.accounts.platform_treasury,
+        &ctx.accounts.recipient.to_account_info(),
+        &ctx.accounts.system_program,
+        fee_tokens.as_ref(),
+    )?;
+
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let fee_recipient = &mut loyalty_program.fee_recipients[index];
+    fee_recipient.accrued = 0;
+    fee_recipient.claimed = fee_recipient
+        .claimed
+        .checked_add(amount)
+        .ok_or(SolcityError::Overflow)?;
+    let total_claimed = fee_recipient.claimed;
+
+    loyalty_program.total_fees_withdrawn = loyalty_program
+        .total_fees_withdrawn
+        .checked_add(amount)
+        .ok_or(SolcityError::Overflow)?;
+
+    emit!(FeesClaimedEvent {
+        loyalty_program: loyalty_program_key,
+        recipient: recipient_key,
+        amount,
+        total_claimed,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Fee recipient {} claimed {} in fees", recipient_key, amount);
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::{LoyaltyProgram, Merchant};
+use crate::{LoyaltyProgram, Merchant, MerchantStatus, SolcityError, PAUSE_MERCHANT_OPS};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -21,7 +21,7 @@ pub struct CloseMerchant<'info> {
 
     #[account(
         mut,
-        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.authority.as_ref()],
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
         bump = loyalty_program.bump,
     )]
     pub loyalty_program: Account<'info, LoyaltyProgram>,
@@ -30,7 +30,25 @@ pub struct CloseMerchant<'info> {
 }
 
 pub fn handler(ctx: Context<CloseMerchant>) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
     let merchant = &ctx.accounts.merchant;
+
+    // Pending merchants must be rejected first so the held fee is refunded
+    require!(
+        merchant.status != MerchantStatus::Pending,
+        SolcityError::InvalidMerchantStatus
+    );
+
+    // Escrowed collateral must be withdrawn first; it would be stranded in the escrow PDA
+    require!(
+        merchant.collateral_balance == 0,
+        SolcityError::CollateralOutstanding
+    );
+
     let loyalty_program = &mut ctx.accounts.loyalty_program;
 
     // Decrement total merchants count
@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::{LoyaltyProgram, Merchant, RedemptionOffer, RedemptionType, SolcityError};
+use crate::{
+    LoyaltyProgram, Merchant, RedemptionOffer, RedemptionType, SolcityError,
+    CURRENT_ACCOUNT_VERSION, PAUSE_MERCHANT_OPS,
+};
 
 #[derive(Accounts)]
 #[instruction(name: String)]
@@ -15,12 +18,12 @@ pub struct CreateRedemptionOffer<'info> {
             loyalty_program.key().as_ref()
         ],
         bump = merchant.bump,
-        constraint = merchant.is_active @ SolcityError::MerchantNotActive,
+        constraint = merchant.is_approved() @ SolcityError::MerchantNotActive,
     )]
     pub merchant: Account<'info, Merchant>,
 
     #[account(
-        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.authority.as_ref()],
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
         bump = loyalty_program.bump,
     )]
     pub loyalty_program: Account<'info, LoyaltyProgram>,
@@ -51,6 +54,11 @@ pub fn handler(
     quantity_limit: Option<u64>,
     expiration: Option<i64>,
 ) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
     require!(!name.is_empty(), SolcityError::NameEmpty);
     require!(name.len() <= 64, SolcityError::NameTooLong);
     require!(description.len() <= 256, SolcityError::NameTooLong);
@@ -60,6 +68,7 @@ pub fn handler(
     let offer = &mut ctx.accounts.redemption_offer;
     let clock = Clock::get()?;
 
+    offer.version = CURRENT_ACCOUNT_VERSION;
     offer.merchant = ctx.accounts.merchant.key();
     offer.loyalty_program = ctx.accounts.loyalty_program.key();
     offer.name = name.clone();
@@ -1,4 +1,4 @@
-use crate::{LoyaltyProgram, Merchant, RewardRule, SolcityError};
+use crate::{LoyaltyProgram, Merchant, RewardRule, SolcityError, PAUSE_MERCHANT_OPS};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -8,10 +8,7 @@ pub struct DeleteRewardRule<'info> {
     pub merchant_authority: Signer<'info>,
 
     #[account(
-        seeds = [
-            LoyaltyProgram::SEED_PREFIX,
-            merchant_authority.key().as_ref()
-        ],
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
         bump = loyalty_program.bump,
     )]
     pub loyalty_program: Account<'info, LoyaltyProgram>,
@@ -41,6 +38,11 @@ pub struct DeleteRewardRule<'info> {
 }
 
 pub fn handler(ctx: Context<DeleteRewardRule>, _rule_id: u64) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
     msg!("Reward rule '{}' deleted", ctx.accounts.reward_rule.name);
     Ok(())
 }
@@ -0,0 +1,107 @@
+use crate::collateral::{deposit_to_escrow, CollateralTokenAccounts};
+use crate::{CollateralDepositedEvent, LoyaltyProgram, Merchant, SolcityError, PAUSE_MERCHANT_OPS};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct DepositCollateral<'info> {
+    #[account(mut)]
+    pub merchant_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Merchant::SEED_PREFIX,
+            merchant_authority.key().as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = merchant.bump,
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+
+    /// Program-owned escrow PDA holding the merchant's collateral
+    #[account(
+        mut,
+        seeds = [Merchant::ESCROW_SEED_PREFIX, merchant.key().as_ref()],
+        bump,
+    )]
+    pub merchant_escrow: SystemAccount<'info>,
+
+    /// Collateral mint (required when collateral is held in an SPL token)
+    pub collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Merchant token account funding the deposit (required when collateral is held in an SPL token)
+    #[account(mut)]
+    pub merchant_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Escrow PDA token account (required when collateral is held in an SPL token)
+    #[account(mut)]
+    pub escrow_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub collateral_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
+    require!(amount > 0, SolcityError::InvalidCollateralAmount);
+
+    let collateral_tokens = CollateralTokenAccounts::resolve(
+        &ctx.accounts.loyalty_program,
+        ctx.accounts.merchant_escrow.key(),
+        ctx.accounts.collateral_mint.as_deref(),
+        ctx.accounts.merchant_collateral_account.as_deref(),
+        ctx.accounts.escrow_collateral_account.as_deref(),
+        ctx.accounts.collateral_token_program.as_ref(),
+    )?;
+
+    let clock = Clock::get()?;
+
+    deposit_to_escrow(
+        amount,
+        &ctx.accounts.merchant_authority,
+        &ctx.accounts.merchant_escrow,
+        &ctx.accounts.system_program,
+        collateral_tokens.as_ref(),
+    )?;
+
+    let merchant = &mut ctx.accounts.merchant;
+    merchant.collateral_balance = merchant
+        .collateral_balance
+        .checked_add(amount)
+        .ok_or(SolcityError::Overflow)?;
+
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    loyalty_program.total_collateral = loyalty_program
+        .total_collateral
+        .checked_add(amount)
+        .ok_or(SolcityError::Overflow)?;
+
+    emit!(CollateralDepositedEvent {
+        merchant: merchant.key(),
+        merchant_authority: ctx.accounts.merchant_authority.key(),
+        amount,
+        collateral_balance: merchant.collateral_balance,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Deposited {} collateral (balance: {})",
+        amount,
+        merchant.collateral_balance
+    );
+
+    Ok(())
+}
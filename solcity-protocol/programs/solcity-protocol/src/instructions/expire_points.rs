@@ -0,0 +1,136 @@
+use crate::collateral::release_lots;
+use crate::{
+    Customer, LoyaltyProgram, PointLots, PointsExpiredEvent, SolcityError, PAUSE_REDEMPTION,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{self, Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct ExpirePoints<'info> {
+    /// Anyone can run the crank
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Customer::SEED_PREFIX,
+            customer.wallet.as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = customer.bump,
+        constraint = !customer.is_frozen @ SolcityError::CustomerFrozen,
+    )]
+    pub customer: Box<Account<'info, Customer>>,
+
+    #[account(
+        mut,
+        seeds = [PointLots::SEED_PREFIX, customer.key().as_ref()],
+        bump = point_lots.bump,
+    )]
+    pub point_lots: Box<Account<'info, PointLots>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint", loyalty_program.key().as_ref()],
+        bump,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = customer.wallet,
+        associated_token::token_program = token_program,
+    )]
+    pub customer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token2022>,
+    // remaining_accounts: Merchant PDAs that issued the expired lots (liability is released)
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ExpirePoints<'info>>) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_REDEMPTION),
+        SolcityError::ProgramPaused
+    );
+
+    let clock = Clock::get()?;
+    let customer = &mut ctx.accounts.customer;
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+
+    let expired_lots = ctx.accounts.point_lots.take_expired(clock.unix_timestamp);
+    let lots_expired = expired_lots.len() as u8;
+    let amount_expired = expired_lots.iter().try_fold(0u64, |total, lot| {
+        total.checked_add(lot.amount).ok_or(SolcityError::Overflow)
+    })?;
+
+    release_lots(&expired_lots, loyalty_program.key(), ctx.remaining_accounts)?;
+
+    // Points may have been transferred away since they were earned; burn what is left
+    let amount_burned = amount_expired.min(ctx.accounts.customer_token_account.amount);
+
+    if amount_burned > 0 {
+        let program_seeds = &[
+            LoyaltyProgram::SEED_PREFIX,
+            loyalty_program.seed_authority.as_ref(),
+            &[loyalty_program.bump],
+        ];
+        let signer_seeds = &[&program_seeds[..]];
+
+        // The loyalty program PDA is the mint's permanent delegate
+        token_2022::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022::Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.customer_token_account.to_account_info(),
+                    authority: loyalty_program.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_burned,
+        )?;
+
+        customer.total_expired = customer
+            .total_expired
+            .checked_add(amount_burned)
+            .ok_or(SolcityError::Overflow)?;
+
+        loyalty_program.total_tokens_expired = loyalty_program
+            .total_tokens_expired
+            .checked_add(amount_burned)
+            .ok_or(SolcityError::Overflow)?;
+    }
+
+    ctx.accounts.point_lots.sync_next_expiry(customer);
+
+    emit!(PointsExpiredEvent {
+        loyalty_program: loyalty_program.key(),
+        customer: customer.key(),
+        customer_wallet: customer.wallet,
+        lots_expired,
+        amount_expired,
+        amount_burned,
+        next_expiry_at: customer.next_expiry_at,
+        next_expiry_amount: customer.next_expiry_amount,
+        cranker: ctx.accounts.cranker.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Expired {} lot(s): {} points, {} burned",
+        lots_expired,
+        amount_expired,
+        amount_burned
+    );
+
+    Ok(())
+}
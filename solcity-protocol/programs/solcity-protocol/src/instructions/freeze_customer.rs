@@ -0,0 +1,85 @@
+use crate::{Customer, CustomerFreezeChangedEvent, LoyaltyProgram, SolcityError};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{self, Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct FreezeCustomer<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Customer::SEED_PREFIX,
+            customer.wallet.as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = customer.bump,
+    )]
+    pub customer: Box<Account<'info, Customer>>,
+
+    #[account(
+        seeds = [b"mint", loyalty_program.key().as_ref()],
+        bump,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = customer.wallet,
+        associated_token::token_program = token_program,
+    )]
+    pub customer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+pub fn handler(ctx: Context<FreezeCustomer>, reason: u8) -> Result<()> {
+    require!(!ctx.accounts.customer.is_frozen, SolcityError::CustomerFrozen);
+
+    let loyalty_program = &ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    // The loyalty program PDA is the mint's freeze authority
+    let program_seeds = &[
+        LoyaltyProgram::SEED_PREFIX,
+        loyalty_program.seed_authority.as_ref(),
+        &[loyalty_program.bump],
+    ];
+    let signer_seeds = &[&program_seeds[..]];
+
+    token_2022::freeze_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token_2022::FreezeAccount {
+            account: ctx.accounts.customer_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: loyalty_program.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    let customer = &mut ctx.accounts.customer;
+    customer.is_frozen = true;
+    customer.freeze_reason = reason;
+
+    emit!(CustomerFreezeChangedEvent {
+        customer: customer.key(),
+        customer_wallet: customer.wallet,
+        authority: ctx.accounts.authority.key(),
+        is_frozen: true,
+        reason,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Customer {} frozen (reason {})", customer.wallet, reason);
+
+    Ok(())
+}
@@ -0,0 +1,253 @@
+use crate::{
+    Customer, LoyaltyProgram, PointLots, PointsGiftedEvent, SolcityError, TransactionRecord,
+    TransferMode, CURRENT_ACCOUNT_VERSION, PAUSE_REDEMPTION, SECONDS_PER_DAY, TOKEN_DECIMALS,
+    TRANSACTION_TYPE_GIFT,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{spl_token_2022, Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct GiftPoints<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Customer::SEED_PREFIX,
+            sender.key().as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = sender_customer.bump,
+        constraint = !sender_customer.is_frozen @ SolcityError::CustomerFrozen,
+    )]
+    pub sender_customer: Box<Account<'info, Customer>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Customer::SEED_PREFIX,
+            recipient_customer.wallet.as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = recipient_customer.bump,
+        constraint = recipient_customer.wallet != sender.key() @ SolcityError::CannotGiftSelf,
+        constraint = !recipient_customer.is_frozen @ SolcityError::CustomerFrozen,
+    )]
+    pub recipient_customer: Box<Account<'info, Customer>>,
+
+    #[account(
+        seeds = [b"mint", loyalty_program.key().as_ref()],
+        bump,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = sender,
+        associated_token::token_program = token_program,
+    )]
+    pub sender_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = recipient_customer.wallet,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sender's point lots, consumed oldest-first
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = 8 + PointLots::INIT_SPACE,
+        seeds = [PointLots::SEED_PREFIX, sender_customer.key().as_ref()],
+        bump
+    )]
+    pub sender_point_lots: Box<Account<'info, PointLots>>,
+
+    /// Recipient's point lots, credited with the gifted lots (expiry dates are kept)
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = 8 + PointLots::INIT_SPACE,
+        seeds = [PointLots::SEED_PREFIX, recipient_customer.key().as_ref()],
+        bump
+    )]
+    pub recipient_point_lots: Box<Account<'info, PointLots>>,
+
+    #[account(
+        init,
+        payer = sender,
+        space = TransactionRecord::SPACE,
+        seeds = [
+            TransactionRecord::SEED_PREFIX,
+            sender.key().as_ref(),
+            &sender_customer.transaction_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub sender_transaction_record: Box<Account<'info, TransactionRecord>>,
+
+    #[account(
+        init,
+        payer = sender,
+        space = TransactionRecord::SPACE,
+        seeds = [
+            TransactionRecord::SEED_PREFIX,
+            recipient_customer.wallet.as_ref(),
+            &recipient_customer.transaction_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub recipient_transaction_record: Box<Account<'info, TransactionRecord>>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: transfer hook extra accounts (RegisteredCustomersOnly mode)
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, GiftPoints<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_REDEMPTION),
+        SolcityError::ProgramPaused
+    );
+    require!(
+        ctx.accounts.loyalty_program.transfer_mode != TransferMode::Soulbound,
+        SolcityError::TransfersDisabled
+    );
+    require!(amount > 0, SolcityError::InvalidRewardAmount);
+    require!(
+        ctx.accounts.sender_token_account.amount >= amount,
+        SolcityError::InsufficientBalance
+    );
+
+    let clock = Clock::get()?;
+    let sender_key = ctx.accounts.sender.key();
+    let recipient_wallet = ctx.accounts.recipient_customer.wallet;
+
+    // Per-tier daily limit, reset at each UTC day boundary
+    let sender_customer = &mut ctx.accounts.sender_customer;
+    let today = clock.unix_timestamp / SECONDS_PER_DAY;
+    if sender_customer.gift_day != today {
+        sender_customer.gift_day = today;
+        sender_customer.gifted_today = 0;
+    }
+    let gifted_today = sender_customer
+        .gifted_today
+        .checked_add(amount)
+        .ok_or(SolcityError::Overflow)?;
+    let daily_limit =
+        ctx.accounts.loyalty_program.gift_daily_limits[sender_customer.tier.index() as usize];
+    require!(
+        daily_limit == 0 || gifted_today <= daily_limit,
+        SolcityError::GiftLimitExceeded
+    );
+    sender_customer.gifted_today = gifted_today;
+
+    // Resolves transfer hook accounts from remaining_accounts when the mint has a hook
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.accounts.token_program.key,
+        ctx.accounts.sender_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.recipient_token_account.to_account_info(),
+        ctx.accounts.sender.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        TOKEN_DECIMALS,
+        &[],
+    )?;
+
+    // Move lots with their original expiry so gifting never extends it
+    let sender_point_lots = &mut ctx.accounts.sender_point_lots;
+    if sender_point_lots.customer == Pubkey::default() {
+        sender_point_lots.version = CURRENT_ACCOUNT_VERSION;
+        sender_point_lots.customer = sender_customer.key();
+        sender_point_lots.bump = ctx.bumps.sender_point_lots;
+    }
+    let gifted_lots = sender_point_lots.take(amount);
+    sender_point_lots.sync_next_expiry(sender_customer);
+
+    let recipient_customer = &mut ctx.accounts.recipient_customer;
+    let recipient_point_lots = &mut ctx.accounts.recipient_point_lots;
+    if recipient_point_lots.customer == Pubkey::default() {
+        recipient_point_lots.version = CURRENT_ACCOUNT_VERSION;
+        recipient_point_lots.customer = recipient_customer.key();
+        recipient_point_lots.bump = ctx.bumps.recipient_point_lots;
+    }
+    for lot in gifted_lots {
+        recipient_point_lots.credit(lot)?;
+    }
+    recipient_point_lots.sync_next_expiry(recipient_customer);
+
+    // One history record on each side
+    let sender_record = &mut ctx.accounts.sender_transaction_record;
+    sender_record.version = CURRENT_ACCOUNT_VERSION;
+    sender_record.customer = sender_key;
+    sender_record.merchant = Pubkey::default();
+    sender_record.transaction_type = TRANSACTION_TYPE_GIFT;
+    sender_record.amount = amount;
+    sender_record.tier = sender_customer.tier.index();
+    sender_record.timestamp = clock.unix_timestamp;
+    sender_record.index = sender_customer.transaction_count;
+    sender_record.bump = ctx.bumps.sender_transaction_record;
+    sender_record.outgoing = true;
+    sender_record.counterparty = recipient_wallet;
+
+    let recipient_record = &mut ctx.accounts.recipient_transaction_record;
+    recipient_record.version = CURRENT_ACCOUNT_VERSION;
+    recipient_record.customer = recipient_wallet;
+    recipient_record.merchant = Pubkey::default();
+    recipient_record.transaction_type = TRANSACTION_TYPE_GIFT;
+    recipient_record.amount = amount;
+    recipient_record.tier = recipient_customer.tier.index();
+    recipient_record.timestamp = clock.unix_timestamp;
+    recipient_record.index = recipient_customer.transaction_count;
+    recipient_record.bump = ctx.bumps.recipient_transaction_record;
+    recipient_record.outgoing = false;
+    recipient_record.counterparty = sender_key;
+
+    sender_customer.transaction_count = sender_customer
+        .transaction_count
+        .checked_add(1)
+        .ok_or(SolcityError::Overflow)?;
+
+    sender_customer.last_activity = clock.unix_timestamp;
+
+    recipient_customer.transaction_count = recipient_customer
+        .transaction_count
+        .checked_add(1)
+        .ok_or(SolcityError::Overflow)?;
+
+    emit!(PointsGiftedEvent {
+        loyalty_program: ctx.accounts.loyalty_program.key(),
+        sender: sender_key,
+        recipient: recipient_wallet,
+        amount,
+        sender_tier: sender_customer.tier.clone(),
+        gifted_today,
+        daily_limit,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Gifted {} points from {} to {}",
+        amount,
+        sender_key,
+        recipient_wallet
+    );
+
+    Ok(())
+}
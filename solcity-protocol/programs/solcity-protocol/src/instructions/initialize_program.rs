@@ -1,7 +1,15 @@
-use crate::{LoyaltyProgram, SolcityError, DEFAULT_INTEREST_RATE};
+use crate::mint::{create_loyalty_mint, LoyaltyMintAccounts};
+use crate::{
+    LoyaltyProgram, SolcityError, TierConfig, TransferMode, CURRENT_ACCOUNT_VERSION,
+    DEFAULT_INTEREST_RATE, DEFAULT_ISSUANCE_FEE_PER_TOKEN, DEFAULT_MERCHANT_REGISTRATION_FEE,
+    DEFAULT_MAX_REFERRALS_PER_REFERRER, DEFAULT_POINTS_EXPIRY_PERIOD, DEFAULT_REFEREE_BONUS,
+    DEFAULT_REFERRER_BONUS, DEFAULT_STREAK_MILESTONES, MAX_INTEREST_RATE, MAX_TIERS,
+    MAX_TOKEN_SYMBOL_LENGTH, MAX_TOKEN_URI_LENGTH,
+};
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::token_2022::Token2022;
-use anchor_spl::token_interface::Mint;
+use solcity_transfer_hook::program::SolcityTransferHook;
 
 #[derive(Accounts)]
 #[instruction(name: String)]
@@ -16,20 +24,45 @@ pub struct InitializeProgram<'info> {
         seeds = [LoyaltyProgram::SEED_PREFIX, authority.key().as_ref()],
         bump
     )]
-    pub loyalty_program: Account<'info, LoyaltyProgram>,
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
 
-    /// Token-2022 mint (will be initialized with extensions)
+    /// CHECK: Token-2022 mint PDA, created and initialized with extensions in the handler
+    #[account(
+        mut,
+        seeds = [b"mint", loyalty_program.key().as_ref()],
+        bump
+    )]
+    pub mint: UncheckedAccount<'info>,
+
+    /// Tier schedule seeded with the default tiers
     #[account(
         init,
         payer = authority,
-        mint::decimals = 6,
-        mint::authority = loyalty_program,
-        mint::freeze_authority = loyalty_program,
-        mint::token_program = token_program,
-        seeds = [b"mint", loyalty_program.key().as_ref()],
+        space = 8 + TierConfig::INIT_SPACE,
+        seeds = [TierConfig::SEED_PREFIX, loyalty_program.key().as_ref()],
         bump
     )]
-    pub mint: InterfaceAccount<'info, Mint>,
+    pub tier_config: Box<Account<'info, TierConfig>>,
+
+    /// Treasury PDA that collects platform fees (funded to rent exemption here)
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::TREASURY_SEED_PREFIX, loyalty_program.key().as_ref()],
+        bump
+    )]
+    pub platform_treasury: SystemAccount<'info>,
+
+    /// CHECK: Hook extra-account-metas PDA, created by the hook program
+    /// (required for RegisteredCustomersOnly mode)
+    #[account(mut)]
+    pub extra_account_meta_list: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Hook transfer stats PDA, created by the hook program
+    /// (required for RegisteredCustomersOnly mode)
+    #[account(mut)]
+    pub transfer_stats: Option<UncheckedAccount<'info>>,
+
+    pub transfer_hook_program: Option<Program<'info, SolcityTransferHook>>,
 
     pub token_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
@@ -39,22 +72,101 @@ pub struct InitializeProgram<'info> {
 pub fn handler(
     ctx: Context<InitializeProgram>,
     name: String,
+    symbol: String,
+    uri: String,
     interest_rate: Option<i16>,
+    transfer_mode: Option<TransferMode>,
 ) -> Result<()> {
     require!(!name.is_empty(), SolcityError::NameEmpty);
     require!(name.len() <= 32, SolcityError::NameTooLong);
+    require!(
+        !symbol.is_empty()
+            && symbol.len() <= MAX_TOKEN_SYMBOL_LENGTH
+            && uri.len() <= MAX_TOKEN_URI_LENGTH,
+        SolcityError::InvalidTokenMetadata
+    );
 
     let rate = interest_rate.unwrap_or(DEFAULT_INTEREST_RATE);
     require!(
-        rate >= 0 && rate <= 10000,
+        (0..=MAX_INTEREST_RATE).contains(&rate),
         SolcityError::InvalidInterestRate
     );
 
+    let transfer_mode = transfer_mode.unwrap_or(TransferMode::Transferable);
+
+    // Fund treasury to rent exemption so small fee transfers never fail
+    let treasury_rent = ctx.accounts.rent.minimum_balance(0);
+    let treasury_shortfall = treasury_rent.saturating_sub(ctx.accounts.platform_treasury.lamports());
+    if treasury_shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.platform_treasury.to_account_info(),
+                },
+            ),
+            treasury_shortfall,
+        )?;
+    }
+
+    let authority_key = ctx.accounts.authority.key();
+    let program_seeds = &[
+        LoyaltyProgram::SEED_PREFIX,
+        authority_key.as_ref(),
+        &[ctx.bumps.loyalty_program],
+    ];
+
+    create_loyalty_mint(
+        LoyaltyMintAccounts {
+            payer: &ctx.accounts.authority.to_account_info(),
+            mint: &ctx.accounts.mint.to_account_info(),
+            loyalty_program: &ctx.accounts.loyalty_program.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+        },
+        ctx.bumps.mint,
+        program_seeds,
+        rate,
+        &transfer_mode,
+        name.clone(),
+        symbol,
+        uri,
+    )?;
+
+    // The hook program owns its validation account, but only the mint authority may create it
+    if transfer_mode == TransferMode::RegisteredCustomersOnly {
+        let (Some(extra_account_meta_list), Some(transfer_stats), Some(transfer_hook_program)) = (
+            ctx.accounts.extra_account_meta_list.as_ref(),
+            ctx.accounts.transfer_stats.as_ref(),
+            ctx.accounts.transfer_hook_program.as_ref(),
+        ) else {
+            return err!(SolcityError::MissingTransferHookAccounts);
+        };
+
+        solcity_transfer_hook::cpi::initialize_extra_account_meta_list(CpiContext::new_with_signer(
+            transfer_hook_program.to_account_info(),
+            solcity_transfer_hook::cpi::accounts::InitializeExtraAccountMetaList {
+                payer: ctx.accounts.authority.to_account_info(),
+                authority: ctx.accounts.loyalty_program.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                extra_account_meta_list: extra_account_meta_list.to_account_info(),
+                transfer_stats: transfer_stats.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            &[&program_seeds[..]],
+        ))?;
+    }
+
     let loyalty_program = &mut ctx.accounts.loyalty_program;
     let clock = Clock::get()?;
 
+    loyalty_program.version = CURRENT_ACCOUNT_VERSION;
     loyalty_program.authority = ctx.accounts.authority.key();
-    loyalty_program.treasury = ctx.accounts.authority.key(); // Treasury is authority for now
+    loyalty_program.seed_authority = ctx.accounts.authority.key();
+    loyalty_program.pending_authority = None;
+    loyalty_program.treasury = ctx.accounts.platform_treasury.key();
+    loyalty_program.treasury_bump = ctx.bumps.platform_treasury;
     loyalty_program.mint = ctx.accounts.mint.key();
     loyalty_program.name = name.clone();
     loyalty_program.total_merchants = 0;
@@ -62,14 +174,41 @@ pub fn handler(
     loyalty_program.total_tokens_issued = 0;
     loyalty_program.total_tokens_redeemed = 0;
     loyalty_program.total_fees_collected = 0;
+    loyalty_program.total_fees_withdrawn = 0;
+    loyalty_program.pending_registration_fees = 0;
+    loyalty_program.fee_mint = None;
     loyalty_program.interest_rate = rate;
+    loyalty_program.merchant_registration_fee = DEFAULT_MERCHANT_REGISTRATION_FEE;
+    loyalty_program.issuance_fee_per_token = DEFAULT_ISSUANCE_FEE_PER_TOKEN;
+    loyalty_program.volume_fee_tiers = Vec::new();
+    loyalty_program.fee_recipients = Vec::new();
+    loyalty_program.paused = 0;
+    loyalty_program.transfer_mode = transfer_mode.clone();
     loyalty_program.bump = ctx.bumps.loyalty_program;
     loyalty_program.created_at = clock.unix_timestamp;
+    loyalty_program.points_expiry_period = DEFAULT_POINTS_EXPIRY_PERIOD;
+    loyalty_program.total_tokens_expired = 0;
+    loyalty_program.gift_daily_limits = [0; MAX_TIERS];
+    loyalty_program.point_value = 0;
+    loyalty_program.collateral_mint = None;
+    loyalty_program.total_collateral = 0;
+    loyalty_program.streak_milestones = DEFAULT_STREAK_MILESTONES;
+    loyalty_program.referrer_bonus = DEFAULT_REFERRER_BONUS;
+    loyalty_program.referee_bonus = DEFAULT_REFEREE_BONUS;
+    loyalty_program.referral_min_purchase = 0;
+    loyalty_program.max_referrals_per_referrer = DEFAULT_MAX_REFERRALS_PER_REFERRER;
+
+    let tier_config = &mut ctx.accounts.tier_config;
+    tier_config.version = CURRENT_ACCOUNT_VERSION;
+    tier_config.loyalty_program = loyalty_program.key();
+    tier_config.tiers = TierConfig::default_tiers();
+    tier_config.bump = ctx.bumps.tier_config;
 
     msg!(
-        "Loyalty Program '{}' initialized with {}% APY",
+        "Loyalty Program '{}' initialized with {}% APY ({:?} points)",
         name,
-        rate as f64 / 100.0
+        rate as f64 / 100.0,
+        transfer_mode
     );
 
     Ok(())
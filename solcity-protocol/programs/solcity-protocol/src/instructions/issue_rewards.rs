@@ -1,14 +1,18 @@
 use crate::{
-    Customer, LoyaltyProgram, Merchant, MerchantCustomerRecord, RewardRule, RewardsIssuedEvent, 
-    TierUpgradeEvent, TransactionRecord, SolcityError, ISSUANCE_FEE_PER_TOKEN, PERCENTAGE_DIVISOR,
+    Customer, FeeDistributedEvent, IssuanceCapAlertEvent, LoyaltyProgram, Merchant,
+    MerchantCustomerRecord, PointLot, PointLots, Referral, ReferralRewardedEvent,
+    RewardsIssuedEvent, RuleContext, StreakMilestoneEvent, TierConfig, TierUpgradeEvent,
+    TransactionRecord, SolcityError,
+    CURRENT_ACCOUNT_VERSION, ISSUANCE_ALERT_THRESHOLDS, PAUSE_ISSUANCE, PERCENTAGE_DIVISOR,
 };
+use crate::fees::{collect_fee, FeeTokenAccounts};
+use crate::rules::{combine_rules, load_rules};
 use anchor_lang::prelude::*;
-use anchor_lang::system_program;
 use anchor_spl::token_2022::{self, Token2022};
-use anchor_spl::token_interface::{Mint, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
-#[instruction(purchase_amount: u64, rule_id: Option<u64>)]
+#[instruction(purchase_amount: u64)]
 pub struct IssueRewards<'info> {
     #[account(mut)]
     pub merchant_authority: Signer<'info>,
@@ -21,7 +25,7 @@ pub struct IssueRewards<'info> {
             loyalty_program.key().as_ref()
         ],
         bump = merchant.bump,
-        constraint = merchant.is_active @ SolcityError::MerchantNotActive,
+        constraint = merchant.is_approved() @ SolcityError::MerchantNotActive,
     )]
     pub merchant: Account<'info, Merchant>,
 
@@ -33,15 +37,22 @@ pub struct IssueRewards<'info> {
             loyalty_program.key().as_ref()
         ],
         bump = customer.bump,
+        constraint = !customer.is_frozen @ SolcityError::CustomerFrozen,
     )]
     pub customer: Account<'info, Customer>,
 
     #[account(
         mut,
-        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.authority.as_ref()],
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
         bump = loyalty_program.bump,
     )]
-    pub loyalty_program: Account<'info, LoyaltyProgram>,
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        seeds = [TierConfig::SEED_PREFIX, loyalty_program.key().as_ref()],
+        bump = tier_config.bump,
+    )]
+    pub tier_config: Box<Account<'info, TierConfig>>,
 
     #[account(
         mut,
@@ -85,28 +96,97 @@ pub struct IssueRewards<'info> {
     )]
     pub merchant_customer_record: Account<'info, MerchantCustomerRecord>,
 
-    /// Optional reward rule to apply
+    /// Customer's point lots, credited with this issuance
+    #[account(
+        init_if_needed,
+        payer = merchant_authority,
+        space = 8 + PointLots::INIT_SPACE,
+        seeds = [PointLots::SEED_PREFIX, customer.key().as_ref()],
+        bump
+    )]
+    pub point_lots: Box<Account<'info, PointLots>>,
+
+    /// Customer's referral record (required on a referred customer's first qualifying purchase)
+    #[account(
+        mut,
+        seeds = [Referral::SEED_PREFIX, customer.key().as_ref()],
+        bump = referral.bump,
+    )]
+    pub referral: Option<Box<Account<'info, Referral>>>,
+
+    /// Referrer's customer account (required with `referral`)
+    #[account(
+        mut,
+        constraint = referrer_customer.key() == customer.referrer @ SolcityError::MissingReferralAccounts,
+    )]
+    pub referrer_customer: Option<Box<Account<'info, Customer>>>,
+
+    /// Referrer's token account receiving the referrer bonus (required with `referral`)
+    #[account(
+        mut,
+        constraint = referrer_token_account.mint == mint.key() @ SolcityError::InvalidMint,
+    )]
+    pub referrer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Referrer's point lots, credited with the referrer bonus (required with `referral`)
+    #[account(
+        init_if_needed,
+        payer = merchant_authority,
+        space = 8 + PointLots::INIT_SPACE,
+        seeds = [PointLots::SEED_PREFIX, customer.referrer.as_ref()],
+        bump
+    )]
+    pub referrer_point_lots: Option<Box<Account<'info, PointLots>>>,
+
+    /// Optional reward rule to apply (System program = none)
     /// CHECK: Optional account, validated in handler if provided
     pub reward_rule: AccountInfo<'info>,
 
-    /// CHECK: Platform treasury account to receive fees
+    /// Platform treasury PDA to receive fees
     #[account(
         mut,
-        constraint = platform_treasury.key() == loyalty_program.treasury @ SolcityError::InvalidTreasury
+        seeds = [LoyaltyProgram::TREASURY_SEED_PREFIX, loyalty_program.key().as_ref()],
+        bump = loyalty_program.treasury_bump,
     )]
-    pub platform_treasury: AccountInfo<'info>,
+    pub platform_treasury: SystemAccount<'info>,
+
+    /// Fee mint (required when fees are paid in an SPL token)
+    pub fee_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Merchant token account paying fees (required when fees are paid in an SPL token)
+    #[account(mut)]
+    pub merchant_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Treasury PDA token account receiving fees (required when fees are paid in an SPL token)
+    #[account(mut)]
+    pub treasury_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub fee_token_program: Option<Interface<'info, TokenInterface>>,
 
     pub token_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
+    // remaining_accounts: additional RewardRule accounts, combined per Merchant.rule_combination
 }
 
-pub fn handler(
-    ctx: Context<IssueRewards>,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, IssueRewards<'info>>,
     purchase_amount: u64, // Amount in cents (e.g., 1000 = $10.00)
-    _rule_id: Option<u64>,
 ) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_ISSUANCE),
+        SolcityError::ProgramPaused
+    );
+
     require!(purchase_amount > 0, SolcityError::InvalidRewardAmount);
 
+    let fee_tokens = FeeTokenAccounts::resolve(
+        &ctx.accounts.loyalty_program,
+        ctx.accounts.fee_mint.as_deref(),
+        ctx.accounts.merchant_fee_account.as_deref(),
+        ctx.accounts.treasury_fee_account.as_deref(),
+        ctx.accounts.fee_token_program.as_ref(),
+    )?;
+
     // Get keys before mutable borrows
     let customer_key = ctx.accounts.customer.key();
     let merchant_key = ctx.accounts.merchant.key();
@@ -129,110 +209,119 @@ pub fn handler(
         .ok_or(SolcityError::Overflow)?;
 
     // Apply tier multiplier
-    let tier_multiplier = customer.get_tier_multiplier();
+    let tier_multiplier = ctx.accounts.tier_config.get_tier_multiplier(&customer.tier);
     let mut final_reward = base_reward
         .checked_mul(tier_multiplier)
         .ok_or(SolcityError::Overflow)?
         .checked_div(PERCENTAGE_DIVISOR)
         .ok_or(SolcityError::Overflow)?;
 
-    // Apply reward rule if provided
-    let mut rule_applied = false;
-    let mut rule_multiplier = 100u64; // Default 1.0x
-    let mut rule_name: Option<String> = None;
-    
-    msg!("Checking reward_rule account: {}", ctx.accounts.reward_rule.key);
-    msg!("System program ID: {}", System::id());
-    
-    // Check if reward_rule account is provided (not the default system program)
-    if *ctx.accounts.reward_rule.key != System::id() {
-        msg!("Reward rule account provided, attempting to deserialize...");
-        
-        // Try to deserialize the reward rule account
-        let rule_data = ctx.accounts.reward_rule.try_borrow_data()?;
-        msg!("Rule data length: {}", rule_data.len());
-        
-        if rule_data.len() > 8 { // Check if account has data beyond discriminator
-            // Manually deserialize using AnchorDeserialize
-            let mut data_slice: &[u8] = &rule_data[8..]; // Skip 8-byte discriminator
-            match RewardRule::deserialize(&mut data_slice) {
-                Ok(rule) => {
-                    msg!("Successfully deserialized rule: {}", rule.name);
-                    msg!("Rule multiplier: {}", rule.multiplier);
-                    msg!("Rule is_active: {}", rule.is_active);
-                    msg!("Rule min_purchase: {}", rule.min_purchase);
-                    msg!("Current time: {}", clock.unix_timestamp);
-                    msg!("Rule start_time: {}", rule.start_time);
-                    msg!("Rule end_time: {}", rule.end_time);
-                    
-                    // Check if rule is active and valid
-                    if rule.is_currently_active(clock.unix_timestamp) {
-                        msg!("Rule is currently active");
-                        
-                        // Check minimum purchase requirement
-                        if purchase_amount >= rule.min_purchase {
-                            msg!("Purchase amount {} meets minimum {}", purchase_amount, rule.min_purchase);
-                            
-                            final_reward = final_reward
-                                .checked_mul(rule.multiplier)
-                                .ok_or(SolcityError::Overflow)?
-                                .checked_div(PERCENTAGE_DIVISOR)
-                                .ok_or(SolcityError::Overflow)?;
-                            
-                            rule_applied = true;
-                            rule_multiplier = rule.multiplier;
-                            rule_name = Some(rule.name.clone());
-                            msg!("Applied rule '{}' with {}x multiplier", rule.name, rule.multiplier as f64 / 100.0);
-                        } else {
-                            msg!("Rule not applied: purchase amount ${} below minimum ${}", 
-                                purchase_amount as f64 / 100.0, 
-                                rule.min_purchase as f64 / 100.0
-                            );
-                        }
-                    } else {
-                        msg!("Rule not applied: rule is not currently active");
-                    }
-                },
-                Err(e) => {
-                    msg!("Failed to deserialize rule: {:?}", e);
-                }
+    // Advance the daily streak
+    if customer.update_streak(clock.unix_timestamp)
+        && loyalty_program.is_streak_milestone(customer.streak_days)
+    {
+        emit!(StreakMilestoneEvent {
+            loyalty_program: loyalty_program.key(),
+            customer: customer.key(),
+            customer_wallet: customer.wallet,
+            streak_days: customer.streak_days,
+            longest_streak: customer.longest_streak,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    // Apply the reward rules passed in reward_rule and remaining_accounts
+    let supplied_rules = std::iter::once(&ctx.accounts.reward_rule)
+        .filter(|account| *account.key != System::id())
+        .chain(ctx.remaining_accounts.iter());
+    let mut rules = load_rules(merchant_key, supplied_rules)?;
+    let rule_combination = merchant.rule_combination;
+    let rule_context = RuleContext {
+        current_time: clock.unix_timestamp,
+        purchase_amount,
+        first_purchase: ctx.accounts.merchant_customer_record.merchant == Pubkey::default(),
+        streak_days: customer.streak_days,
+        tier_index: customer.tier.index(),
+        referred: customer.is_referred(),
+    };
+    let rule_outcome = combine_rules(rule_combination, &mut rules, final_reward, |rule| {
+        rule.applies(&rule_context)
+    })?;
+    let rule_multiplier = rule_outcome.multiplier;
+    let rule_bonus = rule_outcome.bonus;
+    let rules_evaluated = rule_outcome.evaluations;
+    let rule_applied = rules_evaluated.iter().any(|evaluation| evaluation.applied);
+    let rule_name = rules_evaluated
+        .iter()
+        .zip(rules.iter())
+        .find(|(evaluation, _)| evaluation.applied)
+        .map(|(_, loaded)| loaded.rule.name.clone());
+
+    final_reward = final_reward
+        .checked_add(rule_bonus)
+        .ok_or(SolcityError::Overflow)?;
+
+    require!(final_reward > 0, SolcityError::InvalidRewardAmount);
+
+    // Enforce the merchant's issuance caps and alert when usage reaches 80% / 100%
+    for usage in merchant.record_issuance(final_reward, clock.unix_timestamp)? {
+        for threshold_percent in ISSUANCE_ALERT_THRESHOLDS {
+            if usage.crossed(threshold_percent as u64) {
+                emit!(IssuanceCapAlertEvent {
+                    loyalty_program: loyalty_program.key(),
+                    merchant: merchant_key,
+                    kind: usage.kind,
+                    threshold_percent,
+                    issued: usage.after,
+                    cap: usage.cap,
+                    timestamp: clock.unix_timestamp,
+                });
             }
-        } else {
-            msg!("Rule data too short: {} bytes", rule_data.len());
         }
-    } else {
-        msg!("No reward rule provided (System program)");
     }
 
-    require!(final_reward > 0, SolcityError::InvalidRewardAmount);
+    // When points carry a value, every issued point must be backed by free escrowed collateral
+    let point_value = loyalty_program.point_value;
+    if point_value > 0 {
+        let required = final_reward
+            .checked_mul(point_value)
+            .ok_or(SolcityError::Overflow)?;
+        require!(
+            merchant.free_collateral(point_value) >= required,
+            SolcityError::InsufficientCollateral
+        );
+    }
 
-    // Calculate and collect platform fee
+    // Calculate and collect platform fee at the merchant's effective rate
+    let (fee_per_token, fee_rule) = loyalty_program.effective_issuance_fee(merchant);
     let platform_fee = final_reward
-        .checked_mul(ISSUANCE_FEE_PER_TOKEN)
+        .checked_mul(fee_per_token)
         .ok_or(SolcityError::Overflow)?;
 
     if platform_fee > 0 {
-        system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.merchant_authority.to_account_info(),
-                    to: ctx.accounts.platform_treasury.to_account_info(),
-                },
-            ),
+        collect_fee(
             platform_fee,
+            &ctx.accounts.merchant_authority,
+            &ctx.accounts.platform_treasury,
+            &ctx.accounts.system_program,
+            fee_tokens.as_ref(),
         )?;
 
-        loyalty_program.total_fees_collected = loyalty_program
-            .total_fees_collected
-            .checked_add(platform_fee)
-            .ok_or(SolcityError::Overflow)?;
+        for (recipient, amount) in loyalty_program.record_fee(platform_fee)? {
+            emit!(FeeDistributedEvent {
+                loyalty_program: loyalty_program.key(),
+                recipient,
+                amount,
+                total_fee: platform_fee,
+                timestamp: clock.unix_timestamp,
+            });
+        }
     }
 
     // Mint tokens to customer using PDA authority
     let program_seeds = &[
         LoyaltyProgram::SEED_PREFIX,
-        loyalty_program.authority.as_ref(),
+        loyalty_program.seed_authority.as_ref(),
         &[loyalty_program.bump],
     ];
     let signer_seeds = &[&program_seeds[..]];
@@ -256,6 +345,11 @@ pub fn handler(
         .checked_add(final_reward)
         .ok_or(SolcityError::Overflow)?;
 
+    merchant.outstanding_points = merchant
+        .outstanding_points
+        .checked_add(final_reward)
+        .ok_or(SolcityError::Overflow)?;
+
     customer.total_earned = customer
         .total_earned
         .checked_add(final_reward)
@@ -268,17 +362,140 @@ pub fn handler(
 
     customer.last_activity = clock.unix_timestamp;
 
+    // Track the credit as a lot so it can expire
+    let point_lots = &mut ctx.accounts.point_lots;
+    if point_lots.customer == Pubkey::default() {
+        point_lots.version = CURRENT_ACCOUNT_VERSION;
+        point_lots.customer = customer_key;
+        point_lots.bump = ctx.bumps.point_lots;
+    }
+    point_lots.credit(PointLot {
+        amount: final_reward,
+        expires_at: PointLots::expiry_for(clock.unix_timestamp, loyalty_program.points_expiry_period),
+        merchant: merchant_key,
+    })?;
+    point_lots.sync_next_expiry(customer);
+
+    // Pay both referral bonuses on the referee's first qualifying purchase.
+    // Bonuses are protocol-funded: no issuer liability, caps or collateral apply.
+    if customer.referral_pending && purchase_amount >= loyalty_program.referral_min_purchase {
+        let (Some(referral), Some(referrer), Some(referrer_token_account), Some(referrer_point_lots)) = (
+            ctx.accounts.referral.as_mut(),
+            ctx.accounts.referrer_customer.as_mut(),
+            ctx.accounts.referrer_token_account.as_ref(),
+            ctx.accounts.referrer_point_lots.as_mut(),
+        ) else {
+            return err!(SolcityError::MissingReferralAccounts);
+        };
+        require_keys_eq!(
+            referrer_token_account.owner,
+            referrer.wallet,
+            SolcityError::UnauthorizedAccess
+        );
+
+        let bonus_expiry =
+            PointLots::expiry_for(clock.unix_timestamp, loyalty_program.points_expiry_period);
+        let referee_bonus = loyalty_program.referee_bonus;
+        // Frozen referrers forfeit their bonus
+        let referrer_bonus = if referrer.is_frozen { 0 } else { loyalty_program.referrer_bonus };
+
+        if referee_bonus > 0 {
+            token_2022::mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_2022::MintTo {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: ctx.accounts.customer_token_account.to_account_info(),
+                        authority: loyalty_program.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                referee_bonus,
+            )?;
+            customer.total_earned = customer
+                .total_earned
+                .checked_add(referee_bonus)
+                .ok_or(SolcityError::Overflow)?;
+            point_lots.credit(PointLot {
+                amount: referee_bonus,
+                expires_at: bonus_expiry,
+                merchant: Pubkey::default(),
+            })?;
+            point_lots.sync_next_expiry(customer);
+        }
+
+        if referrer_bonus > 0 {
+            token_2022::mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_2022::MintTo {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: referrer_token_account.to_account_info(),
+                        authority: loyalty_program.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                referrer_bonus,
+            )?;
+            referrer.total_earned = referrer
+                .total_earned
+                .checked_add(referrer_bonus)
+                .ok_or(SolcityError::Overflow)?;
+            referrer.referral_earnings = referrer
+                .referral_earnings
+                .checked_add(referrer_bonus)
+                .ok_or(SolcityError::Overflow)?;
+            if referrer_point_lots.customer == Pubkey::default() {
+                referrer_point_lots.version = CURRENT_ACCOUNT_VERSION;
+                referrer_point_lots.customer = referrer.key();
+                referrer_point_lots.bump = ctx
+                    .bumps
+                    .referrer_point_lots
+                    .ok_or(SolcityError::MissingReferralAccounts)?;
+            }
+            referrer_point_lots.credit(PointLot {
+                amount: referrer_bonus,
+                expires_at: bonus_expiry,
+                merchant: Pubkey::default(),
+            })?;
+            referrer_point_lots.sync_next_expiry(referrer);
+        }
+
+        loyalty_program.total_tokens_issued = loyalty_program
+            .total_tokens_issued
+            .checked_add(referee_bonus)
+            .and_then(|total| total.checked_add(referrer_bonus))
+            .ok_or(SolcityError::Overflow)?;
+
+        referral.referrer_bonus = referrer_bonus;
+        referral.referee_bonus = referee_bonus;
+        referral.rewarded_at = clock.unix_timestamp;
+        customer.referral_pending = false;
+
+        emit!(ReferralRewardedEvent {
+            loyalty_program: loyalty_program.key(),
+            referral: referral.key(),
+            referrer: referrer.key(),
+            referee: customer_key,
+            merchant: merchant_key,
+            purchase_amount,
+            referrer_bonus,
+            referee_bonus,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
     loyalty_program.total_tokens_issued = loyalty_program
         .total_tokens_issued
         .checked_add(final_reward)
         .ok_or(SolcityError::Overflow)?;
 
-    // Check for tier upgrade
+    // Check for tier upgrade (tiers never drop when the schedule is reconfigured)
     let old_tier = customer.tier.clone();
-    let new_tier = Customer::calculate_tier(customer.total_earned);
+    let new_tier = ctx.accounts.tier_config.calculate_tier(customer.total_earned);
     let customer_wallet = customer.wallet;
     
-    if new_tier != customer.tier {
+    if new_tier.index() > customer.tier.index() {
         customer.tier = new_tier.clone();
         msg!("Customer upgraded from {:?} to {:?}", old_tier, new_tier);
         
@@ -295,16 +512,12 @@ pub fn handler(
 
     // Store transaction record
     let transaction_record = &mut ctx.accounts.transaction_record;
+    transaction_record.version = CURRENT_ACCOUNT_VERSION;
     transaction_record.customer = customer_wallet;
     transaction_record.merchant = merchant_key;
     transaction_record.transaction_type = 0; // 0 = Earned
     transaction_record.amount = final_reward;
-    transaction_record.tier = match customer.tier {
-        crate::state::CustomerTier::Bronze => 0,
-        crate::state::CustomerTier::Silver => 1,
-        crate::state::CustomerTier::Gold => 2,
-        crate::state::CustomerTier::Platinum => 3,
-    };
+    transaction_record.tier = customer.tier.index();
     transaction_record.timestamp = clock.unix_timestamp;
     transaction_record.index = customer.transaction_count - 1; // Already incremented above
     transaction_record.bump = ctx.bumps.transaction_record;
@@ -313,6 +526,7 @@ pub fn handler(
     let merchant_customer_record = &mut ctx.accounts.merchant_customer_record;
     if merchant_customer_record.merchant == Pubkey::default() {
         // First time initialization
+        merchant_customer_record.version = CURRENT_ACCOUNT_VERSION;
         merchant_customer_record.merchant = merchant_key;
         merchant_customer_record.customer = customer_wallet;
         merchant_customer_record.total_issued = final_reward;
@@ -344,23 +558,30 @@ pub fn handler(
         base_reward,
         tier_multiplier,
         rule_multiplier,
+        rule_bonus,
         rule_applied,
         rule_name,
+        rule_combination,
+        rules_evaluated,
         final_reward,
         customer_tier: customer.tier.clone(),
         platform_fee,
+        fee_per_token,
+        fee_rule: fee_rule.clone(),
         timestamp: clock.unix_timestamp,
     });
 
     msg!(
-        "Issued {} tokens (purchase: ${}, tier: {:?}, tier_mult: {}x, rule_mult: {}x, rule_applied: {}, fee: {} lamports)",
+        "Issued {} tokens (purchase: ${}, tier: {:?}, tier_mult: {}x, rule_mult: {}x, rule_bonus: {}, rule_applied: {}, fee: {}, fee_rule: {:?})",
         final_reward,
         purchase_amount as f64 / 100.0,
         customer.tier,
         tier_multiplier as f64 / 100.0,
         rule_multiplier as f64 / 100.0,
+        rule_bonus,
         rule_applied,
-        platform_fee
+        platform_fee,
+        fee_rule
     );
 
     Ok(())
@@ -0,0 +1,183 @@
+use crate::pda::create_pda_account;
+use crate::{
+    AccountMigratedEvent, Customer, LoyaltyProgram, Merchant, MerchantCustomerRecord,
+    OfferRedemptionRecord, RedemptionOffer, RedemptionVoucher, RewardRule, SolcityError,
+    TierConfig, TransactionRecord, Versioned, CURRENT_ACCOUNT_VERSION,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// Pays for any extra rent (anyone can migrate)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Any Solcity state account; the type is resolved from its discriminator
+    #[account(mut, owner = crate::ID @ SolcityError::InvalidAccount)]
+    pub account: UncheckedAccount<'info>,
+
+    /// CHECK: TierConfig PDA created with the default tiers (required for a LoyaltyProgram)
+    #[account(
+        mut,
+        seeds = [TierConfig::SEED_PREFIX, account.key().as_ref()],
+        bump
+    )]
+    pub tier_config: Option<UncheckedAccount<'info>>,
+
+    /// Treasury PDA replacing the wallet treasury (required for a LoyaltyProgram)
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::TREASURY_SEED_PREFIX, account.key().as_ref()],
+        bump
+    )]
+    pub platform_treasury: Option<SystemAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateAccount>) -> Result<()> {
+    let discriminator: [u8; 8] = {
+        let data = ctx.accounts.account.try_borrow_data()?;
+        require!(data.len() >= 8, SolcityError::InvalidAccount);
+        data[..8].try_into().unwrap()
+    };
+
+    let account_type = match &discriminator[..] {
+        d if d == LoyaltyProgram::DISCRIMINATOR => migrate_loyalty_program(&ctx)?,
+        d if d == Merchant::DISCRIMINATOR => migrate::<Merchant>(&ctx)?,
+        d if d == Customer::DISCRIMINATOR => migrate::<Customer>(&ctx)?,
+        d if d == RewardRule::DISCRIMINATOR => migrate::<RewardRule>(&ctx)?,
+        d if d == RedemptionOffer::DISCRIMINATOR => migrate::<RedemptionOffer>(&ctx)?,
+        d if d == RedemptionVoucher::DISCRIMINATOR => migrate::<RedemptionVoucher>(&ctx)?,
+        d if d == TransactionRecord::DISCRIMINATOR => migrate::<TransactionRecord>(&ctx)?,
+        d if d == MerchantCustomerRecord::DISCRIMINATOR => migrate::<MerchantCustomerRecord>(&ctx)?,
+        d if d == OfferRedemptionRecord::DISCRIMINATOR => migrate::<OfferRedemptionRecord>(&ctx)?,
+        // Every other account type was introduced with a version byte
+        _ => return err!(SolcityError::InvalidAccount),
+    };
+
+    let clock = Clock::get()?;
+
+    emit!(AccountMigratedEvent {
+        account: ctx.accounts.account.key(),
+        account_type: account_type.to_string(),
+        from_version: 0,
+        to_version: CURRENT_ACCOUNT_VERSION,
+        payer: ctx.accounts.payer.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "{} {} migrated from the original layout to version {}",
+        account_type,
+        ctx.accounts.account.key(),
+        CURRENT_ACCOUNT_VERSION
+    );
+
+    Ok(())
+}
+
+/// Rebuild an original-layout account in place and return its type name.
+/// Original accounts have no version byte and are identified by their allocation size.
+fn migrate<T: Versioned>(ctx: &Context<MigrateAccount>) -> Result<&'static str> {
+    let account = ctx.accounts.account.to_account_info();
+    let data_len = account.data_len();
+    require!(
+        data_len != T::CURRENT_SPACE,
+        SolcityError::AccountAlreadyMigrated
+    );
+    require!(data_len == T::BASELINE_SPACE, SolcityError::InvalidAccount);
+
+    let state = T::from_baseline(account.key, &account.try_borrow_data()?[8..])?;
+
+    resize(ctx, T::CURRENT_SPACE)?;
+    let mut data = account.try_borrow_mut_data()?;
+    state.try_serialize(&mut &mut data[..])?;
+
+    Ok(T::NAME)
+}
+
+/// Upgrade an original LoyaltyProgram. Fees now collect in the treasury PDA (funded to rent
+/// exemption) and the fixed tier schedule moves to a TierConfig account.
+fn migrate_loyalty_program(ctx: &Context<MigrateAccount>) -> Result<&'static str> {
+    let (Some(tier_config), Some(platform_treasury)) = (
+        ctx.accounts.tier_config.as_ref(),
+        ctx.accounts.platform_treasury.as_ref(),
+    ) else {
+        return err!(SolcityError::MissingMigrationAccounts);
+    };
+
+    let account_type = migrate::<LoyaltyProgram>(ctx)?;
+    let loyalty_program_key = ctx.accounts.account.key();
+
+    // Fund treasury to rent exemption so small fee transfers never fail
+    let treasury_rent = Rent::get()?.minimum_balance(0);
+    let treasury_shortfall = treasury_rent.saturating_sub(platform_treasury.lamports());
+    if treasury_shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: platform_treasury.to_account_info(),
+                },
+            ),
+            treasury_shortfall,
+        )?;
+    }
+
+    let tier_config_bump = ctx.bumps.tier_config.ok_or(SolcityError::InvalidAccount)?;
+    let tier_config_info = tier_config.to_account_info();
+    create_pda_account(
+        &tier_config_info,
+        8 + TierConfig::INIT_SPACE,
+        &[&[
+            TierConfig::SEED_PREFIX,
+            loyalty_program_key.as_ref(),
+            &[tier_config_bump],
+        ]],
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    // The original program used the default tiers
+    let tiers = TierConfig {
+        version: CURRENT_ACCOUNT_VERSION,
+        loyalty_program: loyalty_program_key,
+        tiers: TierConfig::default_tiers(),
+        bump: tier_config_bump,
+        reserved: [0; 32],
+    };
+    let mut data = tier_config_info.try_borrow_mut_data()?;
+    tiers.try_serialize(&mut &mut data[..])?;
+
+    Ok(account_type)
+}
+
+/// Grow the account to new_len, topping up rent from the payer
+fn resize(ctx: &Context<MigrateAccount>, new_len: usize) -> Result<()> {
+    let account = ctx.accounts.account.to_account_info();
+    if account.data_len() == new_len {
+        return Ok(());
+    }
+
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    account.resize(new_len)?;
+    Ok(())
+}
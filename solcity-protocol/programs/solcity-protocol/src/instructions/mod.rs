@@ -1,35 +1,101 @@
 #![allow(ambiguous_glob_reexports)]
 
+pub mod accept_authority;
+pub mod approve_merchant;
+pub mod cancel_authority_transfer;
+pub mod claim_fees;
 pub mod close_merchant;
 pub mod create_redemption_offer;
 pub mod delete_redemption_offer;
 pub mod delete_reward_rule;
+pub mod deposit_collateral;
+pub mod expire_points;
+pub mod freeze_customer;
+pub mod gift_points;
 pub mod initialize_program;
 pub mod issue_rewards;
+pub mod migrate_account;
+pub mod propose_authority;
 pub mod redeem_rewards;
 pub mod register_customer;
 pub mod register_merchant;
+pub mod reject_merchant;
+pub mod set_collateral_config;
+pub mod set_fee_currency;
+pub mod set_fee_recipients;
+pub mod set_gift_limits;
+pub mod set_issuance_caps;
+pub mod set_issuance_ceiling;
+pub mod set_merchant_fee_override;
+pub mod set_pause_state;
+pub mod set_referral_config;
 pub mod set_reward_rule;
+pub mod set_reward_rule_priority;
+pub mod set_rule_combination;
+pub mod set_streak_milestones;
+pub mod set_tier_config;
+pub mod set_volume_fee_tiers;
+pub mod settle;
+pub mod suspend_merchant;
+pub mod thaw_customer;
 pub mod toggle_redemption_offer;
 pub mod toggle_reward_rule;
+pub mod update_interest_rate;
 pub mod update_merchant;
+pub mod update_program_config;
 pub mod update_redemption_offer;
 pub mod update_reward_rule;
+pub mod update_token_metadata;
 pub mod use_voucher;
+pub mod withdraw_collateral;
+pub mod withdraw_fees;
 
+pub use accept_authority::*;
+pub use approve_merchant::*;
+pub use cancel_authority_transfer::*;
+pub use claim_fees::*;
 pub use close_merchant::*;
 pub use create_redemption_offer::*;
 pub use delete_redemption_offer::*;
 pub use delete_reward_rule::*;
+pub use deposit_collateral::*;
+pub use expire_points::*;
+pub use freeze_customer::*;
+pub use gift_points::*;
 pub use initialize_program::*;
 pub use issue_rewards::*;
+pub use migrate_account::*;
+pub use propose_authority::*;
 pub use redeem_rewards::*;
 pub use register_customer::*;
 pub use register_merchant::*;
+pub use reject_merchant::*;
+pub use set_collateral_config::*;
+pub use set_fee_currency::*;
+pub use set_fee_recipients::*;
+pub use set_gift_limits::*;
+pub use set_issuance_caps::*;
+pub use set_issuance_ceiling::*;
+pub use set_merchant_fee_override::*;
+pub use set_pause_state::*;
+pub use set_referral_config::*;
 pub use set_reward_rule::*;
+pub use set_reward_rule_priority::*;
+pub use set_rule_combination::*;
+pub use set_streak_milestones::*;
+pub use set_tier_config::*;
+pub use set_volume_fee_tiers::*;
+pub use settle::*;
+pub use suspend_merchant::*;
+pub use thaw_customer::*;
 pub use toggle_redemption_offer::*;
 pub use toggle_reward_rule::*;
+pub use update_interest_rate::*;
 pub use update_merchant::*;
+pub use update_program_config::*;
 pub use update_redemption_offer::*;
 pub use update_reward_rule::*;
+pub use update_token_metadata::*;
 pub use use_voucher::*;
+pub use withdraw_collateral::*;
+pub use withdraw_fees::*;
@@ -0,0 +1,39 @@
+use crate::{AuthorityTransferProposedEvent, LoyaltyProgram, SolcityError};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+}
+
+pub fn handler(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    require!(
+        new_authority != Pubkey::default() && new_authority != ctx.accounts.authority.key(),
+        SolcityError::InvalidAuthority
+    );
+
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    // Overwrites any previously proposed authority
+    loyalty_program.pending_authority = Some(new_authority);
+
+    emit!(AuthorityTransferProposedEvent {
+        loyalty_program: loyalty_program.key(),
+        authority: ctx.accounts.authority.key(),
+        pending_authority: new_authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Authority transfer proposed to: {}", new_authority);
+
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use crate::{
-    Customer, LoyaltyProgram, Merchant, MerchantCustomerRecord, OfferRedemptionRecord, 
-    RedemptionOffer, RedemptionVoucher, TransactionRecord, RewardsRedeemedEvent, SolcityError
+    Customer, LoyaltyProgram, Merchant, MerchantCustomerRecord, OfferRedemptionRecord, PointLots,
+    RedemptionOffer, RedemptionVoucher, TransactionRecord, RewardsRedeemedEvent, SolcityError,
+    StreakMilestoneEvent, CURRENT_ACCOUNT_VERSION, PAUSE_REDEMPTION,
 };
+use crate::settlement::record_redemption;
 use anchor_lang::prelude::*;
 use anchor_spl::token_2022::{self, Token2022};
 use anchor_spl::token_interface::{Mint, TokenAccount};
@@ -20,6 +22,7 @@ pub struct RedeemRewards<'info> {
             loyalty_program.key().as_ref()
         ],
         bump = customer.bump,
+        constraint = !customer.is_frozen @ SolcityError::CustomerFrozen,
     )]
     pub customer: Box<Account<'info, Customer>>,
 
@@ -31,12 +34,13 @@ pub struct RedeemRewards<'info> {
             loyalty_program.key().as_ref()
         ],
         bump = merchant.bump,
+        constraint = merchant.is_approved() @ SolcityError::MerchantNotActive,
     )]
     pub merchant: Box<Account<'info, Merchant>>,
 
     #[account(
         mut,
-        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.authority.as_ref()],
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
         bump = loyalty_program.bump,
     )]
     pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
@@ -125,11 +129,30 @@ pub struct RedeemRewards<'info> {
     )]
     pub offer_redemption_record: Box<Account<'info, OfferRedemptionRecord>>,
 
+    /// Customer's point lots, consumed oldest-first
+    #[account(
+        init_if_needed,
+        payer = customer_authority,
+        space = 8 + PointLots::INIT_SPACE,
+        seeds = [PointLots::SEED_PREFIX, customer.key().as_ref()],
+        bump
+    )]
+    pub point_lots: Box<Account<'info, PointLots>>,
+
     pub token_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
+    // remaining_accounts: SettlementLedger PDAs for each other merchant whose points are redeemed
 }
 
-pub fn handler(ctx: Context<RedeemRewards>, voucher_seed: u64) -> Result<()> {
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, RedeemRewards<'info>>,
+    voucher_seed: u64,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_REDEMPTION),
+        SolcityError::ProgramPaused
+    );
+
     let clock = Clock::get()?;
     
     // Check if offer is available (borrow immutably first)
@@ -188,6 +211,7 @@ pub fn handler(ctx: Context<RedeemRewards>, voucher_seed: u64) -> Result<()> {
 
     // Initialize voucher
     let voucher = &mut ctx.accounts.voucher;
+    voucher.version = CURRENT_ACCOUNT_VERSION;
     voucher.customer = ctx.accounts.customer_authority.key();
     voucher.merchant = ctx.accounts.merchant.key();
     voucher.redemption_offer = ctx.accounts.redemption_offer.key();
@@ -213,6 +237,28 @@ pub fn handler(ctx: Context<RedeemRewards>, voucher_seed: u64) -> Result<()> {
         .checked_add(offer_cost)
         .ok_or(SolcityError::Overflow)?;
 
+    // Redemptions spend the oldest points first (FIFO)
+    let point_lots = &mut ctx.accounts.point_lots;
+    if point_lots.customer == Pubkey::default() {
+        point_lots.version = CURRENT_ACCOUNT_VERSION;
+        point_lots.customer = customer.key();
+        point_lots.bump = ctx.bumps.point_lots;
+    }
+    let redeemed_lots = point_lots.take(offer_cost);
+    point_lots.sync_next_expiry(customer);
+
+    // Points issued elsewhere are owed to this merchant (ledgers in remaining_accounts);
+    // the merchant's own points leave circulation and release its liability
+    let own_points = record_redemption(
+        &redeemed_lots,
+        merchant.key(),
+        loyalty_program.key(),
+        ctx.remaining_accounts,
+        &ctx.accounts.customer_authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    merchant.release_points(own_points);
+
     merchant.total_redeemed = merchant
         .total_redeemed
         .checked_add(offer_cost)
@@ -228,6 +274,20 @@ pub fn handler(ctx: Context<RedeemRewards>, voucher_seed: u64) -> Result<()> {
         .checked_add(1)
         .ok_or(SolcityError::Overflow)?;
 
+    // Advance the daily streak
+    if customer.update_streak(clock.unix_timestamp)
+        && loyalty_program.is_streak_milestone(customer.streak_days)
+    {
+        emit!(StreakMilestoneEvent {
+            loyalty_program: loyalty_program.key(),
+            customer: customer.key(),
+            customer_wallet: customer.wallet,
+            streak_days: customer.streak_days,
+            longest_streak: customer.longest_streak,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
     customer.last_activity = clock.unix_timestamp;
 
     customer.transaction_count = customer
@@ -237,16 +297,12 @@ pub fn handler(ctx: Context<RedeemRewards>, voucher_seed: u64) -> Result<()> {
 
     // Store transaction record
     let transaction_record = &mut ctx.accounts.transaction_record;
+    transaction_record.version = CURRENT_ACCOUNT_VERSION;
     transaction_record.customer = ctx.accounts.customer_authority.key();
     transaction_record.merchant = merchant.key();
     transaction_record.transaction_type = 1; // 1 = Redeemed
     transaction_record.amount = offer_cost;
-    transaction_record.tier = match customer.tier {
-        crate::state::CustomerTier::Bronze => 0,
-        crate::state::CustomerTier::Silver => 1,
-        crate::state::CustomerTier::Gold => 2,
-        crate::state::CustomerTier::Platinum => 3,
-    };
+    transaction_record.tier = customer.tier.index();
     transaction_record.timestamp = clock.unix_timestamp;
     transaction_record.index = customer.transaction_count - 1; // Already incremented above
     transaction_record.bump = ctx.bumps.transaction_record;
@@ -255,6 +311,7 @@ pub fn handler(ctx: Context<RedeemRewards>, voucher_seed: u64) -> Result<()> {
     let merchant_customer_record = &mut ctx.accounts.merchant_customer_record;
     if merchant_customer_record.merchant == Pubkey::default() {
         // First time initialization (customer redeemed before earning)
+        merchant_customer_record.version = CURRENT_ACCOUNT_VERSION;
         merchant_customer_record.merchant = merchant.key();
         merchant_customer_record.customer = ctx.accounts.customer_authority.key();
         merchant_customer_record.total_issued = 0;
@@ -278,6 +335,7 @@ pub fn handler(ctx: Context<RedeemRewards>, voucher_seed: u64) -> Result<()> {
 
     // Store offer redemption record
     let offer_redemption_record = &mut ctx.accounts.offer_redemption_record;
+    offer_redemption_record.version = CURRENT_ACCOUNT_VERSION;
     offer_redemption_record.offer = ctx.accounts.redemption_offer.key();
     offer_redemption_record.merchant = merchant.key();
     offer_redemption_record.customer = ctx.accounts.customer_authority.key();
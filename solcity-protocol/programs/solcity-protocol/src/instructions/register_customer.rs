@@ -1,4 +1,7 @@
-use crate::{Customer, CustomerRegisteredEvent, CustomerTier, LoyaltyProgram, SolcityError};
+use crate::{
+    Customer, CustomerRegisteredEvent, CustomerTier, LoyaltyProgram, Referral,
+    ReferralRegisteredEvent, SolcityError, CURRENT_ACCOUNT_VERSION, PAUSE_REGISTRATION,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_2022::Token2022;
@@ -24,7 +27,7 @@ pub struct RegisterCustomer<'info> {
 
     #[account(
         mut,
-        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.authority.as_ref()],
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
         bump = loyalty_program.bump,
     )]
     pub loyalty_program: Account<'info, LoyaltyProgram>,
@@ -44,16 +47,44 @@ pub struct RegisterCustomer<'info> {
     )]
     pub customer_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    /// Customer who referred this one (optional, requires `referral`)
+    #[account(
+        mut,
+        seeds = [
+            Customer::SEED_PREFIX,
+            referrer.wallet.as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = referrer.bump,
+    )]
+    pub referrer: Option<Box<Account<'info, Customer>>>,
+
+    /// Referral record linking the referrer and this customer (optional, requires `referrer`)
+    #[account(
+        init,
+        payer = customer_authority,
+        space = 8 + Referral::INIT_SPACE,
+        seeds = [Referral::SEED_PREFIX, customer.key().as_ref()],
+        bump
+    )]
+    pub referral: Option<Box<Account<'info, Referral>>>,
+
     pub token_program: Program<'info, Token2022>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<RegisterCustomer>) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_REGISTRATION),
+        SolcityError::ProgramPaused
+    );
+
     let customer = &mut ctx.accounts.customer;
     let loyalty_program = &mut ctx.accounts.loyalty_program;
     let clock = Clock::get()?;
 
+    customer.version = CURRENT_ACCOUNT_VERSION;
     customer.wallet = ctx.accounts.customer_authority.key();
     customer.loyalty_program = loyalty_program.key();
     customer.total_earned = 0;
@@ -64,6 +95,19 @@ pub fn handler(ctx: Context<RegisterCustomer>) -> Result<()> {
     customer.last_activity = clock.unix_timestamp;
     customer.bump = ctx.bumps.customer;
     customer.joined_at = clock.unix_timestamp;
+    customer.next_expiry_at = 0;
+    customer.next_expiry_amount = 0;
+    customer.total_expired = 0;
+    customer.is_frozen = false;
+    customer.freeze_reason = 0;
+    customer.gift_day = 0;
+    customer.gifted_today = 0;
+    customer.referrer = Pubkey::default();
+    customer.longest_streak = 0;
+    customer.streak_day = 0;
+    customer.referral_pending = false;
+    customer.referral_count = 0;
+    customer.referral_earnings = 0;
 
     loyalty_program.total_customers = loyalty_program
         .total_customers
@@ -78,6 +122,54 @@ pub fn handler(ctx: Context<RegisterCustomer>) -> Result<()> {
         timestamp: clock.unix_timestamp,
     });
 
+    match (
+        ctx.accounts.referrer.as_mut(),
+        ctx.accounts.referral.as_mut(),
+    ) {
+        (Some(referrer), Some(referral)) => {
+            require_keys_neq!(referrer.wallet, customer.wallet, SolcityError::SelfReferral);
+            // Referral loops cannot form: the referrer must already be registered and a
+            // customer's referrer is only set here, at its own registration, so no existing
+            // customer can have been referred by the one being created
+            require!(
+                referrer.referral_count < loyalty_program.max_referrals_per_referrer,
+                SolcityError::ReferralLimitReached
+            );
+
+            referrer.referral_count = referrer
+                .referral_count
+                .checked_add(1)
+                .ok_or(SolcityError::Overflow)?;
+
+            referral.version = CURRENT_ACCOUNT_VERSION;
+            referral.loyalty_program = loyalty_program.key();
+            referral.referrer = referrer.key();
+            referral.referee = customer.key();
+            referral.created_at = clock.unix_timestamp;
+            referral.referrer_bonus = 0;
+            referral.referee_bonus = 0;
+            referral.rewarded_at = 0;
+            referral.bump = ctx
+                .bumps
+                .referral
+                .ok_or(SolcityError::MissingReferralAccounts)?;
+
+            customer.referrer = referrer.key();
+            customer.referral_pending = true;
+
+            emit!(ReferralRegisteredEvent {
+                loyalty_program: loyalty_program.key(),
+                referral: referral.key(),
+                referrer: referrer.key(),
+                referee: customer.key(),
+                referral_count: referrer.referral_count,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+        (None, None) => {}
+        _ => return err!(SolcityError::MissingReferralAccounts),
+    }
+
     msg!("Customer registered with Bronze tier");
 
     Ok(())
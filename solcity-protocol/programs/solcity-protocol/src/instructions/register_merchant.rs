@@ -1,6 +1,10 @@
-use crate::{LoyaltyProgram, Merchant, MerchantRegisteredEvent, SolcityError, MERCHANT_REGISTRATION_FEE};
+use crate::{
+    IssuanceCaps, LoyaltyProgram, Merchant, MerchantRegisteredEvent, MerchantStatus,
+    RuleCombination, SolcityError, CURRENT_ACCOUNT_VERSION, PAUSE_REGISTRATION,
+};
+use crate::fees::{collect_fee, FeeTokenAccounts};
 use anchor_lang::prelude::*;
-use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 #[instruction(name: String)]
@@ -23,17 +27,31 @@ pub struct RegisterMerchant<'info> {
 
     #[account(
         mut,
-        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.authority.as_ref()],
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
         bump = loyalty_program.bump,
     )]
-    pub loyalty_program: Account<'info, LoyaltyProgram>,
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
 
-    /// CHECK: Platform treasury account to receive fees
+    /// Platform treasury PDA to receive fees
     #[account(
         mut,
-        constraint = platform_treasury.key() == loyalty_program.treasury @ SolcityError::InvalidTreasury
+        seeds = [LoyaltyProgram::TREASURY_SEED_PREFIX, loyalty_program.key().as_ref()],
+        bump = loyalty_program.treasury_bump,
     )]
-    pub platform_treasury: AccountInfo<'info>,
+    pub platform_treasury: SystemAccount<'info>,
+
+    /// Fee mint (required when fees are paid in an SPL token)
+    pub fee_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Merchant token account paying fees (required when fees are paid in an SPL token)
+    #[account(mut)]
+    pub merchant_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Treasury PDA token account receiving fees (required when fees are paid in an SPL token)
+    #[account(mut)]
+    pub treasury_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub fee_token_program: Option<Interface<'info, TokenInterface>>,
 
     pub system_program: Program<'info, System>,
 }
@@ -46,6 +64,11 @@ pub fn handler(
     description: Option<String>,
     reward_rate: u64,
 ) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_REGISTRATION),
+        SolcityError::ProgramPaused
+    );
+
     require!(!name.is_empty(), SolcityError::NameEmpty);
     require!(name.len() <= 32, SolcityError::NameTooLong);
     require!(avatar_url.len() <= 256, SolcityError::NameTooLong);
@@ -57,22 +80,28 @@ pub fn handler(
         require!(desc.len() <= 256, SolcityError::NameTooLong);
     }
 
-    // Collect registration fee
-    system_program::transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.merchant_authority.to_account_info(),
-                to: ctx.accounts.platform_treasury.to_account_info(),
-            },
-        ),
-        MERCHANT_REGISTRATION_FEE,
+    // Collect registration fee (held in treasury until approval)
+    let registration_fee = ctx.accounts.loyalty_program.merchant_registration_fee;
+    let fee_tokens = FeeTokenAccounts::resolve(
+        &ctx.accounts.loyalty_program,
+        ctx.accounts.fee_mint.as_deref(),
+        ctx.accounts.merchant_fee_account.as_deref(),
+        ctx.accounts.treasury_fee_account.as_deref(),
+        ctx.accounts.fee_token_program.as_ref(),
+    )?;
+    collect_fee(
+        registration_fee,
+        &ctx.accounts.merchant_authority,
+        &ctx.accounts.platform_treasury,
+        &ctx.accounts.system_program,
+        fee_tokens.as_ref(),
     )?;
 
     let merchant = &mut ctx.accounts.merchant;
     let loyalty_program = &mut ctx.accounts.loyalty_program;
     let clock = Clock::get()?;
 
+    merchant.version = CURRENT_ACCOUNT_VERSION;
     merchant.authority = ctx.accounts.merchant_authority.key();
     merchant.loyalty_program = loyalty_program.key();
     merchant.name = name.clone();
@@ -82,18 +111,28 @@ pub fn handler(
     merchant.reward_rate = reward_rate;
     merchant.total_issued = 0;
     merchant.total_redeemed = 0;
-    merchant.is_active = true;
+    merchant.status = MerchantStatus::Pending;
+    merchant.registration_fee_paid = registration_fee;
+    merchant.issuance_fee_override = None;
     merchant.bump = ctx.bumps.merchant;
     merchant.created_at = clock.unix_timestamp;
+    merchant.collateral_balance = 0;
+    merchant.outstanding_points = 0;
+    merchant.issuance_caps = IssuanceCaps::default();
+    merchant.issuance_ceiling = IssuanceCaps::default();
+    merchant.issuance_window_start = 0;
+    merchant.issued_in_window = 0;
+    merchant.issued_in_prev_window = 0;
+    merchant.rule_combination = RuleCombination::HighestOnly;
 
     loyalty_program.total_merchants = loyalty_program
         .total_merchants
         .checked_add(1)
         .ok_or(SolcityError::Overflow)?;
 
-    loyalty_program.total_fees_collected = loyalty_program
-        .total_fees_collected
-        .checked_add(MERCHANT_REGISTRATION_FEE)
+    loyalty_program.pending_registration_fees = loyalty_program
+        .pending_registration_fees
+        .checked_add(registration_fee)
         .ok_or(SolcityError::Overflow)?;
 
     // Emit merchant registered event
@@ -106,10 +145,10 @@ pub fn handler(
     });
 
     msg!(
-        "Merchant '{}' registered with reward rate: {} tokens/$ (Fee: {} lamports)",
+        "Merchant '{}' registered pending approval with reward rate: {} tokens/$ (Fee: {})",
         name,
         reward_rate,
-        MERCHANT_REGISTRATION_FEE
+        registration_fee
     );
 
     Ok(())
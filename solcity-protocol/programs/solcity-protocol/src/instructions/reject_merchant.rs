@@ -0,0 +1,117 @@
+use crate::{LoyaltyProgram, Merchant, MerchantStatus, MerchantStatusChangedEvent, SolcityError};
+use crate::fees::{pay_from_treasury, FeeTokenAccounts};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct RejectMerchant<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Merchant::SEED_PREFIX,
+            merchant_authority.key().as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = merchant.bump,
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+
+    /// CHECK: Merchant wallet receiving the refunded registration fee (bound by merchant seeds)
+    #[account(mut)]
+    pub merchant_authority: AccountInfo<'info>,
+
+    /// Platform treasury PDA holding the registration fee
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::TREASURY_SEED_PREFIX, loyalty_program.key().as_ref()],
+        bump = loyalty_program.treasury_bump,
+    )]
+    pub platform_treasury: SystemAccount<'info>,
+
+    /// Fee mint (required when fees are paid in an SPL token)
+    pub fee_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Merchant token account receiving the refund (required when fees are paid in an SPL token)
+    #[account(mut)]
+    pub merchant_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Treasury PDA token account holding fees (required when fees are paid in an SPL token)
+    #[account(mut)]
+    pub treasury_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub fee_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RejectMerchant>) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.merchant.status == MerchantStatus::Pending,
+        SolcityError::InvalidMerchantStatus
+    );
+
+    // Refund the registration fee held since registration
+    let refund = ctx.accounts.merchant.registration_fee_paid;
+    if refund > 0 {
+        let fee_tokens = FeeTokenAccounts::resolve(
+            &ctx.accounts.loyalty_program,
+            ctx.accounts.fee_mint.as_deref(),
+            ctx.accounts.merchant_fee_account.as_deref(),
+            ctx.accounts.treasury_fee_account.as_deref(),
+            ctx.accounts.fee_token_program.as_ref(),
+        )?;
+
+        if let Some(ref tokens) = fee_tokens {
+            require_keys_eq!(
+                tokens.counterparty.owner,
+                ctx.accounts.merchant_authority.key(),
+                SolcityError::UnauthorizedAccess
+            );
+        }
+
+        pay_from_treasury(
+            &ctx.accounts.loyalty_program,
+            refund,
+            &ctx.accounts.platform_treasury,
+            &ctx.accounts.merchant_authority,
+            &ctx.accounts.system_program,
+            fee_tokens.as_ref(),
+        )?;
+
+        let loyalty_program = &mut ctx.accounts.loyalty_program;
+        loyalty_program.pending_registration_fees = loyalty_program
+            .pending_registration_fees
+            .checked_sub(refund)
+            .ok_or(SolcityError::Overflow)?;
+    }
+
+    let merchant = &mut ctx.accounts.merchant;
+    merchant.registration_fee_paid = 0;
+    merchant.status = MerchantStatus::Rejected;
+
+    emit!(MerchantStatusChangedEvent {
+        merchant: merchant.key(),
+        merchant_authority: merchant.authority,
+        authority: ctx.accounts.authority.key(),
+        old_status: MerchantStatus::Pending,
+        new_status: MerchantStatus::Rejected,
+        fee_refunded: refund,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Merchant '{}' rejected, refunded {} in fees", merchant.name, refund);
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::{CollateralConfigUpdatedEvent, LoyaltyProgram, SolcityError};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct SetCollateralConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+
+    /// New collateral mint (required when switching to an SPL token)
+    pub collateral_mint: Option<InterfaceAccount<'info, Mint>>,
+}
+
+pub fn handler(
+    ctx: Context<SetCollateralConfig>,
+    point_value: u64,
+    collateral_mint: Option<Pubkey>,
+) -> Result<()> {
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    // Escrow balances are denominated in the current currency, so they must be withdrawn first
+    if collateral_mint != loyalty_program.collateral_mint {
+        require!(
+            loyalty_program.total_collateral == 0,
+            SolcityError::CollateralOutstanding
+        );
+    }
+
+    if let Some(mint) = collateral_mint {
+        let mint_account = ctx
+            .accounts
+            .collateral_mint
+            .as_ref()
+            .ok_or(SolcityError::MissingCollateralTokenAccounts)?;
+        require_keys_eq!(mint_account.key(), mint, SolcityError::InvalidCollateralMint);
+    }
+
+    let old_point_value = loyalty_program.point_value;
+    let old_collateral_mint = loyalty_program.collateral_mint;
+    loyalty_program.point_value = point_value;
+    loyalty_program.collateral_mint = collateral_mint;
+
+    emit!(CollateralConfigUpdatedEvent {
+        loyalty_program: loyalty_program.key(),
+        authority: ctx.accounts.authority.key(),
+        old_point_value,
+        new_point_value: point_value,
+        old_collateral_mint,
+        new_collateral_mint: collateral_mint,
+        timestamp: clock.unix_timestamp,
+    });
+
+    match collateral_mint {
+        Some(mint) => msg!("Point value set to {} base units of {}", point_value, mint),
+        None => msg!("Point value set to {} lamports", point_value),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use crate::{FeeCurrencyChangedEvent, LoyaltyProgram, SolcityError, VolumeFeeTier};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct SetFeeCurrency<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+
+    /// New fee mint (required when switching to an SPL token)
+    pub fee_mint: Option<InterfaceAccount<'info, Mint>>,
+}
+
+/// Fee amounts are denominated in the fee currency, so the new base fees and volume schedule
+/// are set in the same instruction and merchant overrides lapse until set again
+pub fn handler(
+    ctx: Context<SetFeeCurrency>,
+    fee_mint: Option<Pubkey>,
+    merchant_registration_fee: u64,
+    issuance_fee_per_token: u64,
+    volume_fee_tiers: Vec<VolumeFeeTier>,
+) -> Result<()> {
+    LoyaltyProgram::validate_volume_fee_tiers(&volume_fee_tiers)?;
+
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    // Fee counters are denominated in the current currency, so they must be settled first
+    require!(
+        loyalty_program.total_fees_collected == loyalty_program.total_fees_withdrawn
+            && loyalty_program.pending_registration_fees == 0,
+        SolcityError::FeesOutstanding
+    );
+
+    if let Some(mint) = fee_mint {
+        let mint_account = ctx
+            .accounts
+            .fee_mint
+            .as_ref()
+            .ok_or(SolcityError::MissingFeeTokenAccounts)?;
+        require_keys_eq!(mint_account.key(), mint, SolcityError::InvalidFeeMint);
+    }
+
+    let old_fee_mint = loyalty_program.fee_mint;
+    loyalty_program.fee_mint = fee_mint;
+    loyalty_program.merchant_registration_fee = merchant_registration_fee;
+    loyalty_program.issuance_fee_per_token = issuance_fee_per_token;
+    loyalty_program.volume_fee_tiers = volume_fee_tiers.clone();
+    loyalty_program.fee_currency_epoch = loyalty_program
+        .fee_currency_epoch
+        .checked_add(1)
+        .ok_or(SolcityError::Overflow)?;
+
+    emit!(FeeCurrencyChangedEvent {
+        loyalty_program: loyalty_program.key(),
+        authority: ctx.accounts.authority.key(),
+        old_fee_mint,
+        new_fee_mint: fee_mint,
+        merchant_registration_fee,
+        issuance_fee_per_token,
+        volume_fee_tiers,
+        fee_currency_epoch: loyalty_program.fee_currency_epoch,
+        timestamp: clock.unix_timestamp,
+    });
+
+    match fee_mint {
+        Some(mint) => msg!("Platform fees now paid in token {}", mint),
+        None => msg!("Platform fees now paid in lamports"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use crate::{
+    FeeRecipient, FeeRecipientsUpdatedEvent, LoyaltyProgram, SolcityError, BASIS_POINTS,
+    MAX_FEE_RECIPIENTS,
+};
+use anchor_lang::prelude::*;
+
+/// Recipient and share supplied by the authority
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct FeeRecipientShare {
+    pub recipient: Pubkey,
+    pub share_bps: u16,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeRecipients<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+}
+
+pub fn handler(ctx: Context<SetFeeRecipients>, recipients: Vec<FeeRecipientShare>) -> Result<()> {
+    require!(
+        recipients.len() <= MAX_FEE_RECIPIENTS,
+        SolcityError::InvalidFeeSplit
+    );
+
+    // An empty list disables the split; otherwise shares must cover all fees
+    if !recipients.is_empty() {
+        let mut total_bps = 0u64;
+        for (i, share) in recipients.iter().enumerate() {
+            require!(share.share_bps > 0, SolcityError::InvalidFeeSplit);
+            require!(
+                share.recipient != Pubkey::default(),
+                SolcityError::InvalidFeeSplit
+            );
+            require!(
+                recipients[..i].iter().all(|r| r.recipient != share.recipient),
+                SolcityError::InvalidFeeSplit
+            );
+            total_bps += share.share_bps as u64;
+        }
+        require!(total_bps == BASIS_POINTS, SolcityError::InvalidFeeSplit);
+    }
+
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    // Recipients being removed must claim their balance first
+    for existing in loyalty_program.fee_recipients.iter() {
+        if existing.accrued > 0 {
+            require!(
+                recipients.iter().any(|r| r.recipient == existing.recipient),
+                SolcityError::FeeRecipientHasUnclaimedFees
+            );
+        }
+    }
+
+    // Carry accrued and claimed balances over for recipients that remain
+    let fee_recipients: Vec<FeeRecipient> = recipients
+        .iter()
+        .map(|share| {
+            let existing = loyalty_program
+                .fee_recipients
+                .iter()
+                .find(|r| r.recipient == share.recipient);
+            FeeRecipient {
+                recipient: share.recipient,
+                share_bps: share.share_bps,
+                accrued: existing.map_or(0, |r| r.accrued),
+                claimed: existing.map_or(0, |r| r.claimed),
+            }
+        })
+        .collect();
+    loyalty_program.fee_recipients = fee_recipients;
+
+    emit!(FeeRecipientsUpdatedEvent {
+        loyalty_program: loyalty_program.key(),
+        authority: ctx.accounts.authority.key(),
+        recipients: recipients.iter().map(|r| r.recipient).collect(),
+        shares_bps: recipients.iter().map(|r| r.share_bps).collect(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Fee split updated: {} recipient(s)", recipients.len());
+
+    Ok(())
+}
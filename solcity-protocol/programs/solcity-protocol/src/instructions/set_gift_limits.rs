@@ -0,0 +1,44 @@
+use crate::{GiftLimitsUpdatedEvent, LoyaltyProgram, SolcityError, MAX_TIERS};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetGiftLimits<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+}
+
+pub fn handler(ctx: Context<SetGiftLimits>, daily_limits: Vec<u64>) -> Result<()> {
+    require!(
+        daily_limits.len() <= MAX_TIERS,
+        SolcityError::InvalidGiftLimits
+    );
+
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    // Tiers without an entry are unlimited
+    let mut new_limits = [0u64; MAX_TIERS];
+    new_limits[..daily_limits.len()].copy_from_slice(&daily_limits);
+
+    let old_limits = loyalty_program.gift_daily_limits;
+    loyalty_program.gift_daily_limits = new_limits;
+
+    emit!(GiftLimitsUpdatedEvent {
+        loyalty_program: loyalty_program.key(),
+        authority: ctx.accounts.authority.key(),
+        old_limits,
+        new_limits,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Daily gift limits updated: {:?}", new_limits);
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::{IssuanceCaps, IssuanceCapsUpdatedEvent, LoyaltyProgram, Merchant};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetIssuanceCaps<'info> {
+    pub merchant_authority: Signer<'info>,
+
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Merchant::SEED_PREFIX,
+            merchant_authority.key().as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = merchant.bump,
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+}
+
+pub fn handler(ctx: Context<SetIssuanceCaps>, caps: IssuanceCaps) -> Result<()> {
+    // Not gated by PAUSE_MERCHANT_OPS: tightening caps must stay possible during an incident
+    let merchant = &mut ctx.accounts.merchant;
+    let clock = Clock::get()?;
+
+    merchant.issuance_caps = caps;
+
+    emit!(IssuanceCapsUpdatedEvent {
+        merchant: merchant.key(),
+        updated_by: ctx.accounts.merchant_authority.key(),
+        issuance_caps: merchant.issuance_caps,
+        issuance_ceiling: merchant.issuance_ceiling,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Merchant '{}' issuance caps: {} per tx, {} per day, {} lifetime (0 = unlimited)",
+        merchant.name,
+        caps.per_transaction,
+        caps.per_day,
+        caps.lifetime
+    );
+
+    Ok(())
+}
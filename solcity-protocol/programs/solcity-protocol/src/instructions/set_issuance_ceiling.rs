@@ -0,0 +1,50 @@
+use crate::{IssuanceCaps, IssuanceCapsUpdatedEvent, LoyaltyProgram, Merchant, SolcityError};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetIssuanceCeiling<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Merchant::SEED_PREFIX,
+            merchant.authority.as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = merchant.bump,
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+}
+
+pub fn handler(ctx: Context<SetIssuanceCeiling>, ceiling: IssuanceCaps) -> Result<()> {
+    let merchant = &mut ctx.accounts.merchant;
+    let clock = Clock::get()?;
+
+    merchant.issuance_ceiling = ceiling;
+
+    emit!(IssuanceCapsUpdatedEvent {
+        merchant: merchant.key(),
+        updated_by: ctx.accounts.authority.key(),
+        issuance_caps: merchant.issuance_caps,
+        issuance_ceiling: merchant.issuance_ceiling,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Merchant '{}' issuance ceiling: {} per tx, {} per day, {} lifetime (0 = unlimited)",
+        merchant.name,
+        ceiling.per_transaction,
+        ceiling.per_day,
+        ceiling.lifetime
+    );
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::{LoyaltyProgram, Merchant, MerchantFeeOverrideEvent, SolcityError};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetMerchantFeeOverride<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Merchant::SEED_PREFIX,
+            merchant.authority.as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = merchant.bump,
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+}
+
+pub fn handler(ctx: Context<SetMerchantFeeOverride>, fee_per_token: Option<u64>) -> Result<()> {
+    let loyalty_program = &ctx.accounts.loyalty_program;
+    let merchant = &mut ctx.accounts.merchant;
+    let clock = Clock::get()?;
+
+    let old_fee_override = loyalty_program.merchant_fee_override(merchant);
+    merchant.issuance_fee_override = fee_per_token;
+    merchant.fee_override_epoch = loyalty_program.fee_currency_epoch;
+
+    emit!(MerchantFeeOverrideEvent {
+        merchant: merchant.key(),
+        authority: ctx.accounts.authority.key(),
+        old_fee_override,
+        new_fee_override: fee_per_token,
+        timestamp: clock.unix_timestamp,
+    });
+
+    match fee_per_token {
+        Some(fee) => msg!("Merchant '{}' issuance fee set to {} per token", merchant.name, fee),
+        None => msg!("Merchant '{}' issuance fee override cleared", merchant.name),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::{LoyaltyProgram, PauseStateChangedEvent, SolcityError, PAUSE_ALL};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPauseState<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+}
+
+pub fn handler(ctx: Context<SetPauseState>, paused: u8) -> Result<()> {
+    require!(paused & !PAUSE_ALL == 0, SolcityError::InvalidPauseFlags);
+
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    let old_paused = loyalty_program.paused;
+    loyalty_program.paused = paused;
+
+    emit!(PauseStateChangedEvent {
+        loyalty_program: loyalty_program.key(),
+        authority: ctx.accounts.authority.key(),
+        old_paused,
+        new_paused: paused,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Pause state changed from {:#07b} to {:#07b}", old_paused, paused);
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::{LoyaltyProgram, ReferralConfigUpdatedEvent, SolcityError};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetReferralConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+}
+
+/// A max_referrals_per_referrer of 0 stops new referrals; pending referrals are still paid
+pub fn handler(
+    ctx: Context<SetReferralConfig>,
+    referrer_bonus: u64,
+    referee_bonus: u64,
+    referral_min_purchase: u64,
+    max_referrals_per_referrer: u32,
+) -> Result<()> {
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    loyalty_program.referrer_bonus = referrer_bonus;
+    loyalty_program.referee_bonus = referee_bonus;
+    loyalty_program.referral_min_purchase = referral_min_purchase;
+    loyalty_program.max_referrals_per_referrer = max_referrals_per_referrer;
+
+    emit!(ReferralConfigUpdatedEvent {
+        loyalty_program: loyalty_program.key(),
+        authority: ctx.accounts.authority.key(),
+        referrer_bonus,
+        referee_bonus,
+        referral_min_purchase,
+        max_referrals_per_referrer,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Referral config updated: referrer bonus {}, referee bonus {}, min purchase {}, max referrals {}",
+        referrer_bonus,
+        referee_bonus,
+        referral_min_purchase,
+        max_referrals_per_referrer
+    );
+
+    Ok(())
+}
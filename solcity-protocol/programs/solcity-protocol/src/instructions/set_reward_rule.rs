@@ -1,4 +1,7 @@
-use crate::{LoyaltyProgram, Merchant, RewardRule, RuleType, SolcityError};
+use crate::{
+    LoyaltyProgram, Merchant, RewardKind, RewardRule, RuleType, SolcityError,
+    CURRENT_ACCOUNT_VERSION, PAUSE_MERCHANT_OPS,
+};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -8,10 +11,7 @@ pub struct SetRewardRule<'info> {
     pub merchant_authority: Signer<'info>,
 
     #[account(
-        seeds = [
-            LoyaltyProgram::SEED_PREFIX,
-            merchant_authority.key().as_ref()
-        ],
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
         bump = loyalty_program.bump,
     )]
     pub loyalty_program: Account<'info, LoyaltyProgram>,
@@ -51,10 +51,21 @@ pub fn handler(
     min_purchase: u64,
     start_time: i64,
     end_time: i64,
+    min_streak_days: u16,
+    eligible_tiers: u8,
+    reward_kind: RewardKind,
+    flat_bonus: u64,
+    max_bonus: u64,
 ) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
     require!(!name.is_empty(), SolcityError::NameEmpty);
     require!(name.len() <= 32, SolcityError::NameTooLong);
-    require!(multiplier >= 100, SolcityError::InvalidRewardAmount);
+    RewardRule::validate_reward(reward_kind, multiplier, flat_bonus)?;
+    RewardRule::validate_params(&rule_type, min_streak_days, eligible_tiers)?;
 
     if end_time > 0 {
         require!(end_time > start_time, SolcityError::InvalidTimeRange);
@@ -62,6 +73,7 @@ pub fn handler(
 
     let reward_rule = &mut ctx.accounts.reward_rule;
 
+    reward_rule.version = CURRENT_ACCOUNT_VERSION;
     reward_rule.merchant = ctx.accounts.merchant.key();
     reward_rule.rule_id = _rule_id;
     reward_rule.name = name.clone();
@@ -72,12 +84,22 @@ pub fn handler(
     reward_rule.start_time = start_time;
     reward_rule.end_time = end_time;
     reward_rule.bump = ctx.bumps.reward_rule;
+    reward_rule.priority = 0;
+    reward_rule.exclusive = false;
+    reward_rule.min_streak_days = min_streak_days;
+    reward_rule.eligible_tiers = eligible_tiers;
+    reward_rule.reward_kind = reward_kind;
+    reward_rule.flat_bonus = flat_bonus;
+    reward_rule.max_bonus = max_bonus;
 
     msg!(
-        "Reward rule created: {}, {:?}, multiplier: {}x, min purchase: ${}",
+        "Reward rule created: {}, {:?}, {:?}, multiplier: {}x, flat bonus: {}, max bonus: {}, min purchase: ${}",
         name,
         rule_type,
+        reward_kind,
         multiplier as f64 / 100.0,
+        flat_bonus,
+        max_bonus,
         min_purchase as f64 / 100.0
     );
 
@@ -0,0 +1,61 @@
+use crate::{LoyaltyProgram, Merchant, RewardRule, SolcityError, PAUSE_MERCHANT_OPS};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(rule_id: u64)]
+pub struct SetRewardRulePriority<'info> {
+    pub merchant_authority: Signer<'info>,
+
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        seeds = [
+            Merchant::SEED_PREFIX,
+            merchant_authority.key().as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = merchant.bump,
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RewardRule::SEED_PREFIX,
+            merchant.key().as_ref(),
+            &rule_id.to_le_bytes()
+        ],
+        bump = reward_rule.bump,
+        constraint = reward_rule.merchant == merchant.key() @ SolcityError::UnauthorizedAccess
+    )]
+    pub reward_rule: Box<Account<'info, RewardRule>>,
+}
+
+pub fn handler(
+    ctx: Context<SetRewardRulePriority>,
+    _rule_id: u64,
+    priority: u8,
+    exclusive: bool,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
+    let reward_rule = &mut ctx.accounts.reward_rule;
+    reward_rule.priority = priority;
+    reward_rule.exclusive = exclusive;
+
+    msg!(
+        "Reward rule '{}' priority: {}, exclusive: {}",
+        reward_rule.name,
+        priority,
+        exclusive
+    );
+
+    Ok(())
+}
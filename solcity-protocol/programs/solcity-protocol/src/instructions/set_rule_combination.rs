@@ -0,0 +1,42 @@
+use crate::{LoyaltyProgram, Merchant, RuleCombination, SolcityError, PAUSE_MERCHANT_OPS};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetRuleCombination<'info> {
+    pub merchant_authority: Signer<'info>,
+
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Merchant::SEED_PREFIX,
+            merchant_authority.key().as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = merchant.bump,
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+}
+
+pub fn handler(ctx: Context<SetRuleCombination>, rule_combination: RuleCombination) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
+    let merchant = &mut ctx.accounts.merchant;
+    merchant.rule_combination = rule_combination;
+
+    msg!(
+        "Merchant '{}' reward rules now combine as {:?}",
+        merchant.name,
+        rule_combination
+    );
+
+    Ok(())
+}
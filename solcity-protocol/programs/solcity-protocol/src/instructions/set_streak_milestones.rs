@@ -0,0 +1,48 @@
+use crate::{LoyaltyProgram, SolcityError, StreakMilestonesUpdatedEvent, MAX_STREAK_MILESTONES};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetStreakMilestones<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+}
+
+pub fn handler(ctx: Context<SetStreakMilestones>, milestones: Vec<u16>) -> Result<()> {
+    require!(
+        milestones.len() <= MAX_STREAK_MILESTONES,
+        SolcityError::InvalidStreakMilestones
+    );
+    require!(
+        !milestones.contains(&0) && milestones.windows(2).all(|pair| pair[0] < pair[1]),
+        SolcityError::InvalidStreakMilestones
+    );
+
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    // Unused slots stay 0
+    let mut new_milestones = [0u16; MAX_STREAK_MILESTONES];
+    new_milestones[..milestones.len()].copy_from_slice(&milestones);
+
+    let old_milestones = loyalty_program.streak_milestones;
+    loyalty_program.streak_milestones = new_milestones;
+
+    emit!(StreakMilestonesUpdatedEvent {
+        loyalty_program: loyalty_program.key(),
+        authority: ctx.accounts.authority.key(),
+        old_milestones,
+        new_milestones,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Streak milestones updated: {:?}", new_milestones);
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::{
+    LoyaltyProgram, SolcityError, TierConfig, TierConfigUpdatedEvent, TierLevel,
+    CURRENT_ACCOUNT_VERSION, MAX_TIERS, PERCENTAGE_DIVISOR,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetTierConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + TierConfig::INIT_SPACE,
+        seeds = [TierConfig::SEED_PREFIX, loyalty_program.key().as_ref()],
+        bump
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetTierConfig>, tiers: Vec<TierLevel>) -> Result<()> {
+    require!(
+        !tiers.is_empty() && tiers.len() <= MAX_TIERS,
+        SolcityError::InvalidTierConfig
+    );
+    require!(tiers[0].threshold == 0, SolcityError::InvalidTierConfig);
+
+    for (i, tier) in tiers.iter().enumerate() {
+        require!(!tier.name.is_empty(), SolcityError::NameEmpty);
+        require!(tier.name.len() <= 16, SolcityError::NameTooLong);
+        require!(
+            tier.multiplier >= PERCENTAGE_DIVISOR,
+            SolcityError::InvalidTierConfig
+        );
+        if i > 0 {
+            require!(
+                tier.threshold > tiers[i - 1].threshold,
+                SolcityError::InvalidTierConfig
+            );
+        }
+    }
+
+    let tier_config = &mut ctx.accounts.tier_config;
+    let clock = Clock::get()?;
+
+    tier_config.version = CURRENT_ACCOUNT_VERSION;
+    tier_config.loyalty_program = ctx.accounts.loyalty_program.key();
+    tier_config.tiers = tiers.clone();
+    tier_config.bump = ctx.bumps.tier_config;
+
+    emit!(TierConfigUpdatedEvent {
+        loyalty_program: ctx.accounts.loyalty_program.key(),
+        tiers,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Tier schedule updated: {} tier(s)", tier_config.tiers.len());
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::{LoyaltyProgram, SolcityError, VolumeFeeTier, VolumeFeeTiersUpdatedEvent};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetVolumeFeeTiers<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+}
+
+pub fn handler(ctx: Context<SetVolumeFeeTiers>, tiers: Vec<VolumeFeeTier>) -> Result<()> {
+    LoyaltyProgram::validate_volume_fee_tiers(&tiers)?;
+
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    let old_tiers = std::mem::replace(&mut loyalty_program.volume_fee_tiers, tiers.clone());
+
+    emit!(VolumeFeeTiersUpdatedEvent {
+        loyalty_program: loyalty_program.key(),
+        authority: ctx.accounts.authority.key(),
+        old_tiers,
+        new_tiers: tiers,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Volume fee schedule updated: {} tier(s)",
+        loyalty_program.volume_fee_tiers.len()
+    );
+
+    Ok(())
+}
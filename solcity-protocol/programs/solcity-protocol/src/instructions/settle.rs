@@ -0,0 +1,234 @@
+use crate::collateral::{escrow_rent_due, pay_from_escrow, CollateralTokenAccounts};
+use crate::{
+    LoyaltyProgram, Merchant, SettlementEvent, SettlementLedger, SolcityError, PAUSE_MERCHANT_OPS,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct Settle<'info> {
+    /// Anyone can trigger a settlement; funds only move between the two escrows, except the
+    /// rent-exempt minimum of a creditor escrow that never held collateral
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SettlementLedger::SEED_PREFIX,
+            merchant_a.key().as_ref(),
+            merchant_b.key().as_ref()
+        ],
+        bump = settlement_ledger.bump,
+        has_one = loyalty_program @ SolcityError::InvalidAccount,
+    )]
+    pub settlement_ledger: Box<Account<'info, SettlementLedger>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Merchant::SEED_PREFIX,
+            merchant_a.authority.as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = merchant_a.bump,
+    )]
+    pub merchant_a: Box<Account<'info, Merchant>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Merchant::SEED_PREFIX,
+            merchant_b.authority.as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = merchant_b.bump,
+    )]
+    pub merchant_b: Box<Account<'info, Merchant>>,
+
+    #[account(
+        mut,
+        seeds = [Merchant::ESCROW_SEED_PREFIX, merchant_a.key().as_ref()],
+        bump,
+    )]
+    pub escrow_a: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [Merchant::ESCROW_SEED_PREFIX, merchant_b.key().as_ref()],
+        bump,
+    )]
+    pub escrow_b: SystemAccount<'info>,
+
+    /// Collateral mint (required when collateral is held in an SPL token)
+    pub collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// escrow_a token account (required when collateral is held in an SPL token)
+    #[account(mut)]
+    pub escrow_a_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// escrow_b token account (required when collateral is held in an SPL token)
+    #[account(mut)]
+    pub escrow_b_collateral_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub collateral_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<Settle>) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
+    let point_value = ctx.accounts.loyalty_program.point_value;
+    require!(point_value > 0, SolcityError::CollateralNotConfigured);
+
+    let clock = Clock::get()?;
+    let a_owes_b = ctx.accounts.settlement_ledger.a_owes_b;
+    let b_owes_a = ctx.accounts.settlement_ledger.b_owes_a;
+
+    // Net the two directions; the merchant whose points were redeemed more pays the difference
+    let a_pays = a_owes_b >= b_owes_a;
+    let net_points = a_owes_b.abs_diff(b_owes_a);
+    let value = net_points
+        .checked_mul(point_value)
+        .ok_or(SolcityError::Overflow)?;
+
+    let accounts = &mut *ctx.accounts;
+    let (debtor_escrow, creditor_escrow, debtor_escrow_bump) = if a_pays {
+        (&accounts.escrow_a, &accounts.escrow_b, ctx.bumps.escrow_a)
+    } else {
+        (&accounts.escrow_b, &accounts.escrow_a, ctx.bumps.escrow_b)
+    };
+    let (debtor_token_account, creditor_token_account) = if a_pays {
+        (
+            accounts.escrow_a_collateral_account.as_deref(),
+            accounts.escrow_b_collateral_account.as_deref(),
+        )
+    } else {
+        (
+            accounts.escrow_b_collateral_account.as_deref(),
+            accounts.escrow_a_collateral_account.as_deref(),
+        )
+    };
+
+    let collateral_tokens = CollateralTokenAccounts::resolve(
+        &accounts.loyalty_program,
+        debtor_escrow.key(),
+        accounts.collateral_mint.as_deref(),
+        creditor_token_account,
+        debtor_token_account,
+        accounts.collateral_token_program.as_ref(),
+    )?;
+    if let Some(tokens) = collateral_tokens.as_ref() {
+        require_keys_eq!(
+            tokens.counterparty.owner,
+            creditor_escrow.key(),
+            SolcityError::InvalidAccount
+        );
+    }
+
+    let (debtor, creditor) = if a_pays {
+        (&accounts.merchant_a, &accounts.merchant_b)
+    } else {
+        (&accounts.merchant_b, &accounts.merchant_a)
+    };
+    let debtor_key = debtor.key();
+    let creditor_key = creditor.key();
+
+    if value > 0 {
+        require!(
+            debtor.collateral_balance >= value,
+            SolcityError::InsufficientEscrowBalance
+        );
+
+        // A payment below the rent-exempt minimum cannot open an empty escrow on its own
+        let rent_due = if collateral_tokens.is_none() {
+            escrow_rent_due(creditor_escrow)?
+        } else {
+            0
+        };
+        if rent_due > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: accounts.payer.to_account_info(),
+                        to: creditor_escrow.to_account_info(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+
+        pay_from_escrow(
+            debtor_key,
+            debtor_escrow_bump,
+            value,
+            debtor_escrow,
+            &creditor_escrow.to_account_info(),
+            &accounts.system_program,
+            collateral_tokens.as_ref(),
+        )?;
+    }
+
+    // Both directions are discharged: each issuer's liability for its redeemed points is released
+    accounts.merchant_a.release_points(a_owes_b);
+    accounts.merchant_b.release_points(b_owes_a);
+
+    let (debtor, creditor) = if a_pays {
+        (&mut accounts.merchant_a, &mut accounts.merchant_b)
+    } else {
+        (&mut accounts.merchant_b, &mut accounts.merchant_a)
+    };
+    debtor.collateral_balance = debtor
+        .collateral_balance
+        .checked_sub(value)
+        .ok_or(SolcityError::Overflow)?;
+    creditor.collateral_balance = creditor
+        .collateral_balance
+        .checked_add(value)
+        .ok_or(SolcityError::Overflow)?;
+
+    let ledger = &mut accounts.settlement_ledger;
+    ledger.a_owes_b = 0;
+    ledger.b_owes_a = 0;
+    ledger.total_settled_points = ledger
+        .total_settled_points
+        .checked_add(net_points)
+        .ok_or(SolcityError::Overflow)?;
+    ledger.total_settled_value = ledger
+        .total_settled_value
+        .checked_add(value)
+        .ok_or(SolcityError::Overflow)?;
+    ledger.last_settled_at = clock.unix_timestamp;
+
+    emit!(SettlementEvent {
+        settlement_ledger: ledger.key(),
+        debtor: debtor_key,
+        creditor: creditor_key,
+        net_points,
+        point_value,
+        value,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Settled {} points ({} collateral units) from {} to {}",
+        net_points,
+        value,
+        debtor_key,
+        creditor_key
+    );
+
+    Ok(())
+}
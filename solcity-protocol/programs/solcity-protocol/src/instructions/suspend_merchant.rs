@@ -0,0 +1,51 @@
+use crate::{LoyaltyProgram, Merchant, MerchantStatus, MerchantStatusChangedEvent, SolcityError};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SuspendMerchant<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Merchant::SEED_PREFIX,
+            merchant.authority.as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump = merchant.bump,
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+}
+
+pub fn handler(ctx: Context<SuspendMerchant>) -> Result<()> {
+    let merchant = &mut ctx.accounts.merchant;
+    let clock = Clock::get()?;
+
+    require!(
+        merchant.status == MerchantStatus::Approved,
+        SolcityError::InvalidMerchantStatus
+    );
+
+    merchant.status = MerchantStatus::Suspended;
+
+    emit!(MerchantStatusChangedEvent {
+        merchant: merchant.key(),
+        merchant_authority: merchant.authority,
+        authority: ctx.accounts.authority.key(),
+        old_status: MerchantStatus::Approved,
+        new_status: MerchantStatus::Suspended,
+        fee_refunded: 0,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Merchant '{}' suspended", merchant.name);
+
+    Ok(())
+}
@@ -1,15 +1,21 @@
 use anchor_lang::prelude::*;
-use crate::{Merchant, RedemptionOffer, SolcityError};
+use crate::{LoyaltyProgram, Merchant, RedemptionOffer, SolcityError, PAUSE_MERCHANT_OPS};
 
 #[derive(Accounts)]
 pub struct ToggleRedemptionOffer<'info> {
     pub merchant_authority: Signer<'info>,
 
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+
     #[account(
         seeds = [
             Merchant::SEED_PREFIX,
             merchant_authority.key().as_ref(),
-            merchant.loyalty_program.as_ref()
+            loyalty_program.key().as_ref()
         ],
         bump = merchant.bump,
     )]
@@ -29,6 +35,11 @@ pub struct ToggleRedemptionOffer<'info> {
 }
 
 pub fn handler(ctx: Context<ToggleRedemptionOffer>) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
     let offer = &mut ctx.accounts.redemption_offer;
     offer.is_active = !offer.is_active;
 
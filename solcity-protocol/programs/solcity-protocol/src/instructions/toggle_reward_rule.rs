@@ -1,4 +1,4 @@
-use crate::{LoyaltyProgram, Merchant, RewardRule, SolcityError};
+use crate::{LoyaltyProgram, Merchant, RewardRule, SolcityError, PAUSE_MERCHANT_OPS};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -8,10 +8,7 @@ pub struct ToggleRewardRule<'info> {
     pub merchant_authority: Signer<'info>,
 
     #[account(
-        seeds = [
-            LoyaltyProgram::SEED_PREFIX,
-            merchant_authority.key().as_ref()
-        ],
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
         bump = loyalty_program.bump,
     )]
     pub loyalty_program: Account<'info, LoyaltyProgram>,
@@ -40,6 +37,11 @@ pub struct ToggleRewardRule<'info> {
 }
 
 pub fn handler(ctx: Context<ToggleRewardRule>, _rule_id: u64, is_active: bool) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
     let reward_rule = &mut ctx.accounts.reward_rule;
     reward_rule.is_active = is_active;
 
@@ -0,0 +1,74 @@
+use crate::{InterestRateUpdatedEvent, LoyaltyProgram, SolcityError, MAX_INTEREST_RATE};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_2022_extensions::{
+    interest_bearing_mint_update_rate, InterestBearingMintUpdateRate,
+};
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct UpdateInterestRate<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+        has_one = mint @ SolcityError::InvalidMint,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+
+    /// Interest-bearing loyalty mint (rate authority is the loyalty_program PDA)
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+pub fn handler(ctx: Context<UpdateInterestRate>, rate: i16) -> Result<()> {
+    require!(
+        (0..=MAX_INTEREST_RATE).contains(&rate),
+        SolcityError::InvalidInterestRate
+    );
+
+    let loyalty_program = &ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    let program_seeds = &[
+        LoyaltyProgram::SEED_PREFIX,
+        loyalty_program.seed_authority.as_ref(),
+        &[loyalty_program.bump],
+    ];
+    let signer_seeds = &[&program_seeds[..]];
+
+    interest_bearing_mint_update_rate(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            InterestBearingMintUpdateRate {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                rate_authority: loyalty_program.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        rate,
+    )?;
+
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let old_rate = loyalty_program.interest_rate;
+    loyalty_program.interest_rate = rate;
+
+    emit!(InterestRateUpdatedEvent {
+        loyalty_program: loyalty_program.key(),
+        mint: ctx.accounts.mint.key(),
+        authority: ctx.accounts.authority.key(),
+        old_rate,
+        new_rate: rate,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Interest rate updated to: {}% APY", rate as f64 / 100.0);
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::{Merchant, SolcityError};
+use crate::{LoyaltyProgram, Merchant, SolcityError, PAUSE_MERCHANT_OPS};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -6,12 +6,18 @@ pub struct UpdateMerchant<'info> {
     #[account(mut)]
     pub merchant_authority: Signer<'info>,
 
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+
     #[account(
         mut,
         seeds = [
             Merchant::SEED_PREFIX,
             merchant_authority.key().as_ref(),
-            merchant.loyalty_program.as_ref()
+            loyalty_program.key().as_ref()
         ],
         bump = merchant.bump,
     )]
@@ -24,8 +30,12 @@ pub fn handler(
     description: Option<String>,
     avatar_url: Option<String>,
     category: Option<String>,
-    is_active: Option<bool>,
 ) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
     let merchant = &mut ctx.accounts.merchant;
 
     if let Some(rate) = new_reward_rate {
@@ -53,10 +63,5 @@ pub fn handler(
         msg!("Category updated");
     }
 
-    if let Some(active) = is_active {
-        merchant.is_active = active;
-        msg!("Merchant active status: {}", active);
-    }
-
     Ok(())
 }
@@ -0,0 +1,60 @@
+use crate::{LoyaltyProgram, ProgramConfigUpdatedEvent, SolcityError};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateProgramConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateProgramConfig>,
+    merchant_registration_fee: Option<u64>,
+    issuance_fee_per_token: Option<u64>,
+    points_expiry_period: Option<i64>,
+) -> Result<()> {
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    let old_merchant_registration_fee = loyalty_program.merchant_registration_fee;
+    let old_issuance_fee_per_token = loyalty_program.issuance_fee_per_token;
+    let old_points_expiry_period = loyalty_program.points_expiry_period;
+
+    if let Some(fee) = merchant_registration_fee {
+        loyalty_program.merchant_registration_fee = fee;
+        msg!("Merchant registration fee updated to: {}", fee);
+    }
+
+    if let Some(fee) = issuance_fee_per_token {
+        loyalty_program.issuance_fee_per_token = fee;
+        msg!("Issuance fee updated to: {} per token", fee);
+    }
+
+    // Only affects points earned from now on; existing lots keep their expiry
+    if let Some(period) = points_expiry_period {
+        require!(period >= 0, SolcityError::InvalidExpiryPeriod);
+        loyalty_program.points_expiry_period = period;
+        msg!("Points expiry period updated to: {} seconds", period);
+    }
+
+    emit!(ProgramConfigUpdatedEvent {
+        loyalty_program: loyalty_program.key(),
+        authority: ctx.accounts.authority.key(),
+        old_merchant_registration_fee,
+        new_merchant_registration_fee: loyalty_program.merchant_registration_fee,
+        old_issuance_fee_per_token,
+        new_issuance_fee_per_token: loyalty_program.issuance_fee_per_token,
+        old_points_expiry_period,
+        new_points_expiry_period: loyalty_program.points_expiry_period,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,16 +1,24 @@
 use anchor_lang::prelude::*;
-use crate::{Merchant, RedemptionOffer, RedemptionType, SolcityError};
+use crate::{
+    LoyaltyProgram, Merchant, RedemptionOffer, RedemptionType, SolcityError, PAUSE_MERCHANT_OPS,
+};
 
 #[derive(Accounts)]
 pub struct UpdateRedemptionOffer<'info> {
     #[account(mut)]
     pub merchant_authority: Signer<'info>,
 
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+
     #[account(
         seeds = [
             Merchant::SEED_PREFIX,
             merchant_authority.key().as_ref(),
-            merchant.loyalty_program.as_ref()
+            loyalty_program.key().as_ref()
         ],
         bump = merchant.bump,
     )]
@@ -38,6 +46,11 @@ pub fn handler(
     quantity_limit: Option<Option<u64>>,
     expiration: Option<Option<i64>>,
 ) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
     let offer = &mut ctx.accounts.redemption_offer;
 
     if let Some(desc) = description {
@@ -1,4 +1,6 @@
-use crate::{LoyaltyProgram, Merchant, RewardRule, RuleType, SolcityError};
+use crate::{
+    LoyaltyProgram, Merchant, RewardKind, RewardRule, RuleType, SolcityError, PAUSE_MERCHANT_OPS,
+};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -8,10 +10,7 @@ pub struct UpdateRewardRule<'info> {
     pub merchant_authority: Signer<'info>,
 
     #[account(
-        seeds = [
-            LoyaltyProgram::SEED_PREFIX,
-            merchant_authority.key().as_ref()
-        ],
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
         bump = loyalty_program.bump,
     )]
     pub loyalty_program: Account<'info, LoyaltyProgram>,
@@ -48,7 +47,17 @@ pub fn handler(
     min_purchase: Option<u64>,
     start_time: Option<i64>,
     end_time: Option<i64>,
+    min_streak_days: Option<u16>,
+    eligible_tiers: Option<u8>,
+    reward_kind: Option<RewardKind>,
+    flat_bonus: Option<u64>,
+    max_bonus: Option<u64>,
 ) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_MERCHANT_OPS),
+        SolcityError::ProgramPaused
+    );
+
     let reward_rule = &mut ctx.accounts.reward_rule;
 
     if let Some(new_name) = name {
@@ -62,7 +71,6 @@ pub fn handler(
     }
 
     if let Some(new_multiplier) = multiplier {
-        require!(new_multiplier >= 100, SolcityError::InvalidRewardAmount);
         reward_rule.multiplier = new_multiplier;
     }
 
@@ -84,6 +92,38 @@ pub fn handler(
         reward_rule.end_time = new_end_time;
     }
 
+    if let Some(new_min_streak_days) = min_streak_days {
+        reward_rule.min_streak_days = new_min_streak_days;
+    }
+
+    if let Some(new_eligible_tiers) = eligible_tiers {
+        reward_rule.eligible_tiers = new_eligible_tiers;
+    }
+
+    if let Some(new_reward_kind) = reward_kind {
+        reward_rule.reward_kind = new_reward_kind;
+    }
+
+    if let Some(new_flat_bonus) = flat_bonus {
+        reward_rule.flat_bonus = new_flat_bonus;
+    }
+
+    if let Some(new_max_bonus) = max_bonus {
+        reward_rule.max_bonus = new_max_bonus;
+    }
+
+    // The type and its parameters may change independently; check the resulting combination
+    RewardRule::validate_params(
+        &reward_rule.rule_type,
+        reward_rule.min_streak_days,
+        reward_rule.eligible_tiers,
+    )?;
+    RewardRule::validate_reward(
+        reward_rule.reward_kind,
+        reward_rule.multiplier,
+        reward_rule.flat_bonus,
+    )?;
+
     msg!("Reward rule updated: {}", reward_rule.name);
 
     Ok(())
@@ -0,0 +1,138 @@
+use crate::mint::read_token_metadata;
+use crate::{
+    LoyaltyProgram, SolcityError, TokenMetadataUpdatedEvent, MAX_TOKEN_SYMBOL_LENGTH,
+    MAX_TOKEN_URI_LENGTH,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_2022_extensions::spl_token_metadata_interface::state::Field;
+use anchor_spl::token_2022_extensions::{token_metadata_update_field, TokenMetadataUpdateField};
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct UpdateTokenMetadata<'info> {
+    /// Program authority (pays rent if the metadata grows)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Program account; its name always mirrors the metadata name
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+        has_one = mint @ SolcityError::InvalidMint,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+
+    /// Loyalty mint holding the TokenMetadata extension
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateTokenMetadata>,
+    name: Option<String>,
+    symbol: Option<String>,
+    uri: Option<String>,
+) -> Result<()> {
+    let mut updates = Vec::new();
+
+    if let Some(name) = name {
+        require!(!name.is_empty(), SolcityError::NameEmpty);
+        require!(name.len() <= 32, SolcityError::NameTooLong);
+        updates.push((Field::Name, name));
+    }
+
+    if let Some(symbol) = symbol {
+        require!(
+            !symbol.is_empty() && symbol.len() <= MAX_TOKEN_SYMBOL_LENGTH,
+            SolcityError::InvalidTokenMetadata
+        );
+        updates.push((Field::Symbol, symbol));
+    }
+
+    if let Some(uri) = uri {
+        require!(
+            uri.len() <= MAX_TOKEN_URI_LENGTH,
+            SolcityError::InvalidTokenMetadata
+        );
+        updates.push((Field::Uri, uri));
+    }
+
+    let mint_info = ctx.accounts.mint.to_account_info();
+    let mut metadata = read_token_metadata(&mint_info)?;
+    let old_metadata_len = metadata.tlv_size_of()?;
+    for (field, value) in updates.iter() {
+        metadata.update(field.clone(), value.clone());
+    }
+
+    // Token-2022 reallocs the mint for longer fields; top up rent beforehand
+    let new_len = mint_info.data_len() - old_metadata_len + metadata.tlv_size_of()?;
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(mint_info.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: mint_info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    let loyalty_program = &ctx.accounts.loyalty_program;
+    let program_seeds = &[
+        LoyaltyProgram::SEED_PREFIX,
+        loyalty_program.seed_authority.as_ref(),
+        &[loyalty_program.bump],
+    ];
+    let signer_seeds = &[&program_seeds[..]];
+
+    for (field, value) in updates {
+        token_metadata_update_field(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TokenMetadataUpdateField {
+                    program_id: ctx.accounts.token_program.to_account_info(),
+                    metadata: mint_info.clone(),
+                    update_authority: loyalty_program.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            field,
+            value,
+        )?;
+    }
+
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    loyalty_program.name = metadata.name.clone();
+
+    let clock = Clock::get()?;
+
+    emit!(TokenMetadataUpdatedEvent {
+        loyalty_program: loyalty_program.key(),
+        mint: mint_info.key(),
+        authority: ctx.accounts.authority.key(),
+        name: metadata.name.clone(),
+        symbol: metadata.symbol.clone(),
+        uri: metadata.uri.clone(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Token metadata updated: {} ({})",
+        metadata.name,
+        metadata.symbol
+    );
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use crate::{
-    Merchant, OfferRedemptionRecord, RedemptionVoucher, VoucherUsedEvent, SolcityError
+    LoyaltyProgram, Merchant, OfferRedemptionRecord, RedemptionVoucher, VoucherUsedEvent,
+    SolcityError, PAUSE_VOUCHER_USE,
 };
 use anchor_lang::prelude::*;
 
@@ -17,15 +18,22 @@ pub struct UpdateVoucherStatus<'info> {
     #[account(mut)]
     pub merchant_authority: Signer<'info>,
 
+    #[account(
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+    )]
+    pub loyalty_program: Box<Account<'info, LoyaltyProgram>>,
+
     /// Merchant account - must match the voucher's merchant
     #[account(
         seeds = [
             Merchant::SEED_PREFIX,
             merchant_authority.key().as_ref(),
-            merchant.loyalty_program.as_ref()
+            loyalty_program.key().as_ref()
         ],
         bump = merchant.bump,
         constraint = merchant.authority == merchant_authority.key() @ SolcityError::UnauthorizedAccess,
+        constraint = merchant.is_approved() @ SolcityError::MerchantNotActive,
     )]
     pub merchant: Box<Account<'info, Merchant>>,
 
@@ -45,6 +53,11 @@ pub struct UpdateVoucherStatus<'info> {
 }
 
 pub fn handler(ctx: Context<UpdateVoucherStatus>, status: VoucherStatus) -> Result<()> {
+    require!(
+        !ctx.accounts.loyalty_program.is_paused(PAUSE_VOUCHER_USE),
+        SolcityError::ProgramPaused
+    );
+
     let clock = Clock::get()?;
     let voucher = &mut ctx.accounts.voucher;
     let offer_redemption_record = &mut ctx.accounts.offer_redemption_record;
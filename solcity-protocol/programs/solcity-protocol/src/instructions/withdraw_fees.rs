@@ -0,0 +1,101 @@
+use crate::{FeesWithdrawnEvent, LoyaltyProgram, SolcityError};
+use crate::fees::{pay_from_treasury, FeeTokenAccounts};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::SEED_PREFIX, loyalty_program.seed_authority.as_ref()],
+        bump = loyalty_program.bump,
+        has_one = authority @ SolcityError::UnauthorizedAccess,
+    )]
+    pub loyalty_program: Account<'info, LoyaltyProgram>,
+
+    /// Platform treasury PDA holding collected fees
+    #[account(
+        mut,
+        seeds = [LoyaltyProgram::TREASURY_SEED_PREFIX, loyalty_program.key().as_ref()],
+        bump = loyalty_program.treasury_bump,
+    )]
+    pub platform_treasury: SystemAccount<'info>,
+
+    /// CHECK: Any account chosen by the authority to receive withdrawn lamport fees
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    /// Fee mint (required when fees are paid in an SPL token)
+    pub fee_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// Token account receiving the withdrawn fees (required when fees are paid in an SPL token)
+    #[account(mut)]
+    pub destination_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Treasury PDA token account holding fees (required when fees are paid in an SPL token)
+    #[account(mut)]
+    pub treasury_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub fee_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+    require!(amount > 0, SolcityError::InvalidRewardAmount);
+
+    let loyalty_program = &ctx.accounts.loyalty_program;
+    let clock = Clock::get()?;
+
+    // Never withdraw more than has been collected and not yet withdrawn or
+    // accrued to fee recipients
+    let unallocated = loyalty_program
+        .total_fees_collected
+        .checked_sub(loyalty_program.total_fees_withdrawn)
+        .ok_or(SolcityError::Overflow)?
+        .saturating_sub(loyalty_program.unclaimed_recipient_fees());
+    require!(amount <= unallocated, SolcityError::InsufficientTreasuryBalance);
+
+    let fee_tokens = FeeTokenAccounts::resolve(
+        &ctx.accounts.loyalty_program,
+        ctx.accounts.fee_mint.as_deref(),
+        ctx.accounts.destination_fee_account.as_deref(),
+        ctx.accounts.treasury_fee_account.as_deref(),
+        ctx.accounts.fee_token_program.as_ref(),
+    )?;
+
+    let destination = fee_tokens
+        .as_ref()
+        .map_or(ctx.accounts.destination.key(), |tokens| tokens.counterparty.key());
+
+    let loyalty_program_key = loyalty_program.key();
+    pay_from_treasury(
+        &ctx.accounts.loyalty_program,
+        amount,
+        &ctx.accounts.platform_treasury,
+        &ctx.accounts.destination.to_account_info(),
+        &ctx.accounts.system_program,
+        fee_tokens.as_ref(),
+    )?;
+
+    let loyalty_program = &mut ctx.accounts.loyalty_program;
+    loyalty_program.total_fees_withdrawn = loyalty_program
+        .total_fees_withdrawn
+        .checked_add(amount)
+        .ok_or(SolcityError::Overflow)?;
+
+    emit!(FeesWithdrawnEvent {
+        loyalty_program: loyalty_program_key,
+        authority: ctx.accounts.authority.key(),
+        destination,
+        amount,
+        total_fees_withdrawn: loyalty_program.total_fees_withdrawn,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Withdrew {} in fees", amount);
+
+    Ok(())
+}
@@ -1,9 +1,17 @@
+#![allow(clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
 
+pub mod collateral;
 pub mod constants;
 pub mod errors;
 pub mod events;
+pub mod fees;
 pub mod instructions;
+pub mod mint;
+pub mod pda;
+pub mod rules;
+pub mod settlement;
 pub mod state;
 
 pub use constants::*;
@@ -23,9 +31,134 @@ pub mod solcity_protocol {
     pub fn initialize_program(
         ctx: Context<InitializeProgram>,
         name: String,
+        symbol: String,
+        uri: String,
         interest_rate: Option<i16>,
+        transfer_mode: Option<TransferMode>,
+    ) -> Result<()> {
+        instructions::initialize_program::handler(
+            ctx,
+            name,
+            symbol,
+            uri,
+            interest_rate,
+            transfer_mode,
+        )
+    }
+
+    /// Update loyalty program fees and points expiry period (authority only)
+    pub fn update_program_config(
+        ctx: Context<UpdateProgramConfig>,
+        merchant_registration_fee: Option<u64>,
+        issuance_fee_per_token: Option<u64>,
+        points_expiry_period: Option<i64>,
+    ) -> Result<()> {
+        instructions::update_program_config::handler(
+            ctx,
+            merchant_registration_fee,
+            issuance_fee_per_token,
+            points_expiry_period,
+        )
+    }
+
+    /// Update the interest rate on the Token-2022 mint (authority only)
+    pub fn update_interest_rate(ctx: Context<UpdateInterestRate>, rate: i16) -> Result<()> {
+        instructions::update_interest_rate::handler(ctx, rate)
+    }
+
+    /// Update the mint's on-chain token metadata; the name also renames the program (authority only)
+    pub fn update_token_metadata(
+        ctx: Context<UpdateTokenMetadata>,
+        name: Option<String>,
+        symbol: Option<String>,
+        uri: Option<String>,
     ) -> Result<()> {
-        instructions::initialize_program::handler(ctx, name, interest_rate)
+        instructions::update_token_metadata::handler(ctx, name, symbol, uri)
+    }
+
+    /// Propose a new program authority (step 1 of 2)
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        instructions::propose_authority::handler(ctx, new_authority)
+    }
+
+    /// Accept a proposed program authority transfer (step 2 of 2)
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::accept_authority::handler(ctx)
+    }
+
+    /// Cancel a pending program authority transfer
+    pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
+        instructions::cancel_authority_transfer::handler(ctx)
+    }
+
+    /// Withdraw collected platform fees from the treasury PDA (authority only)
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        instructions::withdraw_fees::handler(ctx, amount)
+    }
+
+    /// Configure the fee revenue split across recipients (authority only)
+    pub fn set_fee_recipients(
+        ctx: Context<SetFeeRecipients>,
+        recipients: Vec<FeeRecipientShare>,
+    ) -> Result<()> {
+        instructions::set_fee_recipients::handler(ctx, recipients)
+    }
+
+    /// Claim fees accrued to the signing fee recipient
+    pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
+        instructions::claim_fees::handler(ctx)
+    }
+
+    /// Set or clear a merchant's negotiated issuance fee (authority only)
+    pub fn set_merchant_fee_override(
+        ctx: Context<SetMerchantFeeOverride>,
+        fee_per_token: Option<u64>,
+    ) -> Result<()> {
+        instructions::set_merchant_fee_override::handler(ctx, fee_per_token)
+    }
+
+    /// Cap a merchant's issuance below its own caps (authority only)
+    pub fn set_issuance_ceiling(
+        ctx: Context<SetIssuanceCeiling>,
+        ceiling: IssuanceCaps,
+    ) -> Result<()> {
+        instructions::set_issuance_ceiling::handler(ctx, ceiling)
+    }
+
+    /// Replace the volume-based issuance fee schedule (authority only)
+    pub fn set_volume_fee_tiers(
+        ctx: Context<SetVolumeFeeTiers>,
+        tiers: Vec<VolumeFeeTier>,
+    ) -> Result<()> {
+        instructions::set_volume_fee_tiers::handler(ctx, tiers)
+    }
+
+    /// Switch platform fees between lamports and an SPL token with fee amounts in the new
+    /// currency; merchant fee overrides lapse (authority only)
+    pub fn set_fee_currency(
+        ctx: Context<SetFeeCurrency>,
+        fee_mint: Option<Pubkey>,
+        merchant_registration_fee: u64,
+        issuance_fee_per_token: u64,
+        volume_fee_tiers: Vec<VolumeFeeTier>,
+    ) -> Result<()> {
+        instructions::set_fee_currency::handler(
+            ctx,
+            fee_mint,
+            merchant_registration_fee,
+            issuance_fee_per_token,
+            volume_fee_tiers,
+        )
+    }
+
+    /// Set paused operation classes (admin emergency switch)
+    pub fn set_pause_state(ctx: Context<SetPauseState>, paused: u8) -> Result<()> {
+        instructions::set_pause_state::handler(ctx, paused)
+    }
+
+    /// Replace the tier schedule (authority only)
+    pub fn set_tier_config(ctx: Context<SetTierConfig>, tiers: Vec<TierLevel>) -> Result<()> {
+        instructions::set_tier_config::handler(ctx, tiers)
     }
 
     /// Register a new merchant in the loyalty program
@@ -40,22 +173,115 @@ pub mod solcity_protocol {
         instructions::register_merchant::handler(ctx, name, avatar_url, category, description, reward_rate)
     }
 
-    /// Register a new customer in the loyalty program
+    /// Approve a pending or suspended merchant (authority only)
+    pub fn approve_merchant(ctx: Context<ApproveMerchant>) -> Result<()> {
+        instructions::approve_merchant::handler(ctx)
+    }
+
+    /// Reject a pending merchant and refund its registration fee (authority only)
+    pub fn reject_merchant(ctx: Context<RejectMerchant>) -> Result<()> {
+        instructions::reject_merchant::handler(ctx)
+    }
+
+    /// Suspend an approved merchant (authority only)
+    pub fn suspend_merchant(ctx: Context<SuspendMerchant>) -> Result<()> {
+        instructions::suspend_merchant::handler(ctx)
+    }
+
+    /// Send points to another registered customer
+    pub fn gift_points<'info>(
+        ctx: Context<'_, '_, '_, 'info, GiftPoints<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::gift_points::handler(ctx, amount)
+    }
+
+    /// Set the per-tier daily gifting limits (authority only)
+    pub fn set_gift_limits(ctx: Context<SetGiftLimits>, daily_limits: Vec<u64>) -> Result<()> {
+        instructions::set_gift_limits::handler(ctx, daily_limits)
+    }
+
+    /// Set the streak lengths that emit StreakMilestoneEvent (authority only)
+    pub fn set_streak_milestones(
+        ctx: Context<SetStreakMilestones>,
+        milestones: Vec<u16>,
+    ) -> Result<()> {
+        instructions::set_streak_milestones::handler(ctx, milestones)
+    }
+
+    /// Set the referral bonuses, qualifying purchase and per-referrer limit (authority only)
+    pub fn set_referral_config(
+        ctx: Context<SetReferralConfig>,
+        referrer_bonus: u64,
+        referee_bonus: u64,
+        referral_min_purchase: u64,
+        max_referrals_per_referrer: u32,
+    ) -> Result<()> {
+        instructions::set_referral_config::handler(
+            ctx,
+            referrer_bonus,
+            referee_bonus,
+            referral_min_purchase,
+            max_referrals_per_referrer,
+        )
+    }
+
+    /// Set the collateral value of one point and the collateral currency (authority only)
+    pub fn set_collateral_config(
+        ctx: Context<SetCollateralConfig>,
+        point_value: u64,
+        collateral_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_collateral_config::handler(ctx, point_value, collateral_mint)
+    }
+
+    /// Deposit collateral into the merchant escrow PDA
+    pub fn deposit_collateral(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
+        instructions::deposit_collateral::handler(ctx, amount)
+    }
+
+    /// Withdraw collateral above the value of the merchant's outstanding points
+    pub fn withdraw_collateral(ctx: Context<WithdrawCollateral>, amount: u64) -> Result<()> {
+        instructions::withdraw_collateral::handler(ctx, amount)
+    }
+
+    /// Pay the net amount owed between two merchants from escrow (permissionless)
+    pub fn settle(ctx: Context<Settle>) -> Result<()> {
+        instructions::settle::handler(ctx)
+    }
+
+    /// Freeze a customer's token account with a reason code (authority only)
+    pub fn freeze_customer(ctx: Context<FreezeCustomer>, reason: u8) -> Result<()> {
+        instructions::freeze_customer::handler(ctx, reason)
+    }
+
+    /// Thaw a frozen customer's token account (authority only)
+    pub fn thaw_customer(ctx: Context<ThawCustomer>) -> Result<()> {
+        instructions::thaw_customer::handler(ctx)
+    }
+
+    /// Register a new customer in the loyalty program (optionally with a referrer)
     pub fn register_customer(ctx: Context<RegisterCustomer>) -> Result<()> {
         instructions::register_customer::handler(ctx)
     }
 
     /// Issue reward tokens to a customer for a purchase
-    pub fn issue_rewards(ctx: Context<IssueRewards>, purchase_amount: u64, rule_id: Option<u64>) -> Result<()> {
-        instructions::issue_rewards::handler(ctx, purchase_amount, rule_id)
+    pub fn issue_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, IssueRewards<'info>>,
+        purchase_amount: u64,
+    ) -> Result<()> {
+        instructions::issue_rewards::handler(ctx, purchase_amount)
     }
 
     /// Redeem reward tokens for benefits
-    pub fn redeem_rewards(ctx: Context<RedeemRewards>, voucher_seed: u64) -> Result<()> {
+    pub fn redeem_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemRewards<'info>>,
+        voucher_seed: u64,
+    ) -> Result<()> {
         instructions::redeem_rewards::handler(ctx, voucher_seed)
     }
 
-    /// Create a new reward rule for a merchant
+    /// Create a new reward rule for a merchant (multiplier, flat bonus, or both, optionally capped)
     pub fn set_reward_rule(
         ctx: Context<SetRewardRule>,
         rule_id: u64,
@@ -65,6 +291,11 @@ pub mod solcity_protocol {
         min_purchase: u64,
         start_time: i64,
         end_time: i64,
+        min_streak_days: u16,
+        eligible_tiers: u8,
+        reward_kind: RewardKind,
+        flat_bonus: u64,
+        max_bonus: u64,
     ) -> Result<()> {
         instructions::set_reward_rule::handler(
             ctx,
@@ -75,6 +306,11 @@ pub mod solcity_protocol {
             min_purchase,
             start_time,
             end_time,
+            min_streak_days,
+            eligible_tiers,
+            reward_kind,
+            flat_bonus,
+            max_bonus,
         )
     }
 
@@ -88,6 +324,11 @@ pub mod solcity_protocol {
         min_purchase: Option<u64>,
         start_time: Option<i64>,
         end_time: Option<i64>,
+        min_streak_days: Option<u16>,
+        eligible_tiers: Option<u8>,
+        reward_kind: Option<RewardKind>,
+        flat_bonus: Option<u64>,
+        max_bonus: Option<u64>,
     ) -> Result<()> {
         instructions::update_reward_rule::handler(
             ctx,
@@ -98,6 +339,11 @@ pub mod solcity_protocol {
             min_purchase,
             start_time,
             end_time,
+            min_streak_days,
+            eligible_tiers,
+            reward_kind,
+            flat_bonus,
+            max_bonus,
         )
     }
 
@@ -110,6 +356,24 @@ pub mod solcity_protocol {
         instructions::toggle_reward_rule::handler(ctx, rule_id, is_active)
     }
 
+    /// Set a reward rule's priority and exclusive flag (used by RuleCombination::Priority)
+    pub fn set_reward_rule_priority(
+        ctx: Context<SetRewardRulePriority>,
+        rule_id: u64,
+        priority: u8,
+        exclusive: bool,
+    ) -> Result<()> {
+        instructions::set_reward_rule_priority::handler(ctx, rule_id, priority, exclusive)
+    }
+
+    /// Choose how the merchant's reward rules combine when several apply
+    pub fn set_rule_combination(
+        ctx: Context<SetRuleCombination>,
+        rule_combination: RuleCombination,
+    ) -> Result<()> {
+        instructions::set_rule_combination::handler(ctx, rule_combination)
+    }
+
     /// Delete a reward rule
     pub fn delete_reward_rule(ctx: Context<DeleteRewardRule>, rule_id: u64) -> Result<()> {
         instructions::delete_reward_rule::handler(ctx, rule_id)
@@ -122,7 +386,6 @@ pub mod solcity_protocol {
         description: Option<String>,
         avatar_url: Option<String>,
         category: Option<String>,
-        is_active: Option<bool>,
     ) -> Result<()> {
         instructions::update_merchant::handler(
             ctx,
@@ -130,10 +393,14 @@ pub mod solcity_protocol {
             description,
             avatar_url,
             category,
-            is_active,
         )
     }
 
+    /// Set the merchant's per-transaction, rolling daily and lifetime issuance caps
+    pub fn set_issuance_caps(ctx: Context<SetIssuanceCaps>, caps: IssuanceCaps) -> Result<()> {
+        instructions::set_issuance_caps::handler(ctx, caps)
+    }
+
     /// Create a new redemption offer
     pub fn create_redemption_offer(
         ctx: Context<CreateRedemptionOffer>,
@@ -197,4 +464,16 @@ pub mod solcity_protocol {
     pub fn use_voucher(ctx: Context<UpdateVoucherStatus>, status: VoucherStatus) -> Result<()> {
         instructions::use_voucher::handler(ctx, status)
     }
+
+    /// Burn a customer's expired point lots (permissionless crank)
+    pub fn expire_points<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExpirePoints<'info>>,
+    ) -> Result<()> {
+        instructions::expire_points::handler(ctx)
+    }
+
+    /// Migrate any Solcity account to the current layout version (permissionless)
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migrate_account::handler(ctx)
+    }
 }
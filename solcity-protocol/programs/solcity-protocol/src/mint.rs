@@ -0,0 +1,184 @@
+use crate::{TransferMode, TOKEN_DECIMALS};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_2022::{self, spl_token_2022};
+use anchor_spl::token_2022_extensions::spl_pod::optional_keys::OptionalNonZeroPubkey;
+use anchor_spl::token_2022_extensions::spl_token_metadata_interface::state::TokenMetadata;
+use anchor_spl::token_2022_extensions::{
+    interest_bearing_mint_initialize, metadata_pointer_initialize,
+    non_transferable_mint_initialize, permanent_delegate_initialize, token_metadata_initialize,
+    transfer_hook_initialize, InterestBearingMintInitialize, MetadataPointerInitialize,
+    NonTransferableMintInitialize, PermanentDelegateInitialize, TokenMetadataInitialize,
+    TransferHookInitialize,
+};
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+
+/// Accounts used to create the loyalty mint
+pub struct LoyaltyMintAccounts<'a, 'info> {
+    /// Pays for the mint account
+    pub payer: &'a AccountInfo<'info>,
+
+    /// Mint PDA ([b"mint", loyalty_program]), not yet created
+    pub mint: &'a AccountInfo<'info>,
+
+    /// Loyalty program PDA: mint, freeze, rate and metadata authority and permanent delegate
+    pub loyalty_program: &'a AccountInfo<'info>,
+
+    pub token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+/// Create the loyalty mint PDA with its Token-2022 extensions.
+/// Extensions must be initialized before the mint itself, so the account is created by hand
+/// instead of with Anchor's `mint::` constraints.
+pub fn create_loyalty_mint(
+    accounts: LoyaltyMintAccounts<'_, '_>,
+    mint_bump: u8,
+    loyalty_program_seeds: &[&[u8]],
+    interest_rate: i16,
+    transfer_mode: &TransferMode,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    let loyalty_program_key = accounts.loyalty_program.key();
+    let mint_seeds = &[b"mint".as_ref(), loyalty_program_key.as_ref(), &[mint_bump]];
+
+    let mut extensions = vec![
+        ExtensionType::InterestBearingConfig,
+        ExtensionType::MetadataPointer,
+        ExtensionType::PermanentDelegate,
+    ];
+    match transfer_mode {
+        TransferMode::Transferable => {}
+        TransferMode::Soulbound => extensions.push(ExtensionType::NonTransferable),
+        TransferMode::RegisteredCustomersOnly => extensions.push(ExtensionType::TransferHook),
+    }
+    let space =
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extensions)?;
+
+    // TokenMetadata is variable-length: Token-2022 reallocs for it, but the rent must already be there
+    let metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey::try_from(Some(loyalty_program_key))?,
+        mint: accounts.mint.key(),
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+        additional_metadata: Vec::new(),
+    };
+    let lamports = Rent::get()?.minimum_balance(space + metadata.tlv_size_of()?);
+
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            accounts.system_program.clone(),
+            system_program::CreateAccount {
+                from: accounts.payer.clone(),
+                to: accounts.mint.clone(),
+            },
+            &[&mint_seeds[..]],
+        ),
+        lamports,
+        space as u64,
+        accounts.token_program.key,
+    )?;
+
+    // Interest accrues on the UI amount only; raw balances never change
+    interest_bearing_mint_initialize(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            InterestBearingMintInitialize {
+                token_program_id: accounts.token_program.clone(),
+                mint: accounts.mint.clone(),
+            },
+        ),
+        Some(loyalty_program_key),
+        interest_rate,
+    )?;
+
+    // Metadata lives on the mint itself
+    metadata_pointer_initialize(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            MetadataPointerInitialize {
+                token_program_id: accounts.token_program.clone(),
+                mint: accounts.mint.clone(),
+            },
+        ),
+        Some(loyalty_program_key),
+        Some(accounts.mint.key()),
+    )?;
+
+    // Lets the expire_points crank burn expired points without the holder's signature
+    permanent_delegate_initialize(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            PermanentDelegateInitialize {
+                token_program_id: accounts.token_program.clone(),
+                mint: accounts.mint.clone(),
+            },
+        ),
+        &loyalty_program_key,
+    )?;
+
+    // Soulbound points can still be burned on redemption
+    if *transfer_mode == TransferMode::Soulbound {
+        non_transferable_mint_initialize(CpiContext::new(
+            accounts.token_program.clone(),
+            NonTransferableMintInitialize {
+                token_program_id: accounts.token_program.clone(),
+                mint: accounts.mint.clone(),
+            },
+        ))?;
+    }
+
+    // Every transfer is checked by the companion hook program
+    if *transfer_mode == TransferMode::RegisteredCustomersOnly {
+        transfer_hook_initialize(
+            CpiContext::new(
+                accounts.token_program.clone(),
+                TransferHookInitialize {
+                    token_program_id: accounts.token_program.clone(),
+                    mint: accounts.mint.clone(),
+                },
+            ),
+            Some(loyalty_program_key),
+            Some(solcity_transfer_hook::ID),
+        )?;
+    }
+
+    token_2022::initialize_mint2(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            token_2022::InitializeMint2 {
+                mint: accounts.mint.clone(),
+            },
+        ),
+        TOKEN_DECIMALS,
+        &loyalty_program_key,
+        Some(&loyalty_program_key),
+    )?;
+
+    token_metadata_initialize(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            TokenMetadataInitialize {
+                program_id: accounts.token_program.clone(),
+                metadata: accounts.mint.clone(),
+                update_authority: accounts.loyalty_program.clone(),
+                mint_authority: accounts.loyalty_program.clone(),
+                mint: accounts.mint.clone(),
+            },
+            &[loyalty_program_seeds],
+        ),
+        name,
+        symbol,
+        uri,
+    )
+}
+
+/// Read the TokenMetadata stored on the loyalty mint
+pub fn read_token_metadata(mint: &AccountInfo) -> Result<TokenMetadata> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(state.get_variable_len_extension::<TokenMetadata>()?)
+}
@@ -0,0 +1,147 @@
+use crate::{
+    RewardRule, RuleCombination, RuleEvaluation, SolcityError, MAX_RULES_PER_ISSUANCE,
+    PERCENTAGE_DIVISOR,
+};
+use anchor_lang::prelude::*;
+
+/// A reward rule account passed to issue_rewards
+pub struct LoadedRule {
+    pub address: Pubkey,
+    pub rule: RewardRule,
+}
+
+/// Deserialize the merchant's reward rules from the passed accounts.
+/// Errors on foreign, malformed or duplicate rules, or more than MAX_RULES_PER_ISSUANCE.
+pub fn load_rules<'a, 'info: 'a>(
+    merchant: Pubkey,
+    accounts: impl Iterator<Item = &'a AccountInfo<'info>>,
+) -> Result<Vec<LoadedRule>> {
+    let mut rules: Vec<LoadedRule> = Vec::new();
+    for account in accounts {
+        require!(
+            rules.len() < MAX_RULES_PER_ISSUANCE,
+            SolcityError::TooManyRewardRules
+        );
+        require!(
+            rules.iter().all(|loaded| loaded.address != account.key()),
+            SolcityError::DuplicateRewardRule
+        );
+        require_keys_eq!(*account.owner, crate::ID, SolcityError::InvalidRewardRule);
+
+        let rule = RewardRule::try_deserialize(&mut &account.try_borrow_data()?[..])
+            .map_err(|_| SolcityError::InvalidRewardRule)?;
+        require_keys_eq!(rule.merchant, merchant, SolcityError::InvalidRewardRule);
+
+        rules.push(LoadedRule {
+            address: account.key(),
+            rule,
+        });
+    }
+    Ok(rules)
+}
+
+/// Result of combining a purchase's reward rules
+pub struct RuleOutcome {
+    /// Combined multiplier of the applied rules (100 = 1x), before max_bonus caps
+    pub multiplier: u64,
+    /// Points the applied rules add to the reward, after caps
+    pub bonus: u64,
+    /// Every rule in evaluation order
+    pub evaluations: Vec<RuleEvaluation>,
+}
+
+/// Combine the eligible rules under `policy` for a tier-adjusted `reward`.
+/// Each rule adds its capped bonus (see RewardRule::bonus_for); multiplicative and priority
+/// rules compound on the reward including earlier bonuses, additive rules on `reward` alone.
+/// `rules` is left in evaluation order.
+pub fn combine_rules(
+    policy: RuleCombination,
+    rules: &mut [LoadedRule],
+    reward: u64,
+    eligible: impl Fn(&RewardRule) -> bool,
+) -> Result<RuleOutcome> {
+    if policy == RuleCombination::Priority {
+        // Stable sort keeps the passed order among equal priorities
+        rules.sort_by(|a, b| b.rule.priority.cmp(&a.rule.priority));
+    }
+
+    let mut evaluations: Vec<RuleEvaluation> = rules
+        .iter()
+        .map(|loaded| RuleEvaluation {
+            rule: loaded.address,
+            rule_id: loaded.rule.rule_id,
+            multiplier: loaded.rule.effective_multiplier(),
+            bonus: 0,
+            applied: eligible(&loaded.rule),
+        })
+        .collect();
+
+    let mut bonus: u64 = 0;
+    match policy {
+        RuleCombination::HighestOnly => {
+            // Keep only the first rule adding the most points
+            let mut best: Option<(usize, u64)> = None;
+            for (index, (evaluation, loaded)) in evaluations.iter().zip(rules.iter()).enumerate() {
+                if !evaluation.applied {
+                    continue;
+                }
+                let points = loaded.rule.bonus_for(reward)?;
+                if best.is_none_or(|(_, top)| points > top) {
+                    best = Some((index, points));
+                }
+            }
+            for (index, evaluation) in evaluations.iter_mut().enumerate() {
+                evaluation.applied = best.is_some_and(|(best_index, _)| best_index == index);
+            }
+            if let Some((index, points)) = best {
+                evaluations[index].bonus = points;
+                bonus = points;
+            }
+        }
+        RuleCombination::Additive => {
+            for (evaluation, loaded) in evaluations.iter_mut().zip(rules.iter()) {
+                if evaluation.applied {
+                    evaluation.bonus = loaded.rule.bonus_for(reward)?;
+                    bonus = bonus
+                        .checked_add(evaluation.bonus)
+                        .ok_or(SolcityError::Overflow)?;
+                }
+            }
+        }
+        RuleCombination::Multiplicative | RuleCombination::Priority => {
+            let mut stopped = false;
+            for (evaluation, loaded) in evaluations.iter_mut().zip(rules.iter()) {
+                if stopped {
+                    evaluation.applied = false;
+                } else if evaluation.applied {
+                    let running = reward.checked_add(bonus).ok_or(SolcityError::Overflow)?;
+                    evaluation.bonus = loaded.rule.bonus_for(running)?;
+                    bonus = bonus
+                        .checked_add(evaluation.bonus)
+                        .ok_or(SolcityError::Overflow)?;
+                    stopped = policy == RuleCombination::Priority && loaded.rule.exclusive;
+                }
+            }
+        }
+    }
+
+    let mut multiplier = PERCENTAGE_DIVISOR;
+    for evaluation in evaluations.iter().filter(|evaluation| evaluation.applied) {
+        multiplier = if policy == RuleCombination::Additive {
+            multiplier
+                .checked_add(evaluation.multiplier.saturating_sub(PERCENTAGE_DIVISOR))
+                .ok_or(SolcityError::Overflow)?
+        } else {
+            multiplier
+                .checked_mul(evaluation.multiplier)
+                .ok_or(SolcityError::Overflow)?
+                / PERCENTAGE_DIVISOR
+        };
+    }
+
+    Ok(RuleOutcome {
+        multiplier,
+        bonus,
+        evaluations,
+    })
+}
@@ -0,0 +1,122 @@
+use crate::pda::create_pda_account;
+use crate::{PointLot, SettlementLedger, SolcityError, CURRENT_ACCOUNT_VERSION};
+use anchor_lang::prelude::*;
+
+/// Record redeemed points against the merchants that issued them.
+/// Points issued by another merchant are owed to `redeeming_merchant`; the ledger PDA for
+/// each such pair must be passed in `ledgers` and is created (paid by `payer`) if missing.
+/// Returns the points that `redeeming_merchant` issued itself.
+pub fn record_redemption<'info>(
+    redeemed: &[PointLot],
+    redeeming_merchant: Pubkey,
+    loyalty_program: Pubkey,
+    ledgers: &[AccountInfo<'info>],
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<u64> {
+    // Total points per issuing merchant
+    let mut own_points: u64 = 0;
+    let mut owed_by_issuer: Vec<(Pubkey, u64)> = Vec::new();
+    for lot in redeemed {
+        // Protocol-issued points (e.g. referral bonuses) are owed by no merchant
+        if lot.merchant == Pubkey::default() {
+            continue;
+        }
+        if lot.merchant == redeeming_merchant {
+            own_points = own_points
+                .checked_add(lot.amount)
+                .ok_or(SolcityError::Overflow)?;
+            continue;
+        }
+        match owed_by_issuer
+            .iter_mut()
+            .find(|(issuer, _)| *issuer == lot.merchant)
+        {
+            Some((_, points)) => {
+                *points = points
+                    .checked_add(lot.amount)
+                    .ok_or(SolcityError::Overflow)?
+            }
+            None => owed_by_issuer.push((lot.merchant, lot.amount)),
+        }
+    }
+
+    for (issuer, points) in owed_by_issuer {
+        let (address, bump) = SettlementLedger::find_address(issuer, redeeming_merchant);
+        let ledger_info = ledgers
+            .iter()
+            .find(|account| account.key() == address)
+            .ok_or(SolcityError::MissingSettlementLedger)?;
+
+        if ledger_info.data_is_empty() {
+            create_ledger(
+                ledger_info,
+                issuer,
+                redeeming_merchant,
+                loyalty_program,
+                bump,
+                payer,
+                system_program,
+            )?;
+        }
+
+        require_keys_eq!(*ledger_info.owner, crate::ID, SolcityError::InvalidAccount);
+        let mut data = ledger_info.try_borrow_mut_data()?;
+        let mut ledger = SettlementLedger::try_deserialize(&mut &data[..])?;
+        ledger.record(issuer, points)?;
+        ledger.try_serialize(&mut &mut data[..])?;
+
+        msg!(
+            "{} points issued by {} owed to {}",
+            points,
+            issuer,
+            redeeming_merchant
+        );
+    }
+
+    Ok(own_points)
+}
+
+/// Allocate and initialize a ledger PDA for a merchant pair
+fn create_ledger<'info>(
+    ledger_info: &AccountInfo<'info>,
+    first: Pubkey,
+    second: Pubkey,
+    loyalty_program: Pubkey,
+    bump: u8,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let (merchant_a, merchant_b) = SettlementLedger::ordered_pair(first, second);
+    let ledger_seeds = &[
+        SettlementLedger::SEED_PREFIX,
+        merchant_a.as_ref(),
+        merchant_b.as_ref(),
+        &[bump],
+    ];
+    create_pda_account(
+        ledger_info,
+        8 + SettlementLedger::INIT_SPACE,
+        &[&ledger_seeds[..]],
+        payer,
+        system_program,
+    )?;
+
+    let ledger = SettlementLedger {
+        version: CURRENT_ACCOUNT_VERSION,
+        loyalty_program,
+        merchant_a,
+        merchant_b,
+        a_owes_b: 0,
+        b_owes_a: 0,
+        total_settled_points: 0,
+        total_settled_value: 0,
+        last_settled_at: 0,
+        bump,
+        reserved: [0; 32],
+    };
+    let mut data = ledger_info.try_borrow_mut_data()?;
+    ledger.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
@@ -1,8 +1,12 @@
+use crate::SECONDS_PER_DAY;
 use anchor_lang::prelude::*;
 
 #[account]
 #[derive(InitSpace)]
 pub struct Customer {
+    /// Account layout version (see CURRENT_ACCOUNT_VERSION)
+    pub version: u8,
+
     /// Customer wallet address
     pub wallet: Pubkey,
 
@@ -21,7 +25,7 @@ pub struct Customer {
     /// Number of transactions
     pub transaction_count: u64,
 
-    /// Consecutive days active
+    /// Consecutive UTC days with issuance or redemption activity
     pub streak_days: u16,
 
     /// Last activity timestamp
@@ -32,36 +36,107 @@ pub struct Customer {
 
     /// Registration timestamp
     pub joined_at: i64,
+
+    /// Expiry timestamp of the earliest expiring point lot (0 = nothing expiring)
+    pub next_expiry_at: i64,
+
+    /// Points that expire at next_expiry_at
+    pub next_expiry_amount: u64,
+
+    /// Lifetime points expired by the expire_points crank
+    pub total_expired: u64,
+
+    /// Token account frozen by the program authority (fraud handling)
+    pub is_frozen: bool,
+
+    /// Admin-defined reason code for the freeze (0 when not frozen)
+    pub freeze_reason: u8,
+
+    /// Day index (unix_timestamp / SECONDS_PER_DAY) that gifted_today applies to
+    pub gift_day: i64,
+
+    /// Points gifted during gift_day
+    pub gifted_today: u64,
+
+    /// Customer PDA that referred this customer (default = not referred)
+    pub referrer: Pubkey,
+
+    /// Longest streak_days ever reached
+    pub longest_streak: u16,
+
+    /// Referral bonuses are due on the next qualifying purchase
+    pub referral_pending: bool,
+
+    /// Customers registered with this customer as referrer
+    pub referral_count: u32,
+
+    /// Lifetime referral bonus points earned as a referrer
+    pub referral_earnings: u64,
+
+    /// Day index (unix_timestamp / SECONDS_PER_DAY) of the last activity counted in streak_days
+    pub streak_day: i64,
+
+    /// Reserved for future fields (new fields are carved out of this)
+    pub reserved: [u8; 31],
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
 pub enum CustomerTier {
-    Bronze,   // 0 - 999 lifetime tokens
-    Silver,   // 1,000 - 9,999
-    Gold,     // 10,000 - 49,999
-    Platinum, // 50,000+
+    Bronze,   // TierConfig index 0 (default 0 - 999 lifetime tokens)
+    Silver,   // TierConfig index 1 (default 1,000 - 9,999)
+    Gold,     // TierConfig index 2 (default 10,000 - 49,999)
+    Platinum, // TierConfig index 3 (default 50,000+)
 }
 
-impl Customer {
-    pub const SEED_PREFIX: &'static [u8] = b"customer";
+impl CustomerTier {
+    /// Position of this tier in the TierConfig schedule
+    pub fn index(&self) -> u8 {
+        match self {
+            CustomerTier::Bronze => 0,
+            CustomerTier::Silver => 1,
+            CustomerTier::Gold => 2,
+            CustomerTier::Platinum => 3,
+        }
+    }
 
-    /// Calculate tier based on total earned
-    pub fn calculate_tier(total_earned: u64) -> CustomerTier {
-        match total_earned {
-            0..=999 => CustomerTier::Bronze,
-            1_000..=9_999 => CustomerTier::Silver,
-            10_000..=49_999 => CustomerTier::Gold,
+    /// Tier at a TierConfig schedule position (clamped to Platinum)
+    pub fn from_index(index: usize) -> Self {
+        match index {
+            0 => CustomerTier::Bronze,
+            1 => CustomerTier::Silver,
+            2 => CustomerTier::Gold,
             _ => CustomerTier::Platinum,
         }
     }
+}
 
-    /// Get tier multiplier (percentage-based, 100 = 1x, divide by PERCENTAGE_DIVISOR)
-    pub fn get_tier_multiplier(&self) -> u64 {
-        match self.tier {
-            CustomerTier::Bronze => 100,   // 1.0x
-            CustomerTier::Silver => 125,   // 1.25x
-            CustomerTier::Gold => 150,     // 1.5x
-            CustomerTier::Platinum => 200, // 2.0x
-        }
+impl Customer {
+    pub const SEED_PREFIX: &'static [u8] = b"customer";
+
+    /// Update streak_days for qualifying activity at `now`, comparing UTC days with
+    /// streak_day (last_activity also moves on gifts, which do not count).
+    /// Returns true when the streak grew.
+    pub fn update_streak(&mut self, now: i64) -> bool {
+        let today = now.div_euclid(SECONDS_PER_DAY);
+        let last_day = self.streak_day;
+
+        let streak_days = if self.streak_days == 0 || today > last_day + 1 {
+            1 // First activity, or the streak was broken by a gap
+        } else if today == last_day + 1 {
+            self.streak_days.saturating_add(1)
+        } else {
+            self.streak_days // Same day
+        };
+
+        let grew = streak_days > self.streak_days;
+        self.streak_days = streak_days;
+        self.streak_day = today;
+        self.longest_streak = self.longest_streak.max(streak_days);
+        grew
+    }
+
+    /// Whether the customer registered through a referral
+    pub fn is_referred(&self) -> bool {
+        self.referrer != Pubkey::default()
     }
 }
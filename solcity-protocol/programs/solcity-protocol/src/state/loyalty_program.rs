@@ -1,14 +1,27 @@
+use crate::{Merchant, SolcityError, BASIS_POINTS, MAX_FEE_RECIPIENTS, MAX_VOLUME_FEE_TIERS};
 use anchor_lang::prelude::*;
 
 #[account]
 #[derive(InitSpace)]
 pub struct LoyaltyProgram {
+    /// Account layout version (see CURRENT_ACCOUNT_VERSION)
+    pub version: u8,
+
     /// Program authority (admin)
     pub authority: Pubkey,
 
-    /// Platform treasury for collecting fees
+    /// Original authority used in PDA derivation (never changes)
+    pub seed_authority: Pubkey,
+
+    /// Proposed new authority awaiting acceptance
+    pub pending_authority: Option<Pubkey>,
+
+    /// Program-owned treasury PDA collecting platform fees
     pub treasury: Pubkey,
 
+    /// Treasury PDA bump
+    pub treasury_bump: u8,
+
     /// SPL Token-2022 mint address
     pub mint: Pubkey,
 
@@ -28,19 +41,226 @@ pub struct LoyaltyProgram {
     /// Total tokens ever burned
     pub total_tokens_redeemed: u64,
 
-    /// Total fees collected (in lamports)
+    /// Total fees collected (in fee currency units)
     pub total_fees_collected: u64,
 
+    /// Total fees withdrawn from the treasury (in fee currency units)
+    pub total_fees_withdrawn: u64,
+
+    /// Registration fees held in the treasury for merchants awaiting approval
+    pub pending_registration_fees: u64,
+
     /// Interest rate in basis points (500 = 5%)
     pub interest_rate: i16,
 
+    /// Fee currency: None = lamports, Some = SPL / Token-2022 mint (fees in base units)
+    pub fee_mint: Option<Pubkey>,
+
+    /// Fee charged on merchant registration (in fee currency units)
+    pub merchant_registration_fee: u64,
+
+    /// Fee charged per token issued (in fee currency units)
+    pub issuance_fee_per_token: u64,
+
+    /// Discounted issuance fees by merchant volume (ascending by min_total_issued)
+    #[max_len(MAX_VOLUME_FEE_TIERS)]
+    pub volume_fee_tiers: Vec<VolumeFeeTier>,
+
+    /// Fee revenue split (empty = all fees withdrawable by the authority)
+    #[max_len(MAX_FEE_RECIPIENTS)]
+    pub fee_recipients: Vec<FeeRecipient>,
+
+    /// Paused operation classes (bitmask of PAUSE_* flags)
+    pub paused: u8,
+
     /// PDA bump
     pub bump: u8,
 
     /// Creation timestamp
     pub created_at: i64,
+
+    /// Point transfer restrictions, fixed at creation
+    pub transfer_mode: TransferMode,
+
+    /// Seconds until earned points expire (0 = points never expire)
+    pub points_expiry_period: i64,
+
+    /// Total tokens ever burned by the expire_points crank
+    pub total_tokens_expired: u64,
+
+    /// Max points a customer may gift per day, indexed by tier (0 = unlimited)
+    pub gift_daily_limits: [u64; 4],
+
+    /// Collateral value of one point: backs issuance and settles coalition redemptions (0 = not required)
+    pub point_value: u64,
+
+    /// Collateral currency: None = lamports, Some = SPL / Token-2022 mint (in base units)
+    pub collateral_mint: Option<Pubkey>,
+
+    /// Collateral held across all merchant escrows (in collateral currency units)
+    pub total_collateral: u64,
+
+    /// Streak lengths (in days) that emit StreakMilestoneEvent, ascending (0 = unused slot)
+    pub streak_milestones: [u16; 4],
+
+    /// Bonus points minted to the referrer on the referee's first qualifying purchase
+    pub referrer_bonus: u64,
+
+    /// Bonus points minted to the referee on their first qualifying purchase
+    pub referee_bonus: u64,
+
+    /// Minimum purchase (in cents) that qualifies a referee for the referral bonuses
+    pub referral_min_purchase: u64,
+
+    /// Maximum customers a single referrer may refer
+    pub max_referrals_per_referrer: u32,
+
+    /// Bumped on every fee currency switch; merchant fee overrides from earlier epochs are void
+    pub fee_currency_epoch: u32,
+
+    /// Reserved for future fields (new fields are carved out of this)
+    pub reserved: [u8; 118],
+}
+
+/// A fee revenue recipient and its accrued, unclaimed balance
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct FeeRecipient {
+    /// Wallet allowed to claim this share
+    pub recipient: Pubkey,
+
+    /// Share of every fee in basis points
+    pub share_bps: u16,
+
+    /// Accrued fees not yet claimed (in fee currency units)
+    pub accrued: u64,
+
+    /// Lifetime fees claimed (in fee currency units)
+    pub claimed: u64,
+}
+
+/// Issuance fee that applies once a merchant has issued at least min_total_issued tokens
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct VolumeFeeTier {
+    /// Merchant lifetime issuance required for this rate
+    pub min_total_issued: u64,
+
+    /// Fee charged per token issued (in fee currency units)
+    pub fee_per_token: u64,
+}
+
+/// How loyalty points may move between wallets
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub enum TransferMode {
+    Transferable,            // Plain Token-2022 transfers
+    Soulbound,               // NonTransferable extension: points can only be minted and burned
+    RegisteredCustomersOnly, // TransferHook extension: destination must be a registered Customer
+}
+
+/// Which rule determined a merchant's issuance fee
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub enum FeeRule {
+    Base,                      // LoyaltyProgram.issuance_fee_per_token
+    VolumeTier { index: u8 },  // LoyaltyProgram.volume_fee_tiers[index]
+    MerchantOverride,          // Merchant.issuance_fee_override
 }
 
 impl LoyaltyProgram {
     pub const SEED_PREFIX: &'static [u8] = b"loyalty_program";
+    pub const TREASURY_SEED_PREFIX: &'static [u8] = b"treasury";
+
+    /// Whether a streak length is a configured milestone
+    pub fn is_streak_milestone(&self, streak_days: u16) -> bool {
+        streak_days > 0 && self.streak_milestones.contains(&streak_days)
+    }
+
+    /// Check if an operation class is paused
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.paused & flag != 0
+    }
+
+    /// Record a collected fee and accrue it across fee recipients.
+    /// Returns each recipient's share; rounding dust goes to the first recipient.
+    pub fn record_fee(&mut self, fee: u64) -> Result<Vec<(Pubkey, u64)>> {
+        self.total_fees_collected = self
+            .total_fees_collected
+            .checked_add(fee)
+            .ok_or(SolcityError::Overflow)?;
+
+        let mut distributions = Vec::with_capacity(self.fee_recipients.len());
+        let mut distributed = 0u64;
+        for fee_recipient in self.fee_recipients.iter() {
+            let share = (fee as u128)
+                .checked_mul(fee_recipient.share_bps as u128)
+                .ok_or(SolcityError::Overflow)?
+                .checked_div(BASIS_POINTS as u128)
+                .ok_or(SolcityError::Overflow)? as u64;
+            distributed = distributed.checked_add(share).ok_or(SolcityError::Overflow)?;
+            distributions.push((fee_recipient.recipient, share));
+        }
+        if let Some(first) = distributions.first_mut() {
+            first.1 = first
+                .1
+                .checked_add(fee.saturating_sub(distributed))
+                .ok_or(SolcityError::Overflow)?;
+        }
+
+        for (fee_recipient, (_, share)) in self.fee_recipients.iter_mut().zip(distributions.iter()) {
+            fee_recipient.accrued = fee_recipient
+                .accrued
+                .checked_add(*share)
+                .ok_or(SolcityError::Overflow)?;
+        }
+
+        Ok(distributions)
+    }
+
+    /// Issuance fee per token for a merchant and the rule that produced it.
+    /// A merchant override set in the current fee currency wins, then the highest volume
+    /// tier reached, then the base fee.
+    pub fn effective_issuance_fee(&self, merchant: &Merchant) -> (u64, FeeRule) {
+        if let Some(fee) = self.merchant_fee_override(merchant) {
+            return (fee, FeeRule::MerchantOverride);
+        }
+
+        match self
+            .volume_fee_tiers
+            .iter()
+            .rposition(|tier| merchant.total_issued >= tier.min_total_issued)
+        {
+            Some(index) => (
+                self.volume_fee_tiers[index].fee_per_token,
+                FeeRule::VolumeTier { index: index as u8 },
+            ),
+            None => (self.issuance_fee_per_token, FeeRule::Base),
+        }
+    }
+
+    /// A merchant's issuance fee override, ignoring one set before the last fee currency switch
+    pub fn merchant_fee_override(&self, merchant: &Merchant) -> Option<u64> {
+        merchant
+            .issuance_fee_override
+            .filter(|_| merchant.fee_override_epoch == self.fee_currency_epoch)
+    }
+
+    /// Check a volume fee schedule: at most MAX_VOLUME_FEE_TIERS, strictly ascending thresholds
+    pub fn validate_volume_fee_tiers(tiers: &[VolumeFeeTier]) -> Result<()> {
+        require!(
+            tiers.len() <= MAX_VOLUME_FEE_TIERS,
+            SolcityError::InvalidVolumeFeeTiers
+        );
+        require!(
+            tiers
+                .windows(2)
+                .all(|pair| pair[1].min_total_issued > pair[0].min_total_issued),
+            SolcityError::InvalidVolumeFeeTiers
+        );
+        Ok(())
+    }
+
+    /// Fees accrued to recipients that have not been claimed yet
+    pub fn unclaimed_recipient_fees(&self) -> u64 {
+        self.fee_recipients
+            .iter()
+            .fold(0u64, |total, r| total.saturating_add(r.accrued))
+    }
 }
@@ -1,8 +1,12 @@
+use crate::{RuleCombination, SolcityError, SECONDS_PER_DAY};
 use anchor_lang::prelude::*;
 
 #[account]
 #[derive(InitSpace)]
 pub struct Merchant {
+    /// Account layout version (see CURRENT_ACCOUNT_VERSION)
+    pub version: u8,
+
     /// Merchant wallet address
     pub authority: Pubkey,
 
@@ -34,16 +38,204 @@ pub struct Merchant {
     /// Total tokens redeemed at this merchant
     pub total_redeemed: u64,
 
-    /// Active status
-    pub is_active: bool,
+    /// Approval status (only Approved merchants can issue or redeem)
+    pub status: MerchantStatus,
+
+    /// Registration fee held until approval (refunded on rejection)
+    pub registration_fee_paid: u64,
+
+    /// Negotiated issuance fee per token set by the program authority (in fee currency units)
+    pub issuance_fee_override: Option<u64>,
 
     /// PDA bump
     pub bump: u8,
 
     /// Registration timestamp
     pub created_at: i64,
+
+    /// Collateral held in the merchant escrow PDA (in collateral currency units)
+    pub collateral_balance: u64,
+
+    /// Points issued by this merchant not yet redeemed, expired or settled (backed by collateral)
+    pub outstanding_points: u64,
+
+    /// Issuance caps chosen by the merchant
+    pub issuance_caps: IssuanceCaps,
+
+    /// Issuance ceiling set by the program authority (the tighter of the two applies)
+    pub issuance_ceiling: IssuanceCaps,
+
+    /// Start of the current 24h issuance window (aligned to the day)
+    pub issuance_window_start: i64,
+
+    /// Tokens issued in the current 24h window
+    pub issued_in_window: u64,
+
+    /// Tokens issued in the previous 24h window
+    pub issued_in_prev_window: u64,
+
+    /// How reward rules combine when several apply to one purchase
+    pub rule_combination: RuleCombination,
+
+    /// LoyaltyProgram.fee_currency_epoch when issuance_fee_override was set
+    pub fee_override_epoch: u32,
+
+    /// Reserved for future fields (new fields are carved out of this)
+    pub reserved: [u8; 35],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub enum MerchantStatus {
+    Pending,   // Awaiting admin review
+    Approved,  // Can issue and redeem rewards
+    Suspended, // Temporarily blocked by admin
+    Rejected,  // Registration declined, fee refunded
+}
+
+/// Issuance limits in tokens (0 = unlimited)
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace, Debug,
+)]
+pub struct IssuanceCaps {
+    /// Maximum tokens per issue_rewards call
+    pub per_transaction: u64,
+
+    /// Maximum tokens per rolling 24h window
+    pub per_day: u64,
+
+    /// Maximum lifetime tokens (compared against total_issued)
+    pub lifetime: u64,
+}
+
+impl IssuanceCaps {
+    /// Combine two cap sets, keeping the tighter non-zero limit of each
+    pub fn tightest(&self, other: &IssuanceCaps) -> IssuanceCaps {
+        let min_limit = |a: u64, b: u64| match (a, b) {
+            (0, limit) | (limit, 0) => limit,
+            (a, b) => a.min(b),
+        };
+        IssuanceCaps {
+            per_transaction: min_limit(self.per_transaction, other.per_transaction),
+            per_day: min_limit(self.per_day, other.per_day),
+            lifetime: min_limit(self.lifetime, other.lifetime),
+        }
+    }
+}
+
+/// Which issuance cap an alert or limit refers to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum IssuanceCapKind {
+    PerTransaction,
+    Daily,
+    Lifetime,
+}
+
+/// Cap usage before and after an issuance
+#[derive(Clone, Copy, Debug)]
+pub struct IssuanceCapUsage {
+    pub kind: IssuanceCapKind,
+    pub cap: u64,
+    pub before: u64,
+    pub after: u64,
+}
+
+impl IssuanceCapUsage {
+    /// Whether this issuance moved usage across `percent` of the cap
+    pub fn crossed(&self, percent: u64) -> bool {
+        let threshold = self.cap as u128 * percent as u128;
+        (self.before as u128 * 100) < threshold && (self.after as u128 * 100) >= threshold
+    }
 }
 
 impl Merchant {
     pub const SEED_PREFIX: &'static [u8] = b"merchant";
+    pub const ESCROW_SEED_PREFIX: &'static [u8] = b"merchant_escrow";
+
+    /// Check if merchant is approved to operate
+    pub fn is_approved(&self) -> bool {
+        self.status == MerchantStatus::Approved
+    }
+
+    /// Collateral above the value of outstanding points
+    pub fn free_collateral(&self, point_value: u64) -> u64 {
+        self.collateral_balance
+            .saturating_sub(self.outstanding_points.saturating_mul(point_value))
+    }
+
+    /// Release liability for points that left circulation
+    pub fn release_points(&mut self, points: u64) {
+        self.outstanding_points = self.outstanding_points.saturating_sub(points);
+    }
+
+    /// Caps in force: the tighter of the merchant's own caps and the authority's ceiling
+    pub fn effective_issuance_caps(&self) -> IssuanceCaps {
+        self.issuance_caps.tightest(&self.issuance_ceiling)
+    }
+
+    /// Advance the issuance window to the day containing `now`
+    fn roll_issuance_window(&mut self, now: i64) {
+        let window_start = now - now.rem_euclid(SECONDS_PER_DAY);
+        if window_start == self.issuance_window_start {
+            return;
+        }
+        self.issued_in_prev_window = if window_start - self.issuance_window_start == SECONDS_PER_DAY
+        {
+            self.issued_in_window
+        } else {
+            0
+        };
+        self.issued_in_window = 0;
+        self.issuance_window_start = window_start;
+    }
+
+    /// Tokens issued over the last 24h, weighting the previous window by its overlap
+    fn rolling_issued(&self, now: i64) -> u64 {
+        let elapsed = (now - self.issuance_window_start).clamp(0, SECONDS_PER_DAY) as u128;
+        let overlap = (SECONDS_PER_DAY as u128 - elapsed) * self.issued_in_prev_window as u128
+            / SECONDS_PER_DAY as u128;
+        (overlap as u64).saturating_add(self.issued_in_window)
+    }
+
+    /// Check `amount` against the effective caps and record it in the issuance window.
+    /// Returns the usage of each configured cap so callers can raise alerts.
+    pub fn record_issuance(&mut self, amount: u64, now: i64) -> Result<Vec<IssuanceCapUsage>> {
+        let caps = self.effective_issuance_caps();
+        self.roll_issuance_window(now);
+
+        let issued_today = self.rolling_issued(now);
+        let usage = [
+            (IssuanceCapKind::PerTransaction, caps.per_transaction, 0),
+            (IssuanceCapKind::Daily, caps.per_day, issued_today),
+            (IssuanceCapKind::Lifetime, caps.lifetime, self.total_issued),
+        ];
+
+        let mut usage_by_cap = Vec::new();
+        for (kind, cap, before) in usage.into_iter().filter(|(_, cap, _)| *cap > 0) {
+            let after = before.checked_add(amount).ok_or(SolcityError::Overflow)?;
+            if after > cap {
+                match kind {
+                    IssuanceCapKind::PerTransaction => {
+                        return err!(SolcityError::TransactionIssuanceCapExceeded)
+                    }
+                    IssuanceCapKind::Daily => return err!(SolcityError::DailyIssuanceCapExceeded),
+                    IssuanceCapKind::Lifetime => {
+                        return err!(SolcityError::IssuanceBudgetExhausted)
+                    }
+                }
+            }
+            usage_by_cap.push(IssuanceCapUsage {
+                kind,
+                cap,
+                before,
+                after,
+            });
+        }
+
+        self.issued_in_window = self
+            .issued_in_window
+            .checked_add(amount)
+            .ok_or(SolcityError::Overflow)?;
+
+        Ok(usage_by_cap)
+    }
 }
@@ -5,6 +5,9 @@ use anchor_lang::prelude::*;
 #[account]
 #[derive(InitSpace)]
 pub struct MerchantCustomerRecord {
+    /// Account layout version (see CURRENT_ACCOUNT_VERSION)
+    pub version: u8,
+
     /// Merchant pubkey
     pub merchant: Pubkey,
 
@@ -28,11 +31,14 @@ pub struct MerchantCustomerRecord {
 
     /// PDA bump
     pub bump: u8,
+
+    /// Reserved for future fields (new fields are carved out of this)
+    pub reserved: [u8; 32],
 }
 
 impl MerchantCustomerRecord {
     pub const SEED_PREFIX: &'static [u8] = b"merchant_customer";
 
-    // Space: 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 = 113
-    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+    // Space: 8 + 1 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 32 = 146
+    pub const SPACE: usize = 8 + 1 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 32;
 }
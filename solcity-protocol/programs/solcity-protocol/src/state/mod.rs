@@ -3,17 +3,27 @@ pub mod loyalty_program;
 pub mod merchant;
 pub mod merchant_customer_record;
 pub mod offer_redemption_record;
+pub mod point_lots;
 pub mod redemption_offer;
 pub mod redemption_voucher;
+pub mod referral;
 pub mod reward_rule;
+pub mod settlement_ledger;
+pub mod tier_config;
 pub mod transaction_record;
+pub mod versioning;
 
 pub use customer::*;
 pub use loyalty_program::*;
 pub use merchant::*;
 pub use merchant_customer_record::*;
 pub use offer_redemption_record::*;
+pub use point_lots::*;
 pub use redemption_offer::*;
 pub use redemption_voucher::*;
+pub use referral::*;
 pub use reward_rule::*;
+pub use settlement_ledger::*;
+pub use tier_config::*;
 pub use transaction_record::*;
+pub use versioning::*;
@@ -5,6 +5,9 @@ use anchor_lang::prelude::*;
 #[account]
 #[derive(InitSpace)]
 pub struct OfferRedemptionRecord {
+    /// Account layout version (see CURRENT_ACCOUNT_VERSION)
+    pub version: u8,
+
     /// Redemption offer that was redeemed
     pub offer: Pubkey,
 
@@ -31,11 +34,14 @@ pub struct OfferRedemptionRecord {
 
     /// PDA bump
     pub bump: u8,
+
+    /// Reserved for future fields (new fields are carved out of this)
+    pub reserved: [u8; 32],
 }
 
 impl OfferRedemptionRecord {
     pub const SEED_PREFIX: &'static [u8] = b"offer_redemption";
 
-    // Space: 8 + 32 + 32 + 32 + 32 + 8 + 8 + 1 + 9 + 1 = 163
-    pub const SPACE: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 1 + 9 + 1;
+    // Space: 8 + 1 + 32 + 32 + 32 + 32 + 8 + 8 + 1 + 9 + 1 + 32 = 196
+    pub const SPACE: usize = 8 + 1 + 32 + 32 + 32 + 32 + 8 + 8 + 1 + 9 + 1 + 32;
 }
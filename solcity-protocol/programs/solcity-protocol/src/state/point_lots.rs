@@ -0,0 +1,174 @@
+use crate::{Customer, SolcityError, MAX_POINT_LOTS, SECONDS_PER_DAY};
+use anchor_lang::prelude::*;
+
+/// Earned points grouped by expiry day and issuing merchant, one account per customer
+/// Lots are kept ordered by expiry so redemptions and the expire crank consume the oldest first
+#[account]
+#[derive(InitSpace)]
+pub struct PointLots {
+    /// Account layout version (see CURRENT_ACCOUNT_VERSION)
+    pub version: u8,
+
+    /// Owning Customer PDA
+    pub customer: Pubkey,
+
+    /// Outstanding lots ordered by ascending expires_at
+    #[max_len(MAX_POINT_LOTS)]
+    pub lots: Vec<PointLot>,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Reserved for future fields (new fields are carved out of this)
+    pub reserved: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct PointLot {
+    /// Points remaining in this lot
+    pub amount: u64,
+
+    /// Expiry timestamp (i64::MAX = never expires)
+    pub expires_at: i64,
+
+    /// Merchant PDA that issued these points (owes their value in settlement;
+    /// default = protocol-issued, owed by no merchant)
+    pub merchant: Pubkey,
+}
+
+impl PointLots {
+    pub const SEED_PREFIX: &'static [u8] = b"point_lots";
+
+    /// Expiry for points earned at `now` under an expiry period (0 = never expires).
+    /// Rounded up to the end of the UTC day so a day's credits share one lot.
+    pub fn expiry_for(now: i64, expiry_period: i64) -> i64 {
+        if expiry_period <= 0 {
+            return i64::MAX;
+        }
+        let expires_at = now.saturating_add(expiry_period);
+        expires_at
+            .saturating_add(SECONDS_PER_DAY - 1)
+            .saturating_div(SECONDS_PER_DAY)
+            .saturating_mul(SECONDS_PER_DAY)
+    }
+
+    /// Record newly earned points. Lots merge when both expiry day and issuing merchant match.
+    /// When the lot list is full the credit joins the merchant's newest lot, which keeps the
+    /// later of the two expiries; a merchant without a lot frees a slot by merging the oldest
+    /// lots instead. Points are never expired early.
+    pub fn credit(&mut self, credit: PointLot) -> Result<()> {
+        let position = self
+            .lots
+            .partition_point(|lot| lot.expires_at < credit.expires_at);
+
+        let same_lot = self.lots[position..]
+            .iter()
+            .take_while(|lot| lot.expires_at == credit.expires_at)
+            .position(|lot| lot.merchant == credit.merchant)
+            .map(|offset| position + offset);
+
+        if let Some(index) = same_lot {
+            let lot = &mut self.lots[index];
+            lot.amount = lot
+                .amount
+                .checked_add(credit.amount)
+                .ok_or(SolcityError::Overflow)?;
+            return Ok(());
+        }
+
+        if self.lots.len() < MAX_POINT_LOTS {
+            self.lots.insert(position, credit);
+            return Ok(());
+        }
+
+        match self
+            .lots
+            .iter()
+            .rposition(|lot| lot.merchant == credit.merchant)
+        {
+            Some(index) => {
+                let mut lot = self.lots.remove(index);
+                lot.amount = lot
+                    .amount
+                    .checked_add(credit.amount)
+                    .ok_or(SolcityError::Overflow)?;
+                lot.expires_at = lot.expires_at.max(credit.expires_at);
+                self.insert(lot);
+            }
+            None => {
+                self.merge_oldest()?;
+                self.insert(credit);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Insert a lot keeping the list ordered by expiry
+    fn insert(&mut self, lot: PointLot) {
+        let position = self
+            .lots
+            .partition_point(|existing| existing.expires_at < lot.expires_at);
+        self.lots.insert(position, lot);
+    }
+
+    /// Free a slot by folding a lot into the next one, which expires no earlier.
+    /// Prefers the oldest neighbouring pair from one merchant so settlement attribution
+    /// survives; otherwise the oldest lot passes to the next lot's merchant.
+    fn merge_oldest(&mut self) -> Result<()> {
+        let index = self
+            .lots
+            .windows(2)
+            .position(|pair| pair[0].merchant == pair[1].merchant)
+            .unwrap_or(0);
+        let older = self.lots.remove(index);
+        let newer = &mut self.lots[index];
+        newer.amount = newer
+            .amount
+            .checked_add(older.amount)
+            .ok_or(SolcityError::Overflow)?;
+        Ok(())
+    }
+
+    /// Split up to `amount` points off the oldest lots, keeping their expiry dates
+    pub fn take(&mut self, amount: u64) -> Vec<PointLot> {
+        let mut taken = Vec::new();
+        let mut remaining = amount;
+        while remaining > 0 {
+            let Some(lot) = self.lots.first_mut() else {
+                break;
+            };
+            let portion = lot.amount.min(remaining);
+            lot.amount -= portion;
+            remaining -= portion;
+            taken.push(PointLot {
+                amount: portion,
+                ..lot.clone()
+            });
+            if lot.amount == 0 {
+                self.lots.remove(0);
+            }
+        }
+        taken
+    }
+
+    /// Remove and return every lot expired at `now`
+    pub fn take_expired(&mut self, now: i64) -> Vec<PointLot> {
+        let count = self.lots.partition_point(|lot| lot.expires_at <= now);
+        self.lots.drain(..count).collect()
+    }
+
+    /// Mirror the earliest expiring lot onto the customer (zeroes when nothing expires)
+    pub fn sync_next_expiry(&self, customer: &mut Customer) {
+        match self.lots.first() {
+            Some(lot) if lot.expires_at != i64::MAX => {
+                customer.next_expiry_at = lot.expires_at;
+                customer.next_expiry_amount = lot.amount;
+            }
+            _ => {
+                customer.next_expiry_at = 0;
+                customer.next_expiry_amount = 0;
+            }
+        }
+    }
+}
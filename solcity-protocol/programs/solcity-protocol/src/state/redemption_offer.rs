@@ -16,6 +16,7 @@ impl anchor_lang::Space for RedemptionType {
 #[account]
 #[derive(InitSpace)]
 pub struct RedemptionOffer {
+    pub version: u8,
     pub merchant: Pubkey,
     pub loyalty_program: Pubkey,
     #[max_len(64)]
@@ -32,6 +33,7 @@ pub struct RedemptionOffer {
     pub is_active: bool,
     pub created_at: i64,
     pub bump: u8,
+    pub reserved: [u8; 64],
 }
 
 impl RedemptionOffer {
@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 
 #[account]
 pub struct RedemptionVoucher {
+    pub version: u8,                // 1
     pub customer: Pubkey,           // 32
     pub merchant: Pubkey,           // 32
     pub redemption_offer: Pubkey,   // 32
@@ -15,14 +16,15 @@ pub struct RedemptionVoucher {
     pub is_used: bool,              // 1
     pub used_at: Option<i64>,       // 1 + 8 = 9
     pub bump: u8,                   // 1
+    pub reserved: [u8; 32],         // 32
 }
 
 impl RedemptionVoucher {
     pub const SEED_PREFIX: &'static [u8] = b"voucher";
     
     // Space calculation:
-    // 8 (discriminator) + 32 + 32 + 32 + 54 + 104 + 204 + 8 + 24 + 8 + 8 + 1 + 9 + 1 = 525
-    pub const SPACE: usize = 8 + 32 + 32 + 32 + 54 + 104 + 204 + 8 + 24 + 8 + 8 + 1 + 9 + 1;
+    // 8 (discriminator) + 1 + 32 + 32 + 32 + 54 + 104 + 204 + 8 + 24 + 8 + 8 + 1 + 9 + 1 + 32 = 558
+    pub const SPACE: usize = 8 + 1 + 32 + 32 + 32 + 54 + 104 + 204 + 8 + 24 + 8 + 8 + 1 + 9 + 1 + 32;
 
     pub fn is_valid(&self, current_timestamp: i64) -> bool {
         !self.is_used && current_timestamp < self.expires_at
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+/// Records who referred a customer and whether the referral bonuses were paid
+#[account]
+#[derive(InitSpace)]
+pub struct Referral {
+    /// Account layout version (see CURRENT_ACCOUNT_VERSION)
+    pub version: u8,
+
+    /// Associated loyalty program
+    pub loyalty_program: Pubkey,
+
+    /// Customer PDA that made the referral
+    pub referrer: Pubkey,
+
+    /// Customer PDA that was referred
+    pub referee: Pubkey,
+
+    /// Registration timestamp of the referee
+    pub created_at: i64,
+
+    /// Bonus points minted to the referrer (0 until rewarded)
+    pub referrer_bonus: u64,
+
+    /// Bonus points minted to the referee (0 until rewarded)
+    pub referee_bonus: u64,
+
+    /// Timestamp of the referee's first qualifying purchase (0 = not yet rewarded)
+    pub rewarded_at: i64,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Reserved for future fields (new fields are carved out of this)
+    pub reserved: [u8; 32],
+}
+
+impl Referral {
+    pub const SEED_PREFIX: &'static [u8] = b"referral";
+}
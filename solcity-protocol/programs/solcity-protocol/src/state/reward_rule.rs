@@ -1,8 +1,12 @@
+use crate::{SolcityError, MAX_TIERS, PERCENTAGE_DIVISOR};
 use anchor_lang::prelude::*;
 
 #[account]
 #[derive(InitSpace)]
 pub struct RewardRule {
+    /// Account layout version (see CURRENT_ACCOUNT_VERSION)
+    pub version: u8,
+
     /// Associated merchant
     pub merchant: Pubkey,
 
@@ -16,7 +20,7 @@ pub struct RewardRule {
     /// Rule type
     pub rule_type: RuleType,
 
-    /// Bonus multiplier (100 = 1x, 200 = 2x)
+    /// Bonus multiplier (100 = 1x, 200 = 2x; unused for RewardKind::FlatBonus)
     pub multiplier: u64,
 
     /// Minimum purchase to trigger (in cents)
@@ -33,6 +37,30 @@ pub struct RewardRule {
 
     /// PDA bump
     pub bump: u8,
+
+    /// Evaluation order under RuleCombination::Priority (higher first)
+    pub priority: u8,
+
+    /// Under RuleCombination::Priority, stop evaluating lower-priority rules once this applies
+    pub exclusive: bool,
+
+    /// StreakBonus: minimum Customer.streak_days to qualify
+    pub min_streak_days: u16,
+
+    /// TierBonus: qualifying tiers (bit n = tier index n)
+    pub eligible_tiers: u8,
+
+    /// Whether the rule multiplies the reward, adds flat points, or both
+    pub reward_kind: RewardKind,
+
+    /// Points added when the rule applies (FlatBonus / MultiplierAndBonus)
+    pub flat_bonus: u64,
+
+    /// Cap on the points this rule adds per issuance (0 = uncapped)
+    pub max_bonus: u64,
+
+    /// Reserved for future fields (new fields are carved out of this)
+    pub reserved: [u8; 42],
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
@@ -45,6 +73,45 @@ pub enum RuleType {
     StreakBonus,        // Consecutive activity bonus
 }
 
+/// How a reward rule adds points
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum RewardKind {
+    Multiplier,         // reward * multiplier
+    FlatBonus,          // reward + flat_bonus
+    MultiplierAndBonus, // reward * multiplier + flat_bonus
+}
+
+/// Purchase and customer facts a reward rule is evaluated against
+pub struct RuleContext {
+    pub current_time: i64,
+    pub purchase_amount: u64,
+    /// No MerchantCustomerRecord existed for this merchant and customer
+    pub first_purchase: bool,
+    pub streak_days: u16,
+    pub tier_index: u8,
+    pub referred: bool,
+}
+
+/// How a merchant's reward rules combine when several apply to one purchase
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum RuleCombination {
+    HighestOnly,    // Only the rule adding the most points applies, flat bonuses included
+    Multiplicative, // Multipliers compound (2x and 1.5x = 3x)
+    Additive,       // Bonuses add up (2x and 1.5x = 2.5x)
+    Priority,       // Compound in priority order; an exclusive rule stops lower ones
+}
+
+/// Outcome of one reward rule evaluated during issue_rewards
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct RuleEvaluation {
+    pub rule: Pubkey,
+    pub rule_id: u64,
+    pub multiplier: u64,
+    /// Points this rule added (after its max_bonus cap; 0 when not applied)
+    pub bonus: u64,
+    pub applied: bool,
+}
+
 impl RewardRule {
     pub const SEED_PREFIX: &'static [u8] = b"reward_rule";
 
@@ -59,4 +126,86 @@ impl RewardRule {
 
         after_start && before_end
     }
+
+    /// Whether the rule applies to a purchase: active, minimum met and type-specific condition
+    pub fn applies(&self, context: &RuleContext) -> bool {
+        if !self.is_currently_active(context.current_time)
+            || context.purchase_amount < self.min_purchase
+        {
+            return false;
+        }
+
+        match self.rule_type {
+            RuleType::BaseReward | RuleType::BonusMultiplier => true,
+            RuleType::FirstPurchaseBonus => context.first_purchase,
+            RuleType::ReferralBonus => context.referred,
+            RuleType::TierBonus => self.eligible_tiers & (1 << context.tier_index) != 0,
+            RuleType::StreakBonus => context.streak_days >= self.min_streak_days,
+        }
+    }
+
+    /// Multiplier this rule applies (1x for flat-bonus rules)
+    pub fn effective_multiplier(&self) -> u64 {
+        match self.reward_kind {
+            RewardKind::FlatBonus => PERCENTAGE_DIVISOR,
+            RewardKind::Multiplier | RewardKind::MultiplierAndBonus => self.multiplier,
+        }
+    }
+
+    /// Points this rule adds to `reward`: the multiplier's share above 1x plus the flat
+    /// bonus, capped at max_bonus
+    pub fn bonus_for(&self, reward: u64) -> Result<u64> {
+        let above_one_x = self
+            .effective_multiplier()
+            .saturating_sub(PERCENTAGE_DIVISOR);
+        let multiplied = reward
+            .checked_mul(above_one_x)
+            .ok_or(SolcityError::Overflow)?
+            / PERCENTAGE_DIVISOR;
+        let flat = match self.reward_kind {
+            RewardKind::Multiplier => 0,
+            RewardKind::FlatBonus | RewardKind::MultiplierAndBonus => self.flat_bonus,
+        };
+        let bonus = multiplied.checked_add(flat).ok_or(SolcityError::Overflow)?;
+
+        Ok(if self.max_bonus > 0 {
+            bonus.min(self.max_bonus)
+        } else {
+            bonus
+        })
+    }
+
+    /// Check the reward parameters: multiplier kinds need at least 1x, bonus kinds need a
+    /// flat bonus, and flat-bonus rules leave the multiplier at 0
+    pub fn validate_reward(
+        reward_kind: RewardKind,
+        multiplier: u64,
+        flat_bonus: u64,
+    ) -> Result<()> {
+        let valid = match reward_kind {
+            RewardKind::Multiplier => multiplier >= PERCENTAGE_DIVISOR && flat_bonus == 0,
+            RewardKind::FlatBonus => multiplier == 0 && flat_bonus > 0,
+            RewardKind::MultiplierAndBonus => multiplier >= PERCENTAGE_DIVISOR && flat_bonus > 0,
+        };
+        require!(valid, SolcityError::InvalidRewardAmount);
+        Ok(())
+    }
+
+    /// Check the type-specific parameters: StreakBonus needs a minimum streak, TierBonus
+    /// needs at least one valid tier, and other types must leave both unset
+    pub fn validate_params(
+        rule_type: &RuleType,
+        min_streak_days: u16,
+        eligible_tiers: u8,
+    ) -> Result<()> {
+        let valid = match rule_type {
+            RuleType::StreakBonus => min_streak_days > 0 && eligible_tiers == 0,
+            RuleType::TierBonus => {
+                min_streak_days == 0 && eligible_tiers != 0 && eligible_tiers >> MAX_TIERS == 0
+            }
+            _ => min_streak_days == 0 && eligible_tiers == 0,
+        };
+        require!(valid, SolcityError::InvalidRuleParameters);
+        Ok(())
+    }
 }
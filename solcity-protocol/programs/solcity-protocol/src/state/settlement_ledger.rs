@@ -0,0 +1,74 @@
+use crate::SolcityError;
+use anchor_lang::prelude::*;
+
+/// Points owed between two merchants for coalition redemptions
+/// One account per merchant pair, keyed with the smaller merchant key first
+#[account]
+#[derive(InitSpace)]
+pub struct SettlementLedger {
+    /// Account layout version (see CURRENT_ACCOUNT_VERSION)
+    pub version: u8,
+
+    /// Associated loyalty program
+    pub loyalty_program: Pubkey,
+
+    /// Merchant PDA with the smaller key
+    pub merchant_a: Pubkey,
+
+    /// Merchant PDA with the larger key
+    pub merchant_b: Pubkey,
+
+    /// Points issued by merchant_a and redeemed at merchant_b, not yet settled
+    pub a_owes_b: u64,
+
+    /// Points issued by merchant_b and redeemed at merchant_a, not yet settled
+    pub b_owes_a: u64,
+
+    /// Lifetime net points settled
+    pub total_settled_points: u64,
+
+    /// Lifetime collateral paid in settlements (in collateral currency units)
+    pub total_settled_value: u64,
+
+    /// Last settlement timestamp (0 = never settled)
+    pub last_settled_at: i64,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Reserved for future fields (new fields are carved out of this)
+    pub reserved: [u8; 32],
+}
+
+impl SettlementLedger {
+    pub const SEED_PREFIX: &'static [u8] = b"settlement";
+
+    /// Order a merchant pair the way ledger seeds expect
+    pub fn ordered_pair(first: Pubkey, second: Pubkey) -> (Pubkey, Pubkey) {
+        if first < second {
+            (first, second)
+        } else {
+            (second, first)
+        }
+    }
+
+    /// Ledger PDA for a merchant pair (in any order)
+    pub fn find_address(first: Pubkey, second: Pubkey) -> (Pubkey, u8) {
+        let (merchant_a, merchant_b) = Self::ordered_pair(first, second);
+        Pubkey::find_program_address(
+            &[Self::SEED_PREFIX, merchant_a.as_ref(), merchant_b.as_ref()],
+            &crate::ID,
+        )
+    }
+
+    /// Record points issued by `issuer` and redeemed at the other merchant
+    pub fn record(&mut self, issuer: Pubkey, points: u64) -> Result<()> {
+        let owed = if issuer == self.merchant_a {
+            &mut self.a_owes_b
+        } else {
+            &mut self.b_owes_a
+        };
+        *owed = owed.checked_add(points).ok_or(SolcityError::Overflow)?;
+        Ok(())
+    }
+}
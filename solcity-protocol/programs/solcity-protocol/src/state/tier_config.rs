@@ -0,0 +1,90 @@
+use crate::{
+    CustomerTier, BRONZE_MULTIPLIER, BRONZE_THRESHOLD, GOLD_MULTIPLIER, GOLD_THRESHOLD, MAX_TIERS,
+    PERCENTAGE_DIVISOR, PLATINUM_MULTIPLIER, PLATINUM_THRESHOLD, SILVER_MULTIPLIER,
+    SILVER_THRESHOLD,
+};
+use anchor_lang::prelude::*;
+
+/// Program-wide tier schedule
+/// Tier at index i maps to the CustomerTier variant with the same index
+#[account]
+#[derive(InitSpace)]
+pub struct TierConfig {
+    /// Account layout version (see CURRENT_ACCOUNT_VERSION)
+    pub version: u8,
+
+    /// Associated loyalty program
+    pub loyalty_program: Pubkey,
+
+    /// Tiers ordered by ascending threshold
+    #[max_len(MAX_TIERS)]
+    pub tiers: Vec<TierLevel>,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Reserved for future fields (new fields are carved out of this)
+    pub reserved: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub struct TierLevel {
+    /// Display name (16 bytes)
+    #[max_len(16)]
+    pub name: String,
+
+    /// Lifetime tokens earned to reach this tier
+    pub threshold: u64,
+
+    /// Reward multiplier (100 = 1x, divide by PERCENTAGE_DIVISOR)
+    pub multiplier: u64,
+}
+
+impl TierConfig {
+    pub const SEED_PREFIX: &'static [u8] = b"tier_config";
+
+    /// Default schedule matching the original Bronze/Silver/Gold/Platinum tiers
+    pub fn default_tiers() -> Vec<TierLevel> {
+        vec![
+            TierLevel {
+                name: "Bronze".to_string(),
+                threshold: BRONZE_THRESHOLD,
+                multiplier: BRONZE_MULTIPLIER,
+            },
+            TierLevel {
+                name: "Silver".to_string(),
+                threshold: SILVER_THRESHOLD,
+                multiplier: SILVER_MULTIPLIER,
+            },
+            TierLevel {
+                name: "Gold".to_string(),
+                threshold: GOLD_THRESHOLD,
+                multiplier: GOLD_MULTIPLIER,
+            },
+            TierLevel {
+                name: "Platinum".to_string(),
+                threshold: PLATINUM_THRESHOLD,
+                multiplier: PLATINUM_MULTIPLIER,
+            },
+        ]
+    }
+
+    /// Calculate tier based on total earned
+    pub fn calculate_tier(&self, total_earned: u64) -> CustomerTier {
+        let index = self
+            .tiers
+            .iter()
+            .rposition(|tier| total_earned >= tier.threshold)
+            .unwrap_or(0);
+        CustomerTier::from_index(index)
+    }
+
+    /// Get tier multiplier (percentage-based, 100 = 1x, divide by PERCENTAGE_DIVISOR)
+    /// Tiers beyond the configured schedule use the highest configured tier
+    pub fn get_tier_multiplier(&self, tier: &CustomerTier) -> u64 {
+        let index = (tier.index() as usize).min(self.tiers.len().saturating_sub(1));
+        self.tiers
+            .get(index)
+            .map_or(PERCENTAGE_DIVISOR, |level| level.multiplier)
+    }
+}
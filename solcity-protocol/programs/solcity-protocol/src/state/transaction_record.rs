@@ -1,17 +1,20 @@
 use anchor_lang::prelude::*;
 
 /// Stores individual transaction records for customers
-/// Each transaction (earn or redeem) creates a new record
+/// Each transaction (earn, redeem or gift) creates a new record
 #[account]
 #[derive(InitSpace)]
 pub struct TransactionRecord {
+    /// Account layout version (see CURRENT_ACCOUNT_VERSION)
+    pub version: u8,
+
     /// Customer wallet
     pub customer: Pubkey,
 
-    /// Merchant involved
+    /// Merchant involved (default for gifts)
     pub merchant: Pubkey,
 
-    /// Transaction type: 0 = Earned, 1 = Redeemed
+    /// Transaction type: 0 = Earned, 1 = Redeemed, 2 = Gift
     pub transaction_type: u8,
 
     /// Amount of tokens
@@ -28,13 +31,23 @@ pub struct TransactionRecord {
 
     /// PDA bump
     pub bump: u8,
+
+    /// Gift direction: true when this customer sent the points
+    pub outgoing: bool,
+
+    /// Other customer's wallet for gifts (default for earn and redeem)
+    pub counterparty: Pubkey,
+
+    /// Reserved for future fields (new fields are carved out of this)
+    pub reserved: [u8; 31],
 }
 
 impl TransactionRecord {
     pub const SEED_PREFIX: &'static [u8] = b"transaction";
 
     // Space calculation:
-    // 8 (discriminator) + 32 (customer) + 32 (merchant) + 1 (type) + 8 (amount) 
-    // + 1 (tier) + 8 (timestamp) + 8 (index) + 1 (bump) = 99
-    pub const SPACE: usize = 8 + 32 + 32 + 1 + 8 + 1 + 8 + 8 + 1;
+    // 8 (discriminator) + 1 (version) + 32 (customer) + 32 (merchant) + 1 (type) + 8 (amount)
+    // + 1 (tier) + 8 (timestamp) + 8 (index) + 1 (bump) + 1 (outgoing) + 32 (counterparty)
+    // + 31 (reserved) = 164
+    pub const SPACE: usize = 8 + 1 + 32 + 32 + 1 + 8 + 1 + 8 + 8 + 1 + 1 + 32 + 31;
 }
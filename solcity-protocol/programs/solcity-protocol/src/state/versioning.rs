@@ -0,0 +1,250 @@
+use crate::{
+    Customer, IssuanceCaps, LoyaltyProgram, Merchant, MerchantCustomerRecord, MerchantStatus,
+    OfferRedemptionRecord, RedemptionOffer, RedemptionVoucher, RewardRule, RuleCombination,
+    RuleType, SolcityError, TransactionRecord, TransferMode, CURRENT_ACCOUNT_VERSION,
+    DEFAULT_ISSUANCE_FEE_PER_TOKEN, DEFAULT_MAX_REFERRALS_PER_REFERRER,
+    DEFAULT_MERCHANT_REGISTRATION_FEE, DEFAULT_REFEREE_BONUS, DEFAULT_REFERRER_BONUS,
+    DEFAULT_STREAK_MILESTONES, MAX_TIERS, SECONDS_PER_DAY,
+};
+use anchor_lang::{prelude::*, Discriminator};
+
+/// Account types that carry a layout version and can be upgraded by migrate_account
+pub trait Versioned: AccountSerialize + AccountDeserialize + Discriminator {
+    /// Type name reported in migration events
+    const NAME: &'static str;
+
+    /// Allocation size of the original layout, before the version byte existed
+    const BASELINE_SPACE: usize;
+
+    /// Allocation size of the current layout
+    const CURRENT_SPACE: usize;
+
+    /// Rebuild an original-layout account at `address` from its data (discriminator
+    /// excluded) in the current layout, filling fields added since with their defaults
+    fn from_baseline(address: &Pubkey, data: &[u8]) -> Result<Self>;
+}
+
+/// Read an original layout whose fields are a prefix of the current one: the version byte
+/// goes after the discriminator and fields added since read as zero
+fn shift_baseline<T: AnchorDeserialize>(data: &[u8], current_space: usize) -> Result<T> {
+    let mut shifted = vec![0u8; current_space - 8];
+    shifted[1..=data.len()].copy_from_slice(data);
+    T::deserialize(&mut &shifted[..]).map_err(|_| SolcityError::InvalidAccount.into())
+}
+
+macro_rules! impl_versioned {
+    ($account:ident, baseline_space = $baseline:expr, current_space = $current:expr) => {
+        impl_versioned!(
+            $account,
+            baseline_space = $baseline,
+            current_space = $current,
+            fill = |_| {}
+        );
+    };
+    (
+        $account:ident,
+        baseline_space = $baseline:expr,
+        current_space = $current:expr,
+        fill = $fill:expr
+    ) => {
+        impl Versioned for $account {
+            const NAME: &'static str = stringify!($account);
+            const BASELINE_SPACE: usize = $baseline;
+            const CURRENT_SPACE: usize = $current;
+
+            fn from_baseline(_address: &Pubkey, data: &[u8]) -> Result<Self> {
+                let mut state: Self = shift_baseline(data, Self::CURRENT_SPACE)?;
+                state.version = CURRENT_ACCOUNT_VERSION;
+                let fill: fn(&mut Self) = $fill;
+                fill(&mut state);
+                Ok(state)
+            }
+        }
+    };
+}
+
+impl_versioned!(
+    Customer,
+    baseline_space = 116,
+    current_space = 8 + Customer::INIT_SPACE,
+    // streak_day now marks the last counted day; without it a running streak would reset
+    fill = |customer| {
+        customer.streak_day = customer.last_activity.div_euclid(SECONDS_PER_DAY);
+        customer.longest_streak = customer.streak_days;
+    }
+);
+impl_versioned!(
+    RewardRule,
+    baseline_space = 119,
+    current_space = 8 + RewardRule::INIT_SPACE,
+    // Rule types were not evaluated originally, so tier and streak rules applied to everyone
+    fill = |rule| match rule.rule_type {
+        RuleType::TierBonus => rule.eligible_tiers = (1u8 << MAX_TIERS) - 1,
+        RuleType::StreakBonus => rule.min_streak_days = 1,
+        _ => {}
+    }
+);
+impl_versioned!(
+    RedemptionOffer,
+    baseline_space = 737,
+    current_space = 8 + RedemptionOffer::INIT_SPACE
+);
+impl_versioned!(
+    RedemptionVoucher,
+    baseline_space = 525,
+    current_space = RedemptionVoucher::SPACE
+);
+impl_versioned!(
+    TransactionRecord,
+    baseline_space = 99,
+    current_space = TransactionRecord::SPACE
+);
+impl_versioned!(
+    MerchantCustomerRecord,
+    baseline_space = 113,
+    current_space = MerchantCustomerRecord::SPACE
+);
+impl_versioned!(
+    OfferRedemptionRecord,
+    baseline_space = 163,
+    current_space = OfferRedemptionRecord::SPACE
+);
+
+/// Original LoyaltyProgram layout: fees were paid straight to a wallet treasury
+#[derive(AnchorDeserialize)]
+struct BaselineLoyaltyProgram {
+    authority: Pubkey,
+    _treasury: Pubkey,
+    mint: Pubkey,
+    name: String,
+    total_merchants: u64,
+    total_customers: u64,
+    total_tokens_issued: u64,
+    total_tokens_redeemed: u64,
+    total_fees_collected: u64,
+    interest_rate: i16,
+    bump: u8,
+    created_at: i64,
+}
+
+impl Versioned for LoyaltyProgram {
+    const NAME: &'static str = "LoyaltyProgram";
+    const BASELINE_SPACE: usize = 191;
+    const CURRENT_SPACE: usize = 8 + LoyaltyProgram::INIT_SPACE;
+
+    /// Fees already collected sit in the authority's wallet, so they count as withdrawn and
+    /// new fees go to the treasury PDA. The original mint has no permanent delegate to burn
+    /// expired points, so points keep never expiring.
+    fn from_baseline(address: &Pubkey, data: &[u8]) -> Result<Self> {
+        let baseline = BaselineLoyaltyProgram::deserialize(&mut &data[..])
+            .map_err(|_| SolcityError::InvalidAccount)?;
+        let (treasury, treasury_bump) = Pubkey::find_program_address(
+            &[LoyaltyProgram::TREASURY_SEED_PREFIX, address.as_ref()],
+            &crate::ID,
+        );
+
+        Ok(LoyaltyProgram {
+            version: CURRENT_ACCOUNT_VERSION,
+            authority: baseline.authority,
+            seed_authority: baseline.authority,
+            pending_authority: None,
+            treasury,
+            treasury_bump,
+            mint: baseline.mint,
+            name: baseline.name,
+            total_merchants: baseline.total_merchants,
+            total_customers: baseline.total_customers,
+            total_tokens_issued: baseline.total_tokens_issued,
+            total_tokens_redeemed: baseline.total_tokens_redeemed,
+            total_fees_collected: baseline.total_fees_collected,
+            total_fees_withdrawn: baseline.total_fees_collected,
+            pending_registration_fees: 0,
+            interest_rate: baseline.interest_rate,
+            fee_mint: None,
+            merchant_registration_fee: DEFAULT_MERCHANT_REGISTRATION_FEE,
+            issuance_fee_per_token: DEFAULT_ISSUANCE_FEE_PER_TOKEN,
+            volume_fee_tiers: Vec::new(),
+            fee_recipients: Vec::new(),
+            paused: 0,
+            bump: baseline.bump,
+            created_at: baseline.created_at,
+            transfer_mode: TransferMode::Transferable,
+            points_expiry_period: 0,
+            total_tokens_expired: 0,
+            gift_daily_limits: [0; MAX_TIERS],
+            point_value: 0,
+            collateral_mint: None,
+            total_collateral: 0,
+            streak_milestones: DEFAULT_STREAK_MILESTONES,
+            referrer_bonus: DEFAULT_REFERRER_BONUS,
+            referee_bonus: DEFAULT_REFEREE_BONUS,
+            referral_min_purchase: 0,
+            max_referrals_per_referrer: DEFAULT_MAX_REFERRALS_PER_REFERRER,
+            fee_currency_epoch: 0,
+            reserved: [0; 118],
+        })
+    }
+}
+
+/// Original Merchant layout: an is_active flag instead of the approval workflow
+#[derive(AnchorDeserialize)]
+struct BaselineMerchant {
+    authority: Pubkey,
+    loyalty_program: Pubkey,
+    name: String,
+    description: String,
+    avatar_url: String,
+    category: String,
+    reward_rate: u64,
+    total_issued: u64,
+    total_redeemed: u64,
+    is_active: bool,
+    bump: u8,
+    created_at: i64,
+}
+
+impl Versioned for Merchant {
+    const NAME: &'static str = "Merchant";
+    const BASELINE_SPACE: usize = 698;
+    const CURRENT_SPACE: usize = 8 + Merchant::INIT_SPACE;
+
+    /// Active merchants were live without review, so they become Approved; deactivated ones
+    /// become Suspended. The registration fee went straight to the wallet treasury, so none
+    /// is held for a refund, and points issued before collateral existed are unbacked.
+    fn from_baseline(_address: &Pubkey, data: &[u8]) -> Result<Self> {
+        let baseline = BaselineMerchant::deserialize(&mut &data[..])
+            .map_err(|_| SolcityError::InvalidAccount)?;
+
+        Ok(Merchant {
+            version: CURRENT_ACCOUNT_VERSION,
+            authority: baseline.authority,
+            loyalty_program: baseline.loyalty_program,
+            name: baseline.name,
+            description: baseline.description,
+            avatar_url: baseline.avatar_url,
+            category: baseline.category,
+            reward_rate: baseline.reward_rate,
+            total_issued: baseline.total_issued,
+            total_redeemed: baseline.total_redeemed,
+            status: if baseline.is_active {
+                MerchantStatus::Approved
+            } else {
+                MerchantStatus::Suspended
+            },
+            registration_fee_paid: 0,
+            issuance_fee_override: None,
+            bump: baseline.bump,
+            created_at: baseline.created_at,
+            collateral_balance: 0,
+            outstanding_points: 0,
+            issuance_caps: IssuanceCaps::default(),
+            issuance_ceiling: IssuanceCaps::default(),
+            issuance_window_start: 0,
+            issued_in_window: 0,
+            issued_in_prev_window: 0,
+            rule_combination: RuleCombination::HighestOnly,
+            fee_override_epoch: 0,
+            reserved: [0; 35],
+        })
+    }
+}
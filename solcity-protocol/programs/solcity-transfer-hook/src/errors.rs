@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum TransferHookError {
+    #[msg("Hook was not invoked by a Token-2022 transfer")]
+    NotTransferring,
+
+    #[msg("Destination wallet is not a registered customer of this loyalty program")]
+    DestinationNotRegistered,
+
+    #[msg("Signer is not the mint authority")]
+    InvalidMintAuthority,
+
+    #[msg("Arithmetic overflow")]
+    Overflow,
+}
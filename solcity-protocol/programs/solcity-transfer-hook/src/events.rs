@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+/// Event emitted when points move between registered customers
+#[event]
+pub struct PointsTransferredEvent {
+    pub mint: Pubkey,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_transfers: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,104 @@
+use crate::{
+    TransferHookError, TransferStats, CUSTOMER_SEED, EXTRA_ACCOUNT_METAS_SEED, SOLCITY_PROTOCOL_ID,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use spl_tlv_account_resolution::{
+    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
+};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Mint authority (the Solcity loyalty_program PDA)
+    pub authority: Signer<'info>,
+
+    #[account(
+        constraint = mint.mint_authority == Some(authority.key()).into()
+            @ TransferHookError::InvalidMintAuthority,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Validation account read by Token-2022, written in the handler
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(extra_account_metas(&authority.key())?.len())?,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + TransferStats::INIT_SPACE,
+        seeds = [TransferStats::SEED_PREFIX, mint.key().as_ref()],
+        bump
+    )]
+    pub transfer_stats: Account<'info, TransferStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Extra accounts Token-2022 resolves for every transfer.
+/// Indices 0-4 are source, mint, destination, owner and the validation account.
+pub fn extra_account_metas(loyalty_program: &Pubkey) -> Result<Vec<ExtraAccountMeta>> {
+    Ok(vec![
+        // 5: Solcity protocol program
+        ExtraAccountMeta::new_with_pubkey(&SOLCITY_PROTOCOL_ID, false, false)?,
+        // 6: LoyaltyProgram PDA
+        ExtraAccountMeta::new_with_pubkey(loyalty_program, false, false)?,
+        // 7: destination owner's Customer PDA ["customer", destination.owner, loyalty_program]
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            5,
+            &[
+                Seed::Literal {
+                    bytes: CUSTOMER_SEED.to_vec(),
+                },
+                Seed::AccountData {
+                    account_index: 2,
+                    data_index: 32,
+                    length: 32,
+                },
+                Seed::AccountKey { index: 6 },
+            ],
+            false,
+            false,
+        )?,
+        // 8: TransferStats PDA
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: TransferStats::SEED_PREFIX.to_vec(),
+                },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            true,
+        )?,
+    ])
+}
+
+pub fn handler(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
+    let metas = extra_account_metas(&ctx.accounts.authority.key())?;
+    let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &metas)?;
+
+    let transfer_stats = &mut ctx.accounts.transfer_stats;
+    transfer_stats.mint = ctx.accounts.mint.key();
+    transfer_stats.total_transfers = 0;
+    transfer_stats.total_volume = 0;
+    transfer_stats.last_transfer_at = 0;
+    transfer_stats.bump = ctx.bumps.transfer_stats;
+
+    msg!(
+        "Transfer hook configured for mint {}",
+        ctx.accounts.mint.key()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use crate::{
+    PointsTransferredEvent, TransferHookError, TransferStats, CUSTOMER_SEED,
+    EXTRA_ACCOUNT_METAS_SEED, SOLCITY_PROTOCOL_ID,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Account as TokenAccountState;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Source owner or delegate, already verified by Token-2022
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Validation account
+    #[account(
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// CHECK: Solcity protocol program
+    #[account(address = SOLCITY_PROTOCOL_ID)]
+    pub solcity_program: UncheckedAccount<'info>,
+
+    /// CHECK: LoyaltyProgram PDA, verified as the mint authority
+    #[account(
+        constraint = mint.mint_authority == Some(loyalty_program.key()).into()
+            @ TransferHookError::InvalidMintAuthority,
+    )]
+    pub loyalty_program: UncheckedAccount<'info>,
+
+    /// CHECK: Destination owner's Customer PDA; must exist for the transfer to pass
+    #[account(
+        seeds = [
+            CUSTOMER_SEED,
+            destination_token.owner.as_ref(),
+            loyalty_program.key().as_ref()
+        ],
+        bump,
+        seeds::program = SOLCITY_PROTOCOL_ID,
+    )]
+    pub destination_customer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [TransferStats::SEED_PREFIX, mint.key().as_ref()],
+        bump = transfer_stats.bump,
+    )]
+    pub transfer_stats: Account<'info, TransferStats>,
+}
+
+pub fn handler(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+    // Only Token-2022 may call this, mid-transfer
+    {
+        let source_info = ctx.accounts.source_token.to_account_info();
+        let data = source_info.try_borrow_data()?;
+        let source = StateWithExtensions::<TokenAccountState>::unpack(&data)?;
+        let hook_account = source.get_extension::<TransferHookAccount>()?;
+        require!(
+            bool::from(hook_account.transferring),
+            TransferHookError::NotTransferring
+        );
+    }
+
+    // A Customer PDA only exists once register_customer has run for this wallet
+    let destination_customer = &ctx.accounts.destination_customer;
+    require!(
+        destination_customer.owner == &SOLCITY_PROTOCOL_ID && !destination_customer.data_is_empty(),
+        TransferHookError::DestinationNotRegistered
+    );
+
+    let clock = Clock::get()?;
+    let transfer_stats = &mut ctx.accounts.transfer_stats;
+    transfer_stats.total_transfers = transfer_stats
+        .total_transfers
+        .checked_add(1)
+        .ok_or(TransferHookError::Overflow)?;
+    transfer_stats.total_volume = transfer_stats
+        .total_volume
+        .checked_add(amount)
+        .ok_or(TransferHookError::Overflow)?;
+    transfer_stats.last_transfer_at = clock.unix_timestamp;
+
+    emit!(PointsTransferredEvent {
+        mint: ctx.accounts.mint.key(),
+        source: ctx.accounts.source_token.owner,
+        destination: ctx.accounts.destination_token.owner,
+        amount,
+        total_transfers: transfer_stats.total_transfers,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use spl_discriminator::SplDiscriminate;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+pub mod errors;
+pub mod events;
+pub mod instructions;
+pub mod state;
+
+pub use errors::*;
+pub use events::*;
+#[allow(ambiguous_glob_reexports)]
+pub use instructions::*;
+pub use state::*;
+
+declare_id!("5WthPonX3iRCfi1C7UECQ3hy4CExxvzRRYxhYBf8Rjy4");
+
+/// Solcity protocol program owning the LoyaltyProgram and Customer accounts
+pub const SOLCITY_PROTOCOL_ID: Pubkey = pubkey!("67XD1wBu5Ja1H5e4Zg4vsjZDoAcB8KwTZqawodZZwqv9");
+
+#[program]
+pub mod solcity_transfer_hook {
+    use super::*;
+
+    /// Create the extra-account-metas list and transfer stats for a loyalty mint
+    /// (signed by the mint authority, i.e. the loyalty_program PDA)
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        instructions::initialize_extra_account_meta_list::handler(ctx)
+    }
+
+    /// Transfer-hook entrypoint invoked by Token-2022 on every transfer
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        instructions::transfer_hook::handler(ctx, amount)
+    }
+}
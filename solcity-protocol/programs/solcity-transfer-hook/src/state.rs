@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+/// Transfer analytics for one loyalty mint
+#[account]
+#[derive(InitSpace)]
+pub struct TransferStats {
+    /// Loyalty mint
+    pub mint: Pubkey,
+
+    /// Number of transfers between customers
+    pub total_transfers: u64,
+
+    /// Tokens moved between customers
+    pub total_volume: u64,
+
+    /// Last transfer timestamp
+    pub last_transfer_at: i64,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl TransferStats {
+    pub const SEED_PREFIX: &'static [u8] = b"transfer_stats";
+}
+
+/// Seed of the extra-account-metas PDA defined by the transfer-hook interface
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+/// Seed of the Customer PDA in the Solcity protocol program
+pub const CUSTOMER_SEED: &[u8] = b"customer";
@@ -1,8 +1,25 @@
 import * as anchor from "@coral-xyz/anchor";
 import { SystemProgram, Keypair, PublicKey } from "@solana/web3.js";
-import { TOKEN_2022_PROGRAM_ID, getAssociatedTokenAddressSync, ASSOCIATED_TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getMint,
+  getInterestBearingMintConfigState,
+  getTokenMetadata,
+  getExtensionTypes,
+  getAccount,
+  transferChecked,
+  burnChecked,
+  transferCheckedWithTransferHook,
+  createAssociatedTokenAccount,
+  createMint,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+  ExtensionType,
+} from "@solana/spl-token";
 import { assert } from "chai";
-import { setupTest, findProgramAddress, SEEDS, airdrop } from "./helpers/setup";
+import { setupTest, findProgramAddress, SEEDS, airdrop, createFundedProgram, FundedProgram, getEvents } from "./helpers/setup";
 import { assertPublicKeyEqual, assertBNEqual, assertError, assertTierEqual, assertBNGreaterThan } from "./helpers/assertions";
 
 describe("Solcity Protocol - Comprehensive Tests", () => {
@@ -16,6 +33,8 @@ describe("Solcity Protocol - Comprehensive Tests", () => {
   let customerTokenAccount: PublicKey;
 
   const programName = "Test Loyalty Program";
+  const tokenSymbol = "SLCY";
+  const tokenUri = "https://solcity.example/token.json";
   const interestRate = 500;
   const merchantName = "Test Merchant";
   const rewardRate = 10;
@@ -35,7 +54,7 @@ describe("Solcity Protocol - Comprehensive Tests", () => {
       );
 
       await program.methods
-        .initializeProgram(programName, interestRate)
+        .initializeProgram(programName, tokenSymbol, tokenUri, interestRate)
         .accounts({
           authority: authority.publicKey,
         })
@@ -61,7 +80,7 @@ describe("Solcity Protocol - Comprehensive Tests", () => {
       await assertError(
         () =>
           program.methods
-            .initializeProgram("", interestRate)
+            .initializeProgram("", tokenSymbol, tokenUri, interestRate)
             .accounts({
               authority: newAuthority.publicKey,
             })
@@ -80,7 +99,7 @@ describe("Solcity Protocol - Comprehensive Tests", () => {
       await assertError(
         () =>
           program.methods
-            .initializeProgram(longName, interestRate)
+            .initializeProgram(longName, tokenSymbol, tokenUri, interestRate)
             .accounts({
               authority: newAuthority.publicKey,
             })
@@ -99,7 +118,7 @@ describe("Solcity Protocol - Comprehensive Tests", () => {
       await assertError(
         () =>
           program.methods
-            .initializeProgram(programName, invalidRate)
+            .initializeProgram(programName, tokenSymbol, tokenUri, invalidRate)
             .accounts({
               authority: newAuthority.publicKey,
             })
@@ -108,6 +127,86 @@ describe("Solcity Protocol - Comprehensive Tests", () => {
         "InvalidInterestRate"
       );
     });
+
+    it("Creates the mint with the InterestBearingConfig extension", async () => {
+      const { connection } = await ctx;
+
+      const mint = await getMint(connection, mintPda, "confirmed", TOKEN_2022_PROGRAM_ID);
+      const config = getInterestBearingMintConfigState(mint);
+
+      assert.ok(config, "mint is missing the InterestBearingConfig extension");
+      assert.equal(config!.currentRate, interestRate);
+      assertPublicKeyEqual(config!.rateAuthority, loyaltyProgramPda);
+    });
+
+    it("Updates the interest rate on the mint", async () => {
+      const { program, connection, authority } = await ctx;
+      const newRate = 750;
+
+      await program.methods
+        .updateInterestRate(newRate)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintPda,
+        })
+        .rpc();
+
+      const mint = await getMint(connection, mintPda, "confirmed", TOKEN_2022_PROGRAM_ID);
+      assert.equal(getInterestBearingMintConfigState(mint)!.currentRate, newRate);
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(loyaltyProgramPda);
+      assert.equal(loyaltyProgram.interestRate, newRate);
+    });
+
+    it("Creates the mint with on-chain token metadata", async () => {
+      const { connection } = await ctx;
+
+      const metadata = await getTokenMetadata(connection, mintPda, "confirmed", TOKEN_2022_PROGRAM_ID);
+
+      assert.ok(metadata, "mint is missing the TokenMetadata extension");
+      assert.equal(metadata!.name, programName);
+      assert.equal(metadata!.symbol, tokenSymbol);
+      assert.equal(metadata!.uri, tokenUri);
+      assertPublicKeyEqual(metadata!.updateAuthority!, loyaltyProgramPda);
+    });
+
+    it("Updates the token metadata", async () => {
+      const { program, connection, authority } = await ctx;
+      const newUri = "https://solcity.example/metadata/loyalty-token-v2.json";
+
+      await program.methods
+        .updateTokenMetadata(null, "SLCY2", newUri)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintPda,
+        })
+        .rpc();
+
+      const metadata = await getTokenMetadata(connection, mintPda, "confirmed", TOKEN_2022_PROGRAM_ID);
+      assert.equal(metadata!.name, programName);
+      assert.equal(metadata!.symbol, "SLCY2");
+      assert.equal(metadata!.uri, newUri);
+    });
+
+    it("Fails to update the interest rate as non-authority", async () => {
+      const { program, connection } = await ctx;
+      const attacker = anchor.web3.Keypair.generate();
+      await airdrop(connection, attacker.publicKey);
+
+      await assertError(
+        () =>
+          program.methods
+            .updateInterestRate(1000)
+            .accountsPartial({
+              authority: attacker.publicKey,
+              loyaltyProgram: loyaltyProgramPda,
+              mint: mintPda,
+            })
+            .signers([attacker])
+            .rpc(),
+        "UnauthorizedAccess"
+      );
+    });
   });
 
   describe("Register Merchant", () => {
@@ -127,7 +226,7 @@ describe("Solcity Protocol - Comprehensive Tests", () => {
       const { program } = await ctx;
 
       await program.methods
-        .registerMerchant(merchantName, new anchor.BN(rewardRate))
+        .registerMerchant(merchantName, "", "Retail", null, new anchor.BN(rewardRate))
         .accounts({
           merchantAuthority: merchantKeypair.publicKey,
           loyaltyProgram: loyaltyProgramPda,
@@ -138,12 +237,113 @@ describe("Solcity Protocol - Comprehensive Tests", () => {
       const merchant = await program.account.merchant.fetch(merchantPda);
       assert.equal(merchant.name, merchantName);
       assertBNEqual(merchant.rewardRate, rewardRate);
-      assert.equal(merchant.isActive, true);
+      assert.ok("pending" in merchant.status);
+      assertBNEqual(merchant.registrationFeePaid, 10_000_000);
       assertBNEqual(merchant.totalIssued, 0);
       assertBNEqual(merchant.totalRedeemed, 0);
 
       const loyaltyProgram = await program.account.loyaltyProgram.fetch(loyaltyProgramPda);
       assertBNEqual(loyaltyProgram.totalMerchants, 1);
+      assertBNEqual(loyaltyProgram.pendingRegistrationFees, 10_000_000);
+    });
+
+    it("Approves a pending merchant and books its registration fee", async () => {
+      const { program, authority } = await ctx;
+
+      await program.methods
+        .approveMerchant()
+        .accounts({
+          authority: authority.publicKey,
+          loyaltyProgram: loyaltyProgramPda,
+          merchant: merchantPda,
+        })
+        .rpc();
+
+      const merchant = await program.account.merchant.fetch(merchantPda);
+      assert.ok("approved" in merchant.status);
+      assertBNEqual(merchant.registrationFeePaid, 0);
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(loyaltyProgramPda);
+      assertBNEqual(loyaltyProgram.pendingRegistrationFees, 0);
+    });
+
+    it("Suspends an approved merchant and approves it again", async () => {
+      const { program, authority } = await ctx;
+      const accounts = {
+        authority: authority.publicKey,
+        loyaltyProgram: loyaltyProgramPda,
+        merchant: merchantPda,
+      };
+
+      await program.methods.suspendMerchant().accounts(accounts).rpc();
+      let merchant = await program.account.merchant.fetch(merchantPda);
+      assert.ok("suspended" in merchant.status);
+
+      // Suspending twice is not a valid transition
+      await assertError(
+        () => program.methods.suspendMerchant().accounts(accounts).rpc(),
+        "InvalidMerchantStatus"
+      );
+
+      await program.methods.approveMerchant().accounts(accounts).rpc();
+      merchant = await program.account.merchant.fetch(merchantPda);
+      assert.ok("approved" in merchant.status);
+    });
+
+    it("Rejects a pending merchant and refunds its registration fee", async () => {
+      const { program, authority, connection } = await ctx;
+      const applicant = Keypair.generate();
+      await airdrop(connection, applicant.publicKey);
+      const [applicantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, applicant.publicKey.toBuffer(), loyaltyProgramPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .registerMerchant("Applicant", "", "Retail", null, new anchor.BN(rewardRate))
+        .accounts({
+          merchantAuthority: applicant.publicKey,
+          loyaltyProgram: loyaltyProgramPda,
+        })
+        .signers([applicant])
+        .rpc();
+
+      const balanceBefore = await connection.getBalance(applicant.publicKey);
+
+      await program.methods
+        .rejectMerchant()
+        .accountsPartial({
+          authority: authority.publicKey,
+          loyaltyProgram: loyaltyProgramPda,
+          merchant: applicantPda,
+          merchantAuthority: applicant.publicKey,
+        })
+        .rpc();
+
+      const balanceAfter = await connection.getBalance(applicant.publicKey);
+      assert.equal(balanceAfter - balanceBefore, 10_000_000);
+
+      const merchant = await program.account.merchant.fetch(applicantPda);
+      assert.ok("rejected" in merchant.status);
+      assertBNEqual(merchant.registrationFeePaid, 0);
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(loyaltyProgramPda);
+      assertBNEqual(loyaltyProgram.pendingRegistrationFees, 0);
+
+      // Only pending merchants can be rejected
+      await assertError(
+        () =>
+          program.methods
+            .rejectMerchant()
+            .accountsPartial({
+              authority: authority.publicKey,
+              loyaltyProgram: loyaltyProgramPda,
+              merchant: merchantPda,
+              merchantAuthority: merchantKeypair.publicKey,
+            })
+            .rpc(),
+        "InvalidMerchantStatus"
+      );
     });
 
     it("Fails to register merchant with empty name", async () => {
@@ -154,7 +354,7 @@ describe("Solcity Protocol - Comprehensive Tests", () => {
       await assertError(
         () =>
           program.methods
-            .registerMerchant("", new anchor.BN(rewardRate))
+            .registerMerchant("", "", "Retail", null, new anchor.BN(rewardRate))
             .accounts({
               merchantAuthority: newMerchantKeypair.publicKey,
               loyaltyProgram: loyaltyProgramPda,
@@ -173,7 +373,7 @@ describe("Solcity Protocol - Comprehensive Tests", () => {
       await assertError(
         () =>
           program.methods
-            .registerMerchant(merchantName, new anchor.BN(0))
+            .registerMerchant(merchantName, "", "Retail", null, new anchor.BN(0))
             .accounts({
               merchantAuthority: newMerchantKeypair.publicKey,
               loyaltyProgram: loyaltyProgramPda,
@@ -465,4 +665,3483 @@ describe("Solcity Protocol - Comprehensive Tests", () => {
       );
     });
   });
+
+  describe("Program Config", () => {
+    const configAuthority = Keypair.generate();
+    const configMerchant = Keypair.generate();
+    const configCustomer = Keypair.generate();
+    let configProgramPda: PublicKey;
+    let configMintPda: PublicKey;
+    let configTreasuryPda: PublicKey;
+
+    const updateConfig = (registrationFee: number | null, issuanceFee: number | null) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .updateProgramConfig(
+            registrationFee === null ? null : new anchor.BN(registrationFee),
+            issuanceFee === null ? null : new anchor.BN(issuanceFee),
+            null
+          )
+          .accountsPartial({ authority: configAuthority.publicKey, loyaltyProgram: configProgramPda })
+          .signers([configAuthority])
+          .rpc({ commitment: "confirmed" })
+      );
+
+    const renameProgram = (name: string) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .updateTokenMetadata(name, null, null)
+          .accountsPartial({ authority: configAuthority.publicKey, loyaltyProgram: configProgramPda, mint: configMintPda })
+          .signers([configAuthority])
+          .rpc({ commitment: "confirmed" })
+      );
+
+    before(async () => {
+      const { program, connection } = await ctx;
+      for (const keypair of [configAuthority, configMerchant, configCustomer]) {
+        await airdrop(connection, keypair.publicKey);
+      }
+
+      [configProgramPda] = findProgramAddress(
+        [SEEDS.LOYALTY_PROGRAM, configAuthority.publicKey.toBuffer()],
+        program.programId
+      );
+      [configMintPda] = findProgramAddress([SEEDS.MINT, configProgramPda.toBuffer()], program.programId);
+      [configTreasuryPda] = findProgramAddress(
+        [Buffer.from("treasury"), configProgramPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initializeProgram("Config Program", tokenSymbol, tokenUri, interestRate)
+        .accounts({ authority: configAuthority.publicKey })
+        .signers([configAuthority])
+        .rpc();
+    });
+
+    it("Updates every field and emits the old and new values", async () => {
+      const { program } = await ctx;
+
+      const signature = await updateConfig(20_000_000, 250);
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(configProgramPda);
+      assertBNEqual(loyaltyProgram.merchantRegistrationFee, 20_000_000);
+      assertBNEqual(loyaltyProgram.issuanceFeePerToken, 250);
+
+      const event = (await getEvents(await ctx, signature)).find(
+        (e) => e.name === "programConfigUpdatedEvent"
+      );
+      assert.ok(event, "ProgramConfigUpdatedEvent not emitted");
+      assertPublicKeyEqual(event!.data.loyaltyProgram, configProgramPda);
+      assertPublicKeyEqual(event!.data.authority, configAuthority.publicKey);
+      assertBNEqual(event!.data.oldMerchantRegistrationFee, 10_000_000);
+      assertBNEqual(event!.data.newMerchantRegistrationFee, 20_000_000);
+      assertBNEqual(event!.data.oldIssuanceFeePerToken, 100);
+      assertBNEqual(event!.data.newIssuanceFeePerToken, 250);
+    });
+
+    it("Leaves omitted fields unchanged", async () => {
+      const { program } = await ctx;
+
+      const signature = await updateConfig(5_000_000, null);
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(configProgramPda);
+      assertBNEqual(loyaltyProgram.merchantRegistrationFee, 5_000_000);
+      assertBNEqual(loyaltyProgram.issuanceFeePerToken, 250);
+
+      const event = (await getEvents(await ctx, signature)).find(
+        (e) => e.name === "programConfigUpdatedEvent"
+      );
+      assertBNEqual(event!.data.oldMerchantRegistrationFee, 20_000_000);
+      assertBNEqual(event!.data.newMerchantRegistrationFee, 5_000_000);
+    });
+
+    it("Charges the configured fees", async () => {
+      const { program, connection } = await ctx;
+      const [configMerchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, configMerchant.publicKey.toBuffer(), configProgramPda.toBuffer()],
+        program.programId
+      );
+      const [configCustomerPda] = findProgramAddress(
+        [SEEDS.CUSTOMER, configCustomer.publicKey.toBuffer(), configProgramPda.toBuffer()],
+        program.programId
+      );
+
+      let treasuryBalance = await connection.getBalance(configTreasuryPda);
+      await program.methods
+        .registerMerchant(merchantName, "", "Retail", null, new anchor.BN(rewardRate))
+        .accountsPartial({
+          merchantAuthority: configMerchant.publicKey,
+          loyaltyProgram: configProgramPda,
+        })
+        .signers([configMerchant])
+        .rpc();
+      assert.equal(await connection.getBalance(configTreasuryPda), treasuryBalance + 5_000_000);
+
+      await program.methods
+        .approveMerchant()
+        .accounts({ authority: configAuthority.publicKey, loyaltyProgram: configProgramPda, merchant: configMerchantPda })
+        .signers([configAuthority])
+        .rpc();
+
+      await program.methods
+        .registerCustomer()
+        .accounts({ customerAuthority: configCustomer.publicKey, loyaltyProgram: configProgramPda })
+        .signers([configCustomer])
+        .rpc();
+
+      treasuryBalance = await connection.getBalance(configTreasuryPda);
+      const signature = await program.methods
+        .issueRewards(new anchor.BN(10_000))
+        .accountsPartial({
+          merchantAuthority: configMerchant.publicKey,
+          merchant: configMerchantPda,
+          customer: configCustomerPda,
+          loyaltyProgram: configProgramPda,
+          mint: configMintPda,
+          customerTokenAccount: getAssociatedTokenAddressSync(
+            configMintPda,
+            configCustomer.publicKey,
+            false,
+            TOKEN_2022_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID
+          ),
+          rewardRule: SystemProgram.programId,
+        })
+        .signers([configMerchant])
+        .rpc({ commitment: "confirmed" });
+
+      const event = (await getEvents(await ctx, signature)).find((e) => e.name === "rewardsIssuedEvent");
+      assertBNEqual(event!.data.platformFee, event!.data.finalReward.muln(250));
+      assert.equal(
+        await connection.getBalance(configTreasuryPda),
+        treasuryBalance + event!.data.platformFee.toNumber()
+      );
+    });
+
+    it("Renames the program through its token metadata", async () => {
+      const { program, connection } = await ctx;
+
+      await renameProgram("Renamed Program");
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(configProgramPda);
+      assert.equal(loyaltyProgram.name, "Renamed Program");
+      const metadata = await getTokenMetadata(connection, configMintPda, "confirmed", TOKEN_2022_PROGRAM_ID);
+      assert.equal(metadata!.name, "Renamed Program");
+    });
+
+    it("Rejects out-of-bounds names", async () => {
+      await assertError(() => renameProgram(""), "NameEmpty");
+      await assertError(() => renameProgram("A".repeat(33)), "NameTooLong");
+    });
+
+    it("Rejects updates from anyone but the authority", async () => {
+      const { program } = await ctx;
+
+      await assertError(
+        () =>
+          program.methods
+            .updateProgramConfig(new anchor.BN(0), new anchor.BN(0), null)
+            .accountsPartial({ authority: configMerchant.publicKey, loyaltyProgram: configProgramPda })
+            .signers([configMerchant])
+            .rpc(),
+        "UnauthorizedAccess"
+      );
+    });
+  });
+
+  describe("Authority Transfer", () => {
+    const originalAuthority = Keypair.generate();
+    const newAuthority = Keypair.generate();
+    const transferMerchant = Keypair.generate();
+    const transferCustomer = Keypair.generate();
+    let transferProgramPda: PublicKey;
+    let transferMintPda: PublicKey;
+
+    const propose = (signer: Keypair, candidate: PublicKey) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .proposeAuthority(candidate)
+          .accountsPartial({ authority: signer.publicKey, loyaltyProgram: transferProgramPda })
+          .signers([signer])
+          .rpc({ commitment: "confirmed" })
+      );
+
+    const accept = (signer: Keypair) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .acceptAuthority()
+          .accountsPartial({ newAuthority: signer.publicKey, loyaltyProgram: transferProgramPda })
+          .signers([signer])
+          .rpc({ commitment: "confirmed" })
+      );
+
+    before(async () => {
+      const { program, connection } = await ctx;
+      for (const keypair of [originalAuthority, newAuthority, transferMerchant, transferCustomer]) {
+        await airdrop(connection, keypair.publicKey);
+      }
+
+      [transferProgramPda] = findProgramAddress(
+        [SEEDS.LOYALTY_PROGRAM, originalAuthority.publicKey.toBuffer()],
+        program.programId
+      );
+      [transferMintPda] = findProgramAddress([SEEDS.MINT, transferProgramPda.toBuffer()], program.programId);
+      const [transferMerchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, transferMerchant.publicKey.toBuffer(), transferProgramPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initializeProgram("Transfer Program", tokenSymbol, tokenUri, interestRate)
+        .accounts({ authority: originalAuthority.publicKey })
+        .signers([originalAuthority])
+        .rpc();
+
+      await program.methods
+        .registerMerchant(merchantName, "", "Retail", null, new anchor.BN(rewardRate))
+        .accountsPartial({
+          merchantAuthority: transferMerchant.publicKey,
+          loyaltyProgram: transferProgramPda,
+        })
+        .signers([transferMerchant])
+        .rpc();
+
+      await program.methods
+        .approveMerchant()
+        .accounts({ authority: originalAuthority.publicKey, loyaltyProgram: transferProgramPda, merchant: transferMerchantPda })
+        .signers([originalAuthority])
+        .rpc();
+
+      await program.methods
+        .registerCustomer()
+        .accounts({ customerAuthority: transferCustomer.publicKey, loyaltyProgram: transferProgramPda })
+        .signers([transferCustomer])
+        .rpc();
+    });
+
+    it("Records the initializing authority as the seed authority", async () => {
+      const { program } = await ctx;
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(transferProgramPda);
+      assertPublicKeyEqual(loyaltyProgram.authority, originalAuthority.publicKey);
+      assertPublicKeyEqual(loyaltyProgram.seedAuthority, originalAuthority.publicKey);
+      assert.isNull(loyaltyProgram.pendingAuthority);
+    });
+
+    it("Only lets the authority propose a valid new authority", async () => {
+      await assertError(() => propose(newAuthority, newAuthority.publicKey), "UnauthorizedAccess");
+      await assertError(() => propose(originalAuthority, originalAuthority.publicKey), "InvalidAuthority");
+      await assertError(() => propose(originalAuthority, PublicKey.default), "InvalidAuthority");
+    });
+
+    it("Cancels a pending transfer", async () => {
+      const { program } = await ctx;
+
+      await propose(originalAuthority, newAuthority.publicKey);
+      let loyaltyProgram = await program.account.loyaltyProgram.fetch(transferProgramPda);
+      assertPublicKeyEqual(loyaltyProgram.pendingAuthority!, newAuthority.publicKey);
+
+      const signature = await program.methods
+        .cancelAuthorityTransfer()
+        .accountsPartial({ authority: originalAuthority.publicKey, loyaltyProgram: transferProgramPda })
+        .signers([originalAuthority])
+        .rpc({ commitment: "confirmed" });
+
+      loyaltyProgram = await program.account.loyaltyProgram.fetch(transferProgramPda);
+      assert.isNull(loyaltyProgram.pendingAuthority);
+
+      const event = (await getEvents(await ctx, signature)).find(
+        (e) => e.name === "authorityTransferCancelledEvent"
+      );
+      assert.ok(event, "AuthorityTransferCancelledEvent not emitted");
+      assertPublicKeyEqual(event!.data.cancelledAuthority, newAuthority.publicKey);
+
+      await assertError(() => accept(newAuthority), "NoPendingAuthority");
+    });
+
+    it("Rejects acceptance by anyone but the pending authority", async () => {
+      await propose(originalAuthority, newAuthority.publicKey);
+
+      await assertError(() => accept(transferMerchant), "UnauthorizedAccess");
+      await assertError(() => accept(originalAuthority), "UnauthorizedAccess");
+    });
+
+    it("Hands over to the pending authority", async () => {
+      const { program } = await ctx;
+
+      const signature = await accept(newAuthority);
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(transferProgramPda);
+      assertPublicKeyEqual(loyaltyProgram.authority, newAuthority.publicKey);
+      assertPublicKeyEqual(loyaltyProgram.seedAuthority, originalAuthority.publicKey);
+      assert.isNull(loyaltyProgram.pendingAuthority);
+
+      const event = (await getEvents(await ctx, signature)).find(
+        (e) => e.name === "authorityTransferredEvent"
+      );
+      assert.ok(event, "AuthorityTransferredEvent not emitted");
+      assertPublicKeyEqual(event!.data.oldAuthority, originalAuthority.publicKey);
+      assertPublicKeyEqual(event!.data.newAuthority, newAuthority.publicKey);
+
+      // The previous authority has lost admin access
+      await assertError(
+        () =>
+          program.methods
+            .updateProgramConfig(new anchor.BN(0), null, null)
+            .accountsPartial({ authority: originalAuthority.publicKey, loyaltyProgram: transferProgramPda })
+            .signers([originalAuthority])
+            .rpc(),
+        "UnauthorizedAccess"
+      );
+
+      await program.methods
+        .updateTokenMetadata("Rotated Program", null, null)
+        .accountsPartial({ authority: newAuthority.publicKey, loyaltyProgram: transferProgramPda, mint: transferMintPda })
+        .signers([newAuthority])
+        .rpc();
+      assert.equal(
+        (await program.account.loyaltyProgram.fetch(transferProgramPda)).name,
+        "Rotated Program"
+      );
+    });
+
+    it("Keeps PDAs derived from the seed authority after the handover", async () => {
+      const { program } = await ctx;
+      const [transferMerchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, transferMerchant.publicKey.toBuffer(), transferProgramPda.toBuffer()],
+        program.programId
+      );
+      const [transferCustomerPda] = findProgramAddress(
+        [SEEDS.CUSTOMER, transferCustomer.publicKey.toBuffer(), transferProgramPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .issueRewards(new anchor.BN(10_000))
+        .accountsPartial({
+          merchantAuthority: transferMerchant.publicKey,
+          merchant: transferMerchantPda,
+          customer: transferCustomerPda,
+          loyaltyProgram: transferProgramPda,
+          mint: transferMintPda,
+          customerTokenAccount: getAssociatedTokenAddressSync(
+            transferMintPda,
+            transferCustomer.publicKey,
+            false,
+            TOKEN_2022_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID
+          ),
+          rewardRule: SystemProgram.programId,
+        })
+        .signers([transferMerchant])
+        .rpc();
+
+      const customer = await program.account.customer.fetch(transferCustomerPda);
+      assertBNGreaterThan(customer.totalEarned, 0);
+    });
+  });
+
+  describe("Pause State", () => {
+    const PAUSE_ISSUANCE = 1 << 0;
+    const PAUSE_REGISTRATION = 1 << 2;
+    const PAUSE_MERCHANT_OPS = 1 << 4;
+    const pauseAuthority = Keypair.generate();
+    const pauseMerchant = Keypair.generate();
+    const pauseCustomer = Keypair.generate();
+    let pauseProgramPda: PublicKey;
+    let pauseMintPda: PublicKey;
+    let pauseMerchantPda: PublicKey;
+    let pauseCustomerPda: PublicKey;
+
+    const setPauseState = (paused: number, signer: Keypair = pauseAuthority) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .setPauseState(paused)
+          .accountsPartial({ authority: signer.publicKey, loyaltyProgram: pauseProgramPda })
+          .signers([signer])
+          .rpc({ commitment: "confirmed" })
+      );
+
+    const issue = () =>
+      ctx.then(({ program }) =>
+        program.methods
+          .issueRewards(new anchor.BN(10_000))
+          .accountsPartial({
+            merchantAuthority: pauseMerchant.publicKey,
+            merchant: pauseMerchantPda,
+            customer: pauseCustomerPda,
+            loyaltyProgram: pauseProgramPda,
+            mint: pauseMintPda,
+            customerTokenAccount: getAssociatedTokenAddressSync(
+              pauseMintPda,
+              pauseCustomer.publicKey,
+              false,
+              TOKEN_2022_PROGRAM_ID,
+              ASSOCIATED_TOKEN_PROGRAM_ID
+            ),
+            rewardRule: SystemProgram.programId,
+          })
+          .signers([pauseMerchant])
+          .rpc()
+      );
+
+    const createOffer = (name: string) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .createRedemptionOffer(name, "Free coffee", "coffee", new anchor.BN(1), { discount: { percentage: 100 } }, null, null)
+          .accountsPartial({ merchantAuthority: pauseMerchant.publicKey, loyaltyProgram: pauseProgramPda })
+          .signers([pauseMerchant])
+          .rpc()
+      );
+
+    before(async () => {
+      const { program, connection } = await ctx;
+      for (const keypair of [pauseAuthority, pauseMerchant, pauseCustomer]) {
+        await airdrop(connection, keypair.publicKey);
+      }
+
+      [pauseProgramPda] = findProgramAddress(
+        [SEEDS.LOYALTY_PROGRAM, pauseAuthority.publicKey.toBuffer()],
+        program.programId
+      );
+      [pauseMintPda] = findProgramAddress([SEEDS.MINT, pauseProgramPda.toBuffer()], program.programId);
+      [pauseMerchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, pauseMerchant.publicKey.toBuffer(), pauseProgramPda.toBuffer()],
+        program.programId
+      );
+      [pauseCustomerPda] = findProgramAddress(
+        [SEEDS.CUSTOMER, pauseCustomer.publicKey.toBuffer(), pauseProgramPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initializeProgram("Pause Program", tokenSymbol, tokenUri, interestRate)
+        .accounts({ authority: pauseAuthority.publicKey })
+        .signers([pauseAuthority])
+        .rpc();
+
+      await program.methods
+        .registerMerchant(merchantName, "", "Retail", null, new anchor.BN(rewardRate))
+        .accountsPartial({
+          merchantAuthority: pauseMerchant.publicKey,
+          loyaltyProgram: pauseProgramPda,
+        })
+        .signers([pauseMerchant])
+        .rpc();
+
+      await program.methods
+        .approveMerchant()
+        .accounts({ authority: pauseAuthority.publicKey, loyaltyProgram: pauseProgramPda, merchant: pauseMerchantPda })
+        .signers([pauseAuthority])
+        .rpc();
+
+      await program.methods
+        .registerCustomer()
+        .accounts({ customerAuthority: pauseCustomer.publicKey, loyaltyProgram: pauseProgramPda })
+        .signers([pauseCustomer])
+        .rpc();
+    });
+
+    it("Rejects unknown flags and non-authority signers", async () => {
+      await assertError(() => setPauseState(1 << 7), "InvalidPauseFlags");
+      await assertError(() => setPauseState(PAUSE_ISSUANCE, pauseMerchant), "UnauthorizedAccess");
+    });
+
+    it("Pauses issuance and registration and emits the change", async () => {
+      const { program } = await ctx;
+      const paused = PAUSE_ISSUANCE | PAUSE_REGISTRATION;
+
+      const signature = await setPauseState(paused);
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(pauseProgramPda);
+      assert.equal(loyaltyProgram.paused, paused);
+
+      const event = (await getEvents(await ctx, signature)).find(
+        (e) => e.name === "pauseStateChangedEvent"
+      );
+      assert.ok(event, "PauseStateChangedEvent not emitted");
+      assert.equal(event!.data.oldPaused, 0);
+      assert.equal(event!.data.newPaused, paused);
+    });
+
+    it("Blocks issuance and registration while paused", async () => {
+      const { program, connection } = await ctx;
+      const newcomer = Keypair.generate();
+      await airdrop(connection, newcomer.publicKey);
+
+      await assertError(() => issue(), "ProgramPaused");
+      await assertError(
+        () =>
+          program.methods
+            .registerCustomer()
+            .accounts({ customerAuthority: newcomer.publicKey, loyaltyProgram: pauseProgramPda })
+            .signers([newcomer])
+            .rpc(),
+        "ProgramPaused"
+      );
+      await assertError(
+        () =>
+          program.methods
+            .registerMerchant("Paused Merchant", "", "Retail", null, new anchor.BN(rewardRate))
+            .accountsPartial({
+              merchantAuthority: newcomer.publicKey,
+              loyaltyProgram: pauseProgramPda,
+            })
+            .signers([newcomer])
+            .rpc(),
+        "ProgramPaused"
+      );
+    });
+
+    it("Keeps unpaused categories and admin configuration working", async () => {
+      const { program } = await ctx;
+
+      await createOffer("Open Offer");
+
+      await program.methods
+        .updateTokenMetadata("Paused Program", null, null)
+        .accountsPartial({ authority: pauseAuthority.publicKey, loyaltyProgram: pauseProgramPda, mint: pauseMintPda })
+        .signers([pauseAuthority])
+        .rpc();
+      assert.equal((await program.account.loyaltyProgram.fetch(pauseProgramPda)).name, "Paused Program");
+    });
+
+    it("Switches each category independently", async () => {
+      const { program } = await ctx;
+
+      await setPauseState(PAUSE_MERCHANT_OPS);
+      await assertError(() => createOffer("Closed Offer"), "ProgramPaused");
+
+      await issue();
+      assertBNGreaterThan((await program.account.customer.fetch(pauseCustomerPda)).totalEarned, 0);
+
+      await setPauseState(0);
+      await createOffer("Reopened Offer");
+    });
+  });
+
+  describe("Fee Withdrawal", () => {
+    const withdrawAuthority = Keypair.generate();
+    const withdrawMerchant = Keypair.generate();
+    const withdrawCustomer = Keypair.generate();
+    const destination = Keypair.generate();
+    let withdrawProgramPda: PublicKey;
+    let withdrawMintPda: PublicKey;
+    let treasuryPda: PublicKey;
+
+    const withdraw = (signer: Keypair, amount: anchor.BN) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .withdrawFees(amount)
+          .accountsPartial({
+            authority: signer.publicKey,
+            loyaltyProgram: withdrawProgramPda,
+            destination: destination.publicKey,
+          })
+          .signers([signer])
+          .rpc({ commitment: "confirmed" })
+      );
+
+    const unwithdrawnFees = async () => {
+      const { program } = await ctx;
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(withdrawProgramPda);
+      return loyaltyProgram.totalFeesCollected.sub(loyaltyProgram.totalFeesWithdrawn);
+    };
+
+    before(async () => {
+      const { program, connection } = await ctx;
+      for (const keypair of [withdrawAuthority, withdrawMerchant, withdrawCustomer, destination]) {
+        await airdrop(connection, keypair.publicKey);
+      }
+
+      [withdrawProgramPda] = findProgramAddress(
+        [SEEDS.LOYALTY_PROGRAM, withdrawAuthority.publicKey.toBuffer()],
+        program.programId
+      );
+      [withdrawMintPda] = findProgramAddress([SEEDS.MINT, withdrawProgramPda.toBuffer()], program.programId);
+      [treasuryPda] = findProgramAddress(
+        [Buffer.from("treasury"), withdrawProgramPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initializeProgram("Withdraw Program", tokenSymbol, tokenUri, interestRate)
+        .accounts({ authority: withdrawAuthority.publicKey })
+        .signers([withdrawAuthority])
+        .rpc();
+    });
+
+    it("Creates a rent-exempt treasury PDA at initialization", async () => {
+      const { program, connection } = await ctx;
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(withdrawProgramPda);
+      assertPublicKeyEqual(loyaltyProgram.treasury, treasuryPda);
+      assertBNEqual(loyaltyProgram.totalFeesWithdrawn, 0);
+
+      const rentExempt = await connection.getMinimumBalanceForRentExemption(0);
+      assert.equal(await connection.getBalance(treasuryPda), rentExempt);
+    });
+
+    it("Collects registration and issuance fees in the treasury PDA", async () => {
+      const { program, connection } = await ctx;
+      const [withdrawMerchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, withdrawMerchant.publicKey.toBuffer(), withdrawProgramPda.toBuffer()],
+        program.programId
+      );
+      const [withdrawCustomerPda] = findProgramAddress(
+        [SEEDS.CUSTOMER, withdrawCustomer.publicKey.toBuffer(), withdrawProgramPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .registerMerchant(merchantName, "", "Retail", null, new anchor.BN(rewardRate))
+        .accounts({ merchantAuthority: withdrawMerchant.publicKey, loyaltyProgram: withdrawProgramPda })
+        .signers([withdrawMerchant])
+        .rpc();
+
+      // The registration fee is held until the merchant is approved
+      let loyaltyProgram = await program.account.loyaltyProgram.fetch(withdrawProgramPda);
+      assertBNEqual(loyaltyProgram.pendingRegistrationFees, 10_000_000);
+      assertBNEqual(loyaltyProgram.totalFeesCollected, 0);
+
+      await program.methods
+        .approveMerchant()
+        .accounts({ authority: withdrawAuthority.publicKey, loyaltyProgram: withdrawProgramPda, merchant: withdrawMerchantPda })
+        .signers([withdrawAuthority])
+        .rpc();
+
+      await program.methods
+        .registerCustomer()
+        .accounts({ customerAuthority: withdrawCustomer.publicKey, loyaltyProgram: withdrawProgramPda })
+        .signers([withdrawCustomer])
+        .rpc();
+
+      await program.methods
+        .issueRewards(new anchor.BN(10_000))
+        .accountsPartial({
+          merchantAuthority: withdrawMerchant.publicKey,
+          merchant: withdrawMerchantPda,
+          customer: withdrawCustomerPda,
+          loyaltyProgram: withdrawProgramPda,
+          mint: withdrawMintPda,
+          customerTokenAccount: getAssociatedTokenAddressSync(
+            withdrawMintPda,
+            withdrawCustomer.publicKey,
+            false,
+            TOKEN_2022_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID
+          ),
+          rewardRule: SystemProgram.programId,
+        })
+        .signers([withdrawMerchant])
+        .rpc();
+
+      loyaltyProgram = await program.account.loyaltyProgram.fetch(withdrawProgramPda);
+      assertBNEqual(loyaltyProgram.pendingRegistrationFees, 0);
+      assertBNGreaterThan(loyaltyProgram.totalFeesCollected, 10_000_000);
+
+      const rentExempt = await connection.getMinimumBalanceForRentExemption(0);
+      assert.equal(
+        await connection.getBalance(treasuryPda),
+        rentExempt + loyaltyProgram.totalFeesCollected.toNumber()
+      );
+    });
+
+    it("Rejects invalid withdrawals", async () => {
+      await assertError(() => withdraw(withdrawAuthority, new anchor.BN(0)), "InvalidRewardAmount");
+      await assertError(() => withdraw(withdrawMerchant, new anchor.BN(1)), "UnauthorizedAccess");
+      await assertError(
+        async () => withdraw(withdrawAuthority, (await unwithdrawnFees()).addn(1)),
+        "InsufficientTreasuryBalance"
+      );
+    });
+
+    it("Withdraws fees to the chosen destination", async () => {
+      const { program, connection } = await ctx;
+      const before = await connection.getBalance(destination.publicKey);
+
+      const signature = await withdraw(withdrawAuthority, new anchor.BN(1_000_000));
+
+      assert.equal(await connection.getBalance(destination.publicKey), before + 1_000_000);
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(withdrawProgramPda);
+      assertBNEqual(loyaltyProgram.totalFeesWithdrawn, 1_000_000);
+
+      const event = (await getEvents(await ctx, signature)).find((e) => e.name === "feesWithdrawnEvent");
+      assert.ok(event, "FeesWithdrawnEvent not emitted");
+      assertPublicKeyEqual(event!.data.destination, destination.publicKey);
+      assertBNEqual(event!.data.amount, 1_000_000);
+      assertBNEqual(event!.data.totalFeesWithdrawn, 1_000_000);
+    });
+
+    it("Keeps the treasury rent-exempt after withdrawing every fee", async () => {
+      const { program, connection } = await ctx;
+
+      await withdraw(withdrawAuthority, await unwithdrawnFees());
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(withdrawProgramPda);
+      assertBNEqual(loyaltyProgram.totalFeesWithdrawn, loyaltyProgram.totalFeesCollected);
+      const rentExempt = await connection.getMinimumBalanceForRentExemption(0);
+      assert.equal(await connection.getBalance(treasuryPda), rentExempt);
+
+      await assertError(() => withdraw(withdrawAuthority, new anchor.BN(1)), "InsufficientTreasuryBalance");
+    });
+
+    it("Does not release registration fees held for pending merchants", async () => {
+      const { program, connection } = await ctx;
+      const applicant = Keypair.generate();
+      await airdrop(connection, applicant.publicKey);
+
+      await program.methods
+        .registerMerchant("Pending Applicant", "", "Retail", null, new anchor.BN(rewardRate))
+        .accounts({ merchantAuthority: applicant.publicKey, loyaltyProgram: withdrawProgramPda })
+        .signers([applicant])
+        .rpc();
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(withdrawProgramPda);
+      assertBNEqual(loyaltyProgram.pendingRegistrationFees, 10_000_000);
+      await assertError(() => withdraw(withdrawAuthority, new anchor.BN(1)), "InsufficientTreasuryBalance");
+    });
+  });
+
+  describe("Fee Recipients", () => {
+    const splitAuthority = Keypair.generate();
+    const splitMerchant = Keypair.generate();
+    const recipients = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+    let splitProgramPda: PublicKey;
+
+    const setRecipients = (shares: { recipient: PublicKey; shareBps: number }[], signer: Keypair = splitAuthority) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .setFeeRecipients(shares)
+          .accountsPartial({ authority: signer.publicKey, loyaltyProgram: splitProgramPda })
+          .signers([signer])
+          .rpc({ commitment: "confirmed" })
+      );
+
+    const claim = (recipient: Keypair) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .claimFees()
+          .accountsPartial({ recipient: recipient.publicKey, loyaltyProgram: splitProgramPda })
+          .signers([recipient])
+          .rpc({ commitment: "confirmed" })
+      );
+
+    before(async () => {
+      const { program, connection } = await ctx;
+      for (const keypair of [splitAuthority, splitMerchant, ...recipients]) {
+        await airdrop(connection, keypair.publicKey);
+      }
+
+      [splitProgramPda] = findProgramAddress(
+        [SEEDS.LOYALTY_PROGRAM, splitAuthority.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initializeProgram("Split Program", tokenSymbol, tokenUri, interestRate)
+        .accounts({ authority: splitAuthority.publicKey })
+        .signers([splitAuthority])
+        .rpc();
+
+      // A 10 lamport registration fee splits 3/3/3 with 1 lamport of dust
+      await program.methods
+        .updateProgramConfig(new anchor.BN(10), null, null)
+        .accountsPartial({ authority: splitAuthority.publicKey, loyaltyProgram: splitProgramPda })
+        .signers([splitAuthority])
+        .rpc();
+    });
+
+    it("Rejects invalid splits", async () => {
+      const [first, second] = recipients;
+
+      await assertError(
+        () => setRecipients([{ recipient: first.publicKey, shareBps: 5_000 }]),
+        "InvalidFeeSplit"
+      );
+      await assertError(
+        () =>
+          setRecipients([
+            { recipient: first.publicKey, shareBps: 5_000 },
+            { recipient: first.publicKey, shareBps: 5_000 },
+          ]),
+        "InvalidFeeSplit"
+      );
+      await assertError(
+        () =>
+          setRecipients([
+            { recipient: first.publicKey, shareBps: 10_000 },
+            { recipient: second.publicKey, shareBps: 0 },
+          ]),
+        "InvalidFeeSplit"
+      );
+      await assertError(
+        () => setRecipients([{ recipient: first.publicKey, shareBps: 10_000 }], splitMerchant),
+        "UnauthorizedAccess"
+      );
+    });
+
+    it("Splits each fee and gives the rounding dust to the first recipient", async () => {
+      const { program } = await ctx;
+
+      const updateSignature = await setRecipients([
+        { recipient: recipients[0].publicKey, shareBps: 3_333 },
+        { recipient: recipients[1].publicKey, shareBps: 3_333 },
+        { recipient: recipients[2].publicKey, shareBps: 3_334 },
+      ]);
+      const updated = (await getEvents(await ctx, updateSignature)).find(
+        (e) => e.name === "feeRecipientsUpdatedEvent"
+      );
+      assert.ok(updated, "FeeRecipientsUpdatedEvent not emitted");
+      assert.deepEqual(updated!.data.sharesBps, [3_333, 3_333, 3_334]);
+
+      const [splitMerchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, splitMerchant.publicKey.toBuffer(), splitProgramPda.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .registerMerchant(merchantName, "", "Retail", null, new anchor.BN(rewardRate))
+        .accounts({ merchantAuthority: splitMerchant.publicKey, loyaltyProgram: splitProgramPda })
+        .signers([splitMerchant])
+        .rpc();
+
+      // The held registration fee is split once the merchant is approved
+      const signature = await program.methods
+        .approveMerchant()
+        .accounts({ authority: splitAuthority.publicKey, loyaltyProgram: splitProgramPda, merchant: splitMerchantPda })
+        .signers([splitAuthority])
+        .rpc({ commitment: "confirmed" });
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(splitProgramPda);
+      const accrued = loyaltyProgram.feeRecipients.map((r: any) => r.accrued.toNumber());
+      assert.deepEqual(accrued, [4, 3, 3]);
+
+      const distributions = (await getEvents(await ctx, signature)).filter(
+        (e) => e.name === "feeDistributedEvent"
+      );
+      assert.equal(distributions.length, 3);
+      distributions.forEach((event, i) => {
+        assertPublicKeyEqual(event.data.recipient, recipients[i].publicKey);
+        assertBNEqual(event.data.amount, accrued[i]);
+        assertBNEqual(event.data.totalFee, 10);
+      });
+    });
+
+    it("Keeps accrued recipient fees out of withdraw_fees", async () => {
+      const { program } = await ctx;
+
+      await assertError(
+        () =>
+          program.methods
+            .withdrawFees(new anchor.BN(1))
+            .accountsPartial({
+              authority: splitAuthority.publicKey,
+              loyaltyProgram: splitProgramPda,
+              destination: splitAuthority.publicKey,
+            })
+            .signers([splitAuthority])
+            .rpc(),
+        "InsufficientTreasuryBalance"
+      );
+    });
+
+    it("Rejects claims while fee claims are paused", async () => {
+      const { program } = await ctx;
+      const PAUSE_FEE_CLAIMS = 1 << 5;
+      const setPaused = (paused: number) =>
+        program.methods
+          .setPauseState(paused)
+          .accountsPartial({ authority: splitAuthority.publicKey, loyaltyProgram: splitProgramPda })
+          .signers([splitAuthority])
+          .rpc();
+
+      await setPaused(PAUSE_FEE_CLAIMS);
+      await assertError(() => claim(recipients[0]), "ProgramPaused");
+
+      await setPaused(0);
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(splitProgramPda);
+      assertBNEqual(loyaltyProgram.feeRecipients[0].accrued, 4);
+    });
+
+    it("Pays a recipient its accrued share on claim", async () => {
+      const { program, connection } = await ctx;
+      const [first] = recipients;
+      const before = await connection.getBalance(first.publicKey);
+
+      const signature = await claim(first);
+
+      // The provider wallet pays the transaction fee
+      assert.equal(await connection.getBalance(first.publicKey), before + 4);
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(splitProgramPda);
+      assertBNEqual(loyaltyProgram.feeRecipients[0].accrued, 0);
+      assertBNEqual(loyaltyProgram.feeRecipients[0].claimed, 4);
+      assertBNEqual(loyaltyProgram.totalFeesWithdrawn, 4);
+
+      const event = (await getEvents(await ctx, signature)).find((e) => e.name === "feesClaimedEvent");
+      assert.ok(event, "FeesClaimedEvent not emitted");
+      assertBNEqual(event!.data.amount, 4);
+      assertBNEqual(event!.data.totalClaimed, 4);
+
+      await assertError(() => claim(first), "NothingToClaim");
+    });
+
+    it("Rejects claims from non-recipients", async () => {
+      await assertError(() => claim(splitMerchant), "NotFeeRecipient");
+    });
+
+    it("Refuses to drop a recipient with unclaimed fees", async () => {
+      await assertError(
+        () =>
+          setRecipients([
+            { recipient: recipients[0].publicKey, shareBps: 5_000 },
+            { recipient: recipients[1].publicKey, shareBps: 5_000 },
+          ]),
+        "FeeRecipientHasUnclaimedFees"
+      );
+
+      await claim(recipients[2]);
+      await setRecipients([
+        { recipient: recipients[0].publicKey, shareBps: 5_000 },
+        { recipient: recipients[1].publicKey, shareBps: 5_000 },
+      ]);
+    });
+  });
+
+  describe("Tier Config", () => {
+    const tierAuthority = Keypair.generate();
+    const tierMerchant = Keypair.generate();
+    const tierCustomer = Keypair.generate();
+    let tierProgramPda: PublicKey;
+    let tierMintPda: PublicKey;
+    let tierConfigPda: PublicKey;
+    let tierMerchantPda: PublicKey;
+    let tierCustomerPda: PublicKey;
+
+    const tier = (name: string, threshold: number, multiplier: number) => ({
+      name,
+      threshold: new anchor.BN(threshold),
+      multiplier: new anchor.BN(multiplier),
+    });
+
+    const setTiers = (tiers: ReturnType<typeof tier>[], signer: Keypair = tierAuthority) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .setTierConfig(tiers)
+          .accountsPartial({ authority: signer.publicKey, loyaltyProgram: tierProgramPda })
+          .signers([signer])
+          .rpc({ commitment: "confirmed" })
+      );
+
+    // Issue rewards for a $100 purchase and return the tokens earned
+    const issue = async () => {
+      const { program } = await ctx;
+      const before = await program.account.customer.fetch(tierCustomerPda);
+      await program.methods
+        .issueRewards(new anchor.BN(10_000))
+        .accountsPartial({
+          merchantAuthority: tierMerchant.publicKey,
+          merchant: tierMerchantPda,
+          customer: tierCustomerPda,
+          loyaltyProgram: tierProgramPda,
+          mint: tierMintPda,
+          customerTokenAccount: getAssociatedTokenAddressSync(
+            tierMintPda,
+            tierCustomer.publicKey,
+            false,
+            TOKEN_2022_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID
+          ),
+          rewardRule: SystemProgram.programId,
+        })
+        .signers([tierMerchant])
+        .rpc();
+      const after = await program.account.customer.fetch(tierCustomerPda);
+      return after.totalEarned.sub(before.totalEarned);
+    };
+
+    before(async () => {
+      const { program, connection } = await ctx;
+      for (const keypair of [tierAuthority, tierMerchant, tierCustomer]) {
+        await airdrop(connection, keypair.publicKey);
+      }
+
+      [tierProgramPda] = findProgramAddress(
+        [SEEDS.LOYALTY_PROGRAM, tierAuthority.publicKey.toBuffer()],
+        program.programId
+      );
+      [tierMintPda] = findProgramAddress([SEEDS.MINT, tierProgramPda.toBuffer()], program.programId);
+      [tierConfigPda] = findProgramAddress(
+        [Buffer.from("tier_config"), tierProgramPda.toBuffer()],
+        program.programId
+      );
+      [tierMerchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, tierMerchant.publicKey.toBuffer(), tierProgramPda.toBuffer()],
+        program.programId
+      );
+      [tierCustomerPda] = findProgramAddress(
+        [SEEDS.CUSTOMER, tierCustomer.publicKey.toBuffer(), tierProgramPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initializeProgram("Tier Program", tokenSymbol, tokenUri, interestRate)
+        .accounts({ authority: tierAuthority.publicKey })
+        .signers([tierAuthority])
+        .rpc();
+
+      await program.methods
+        .registerMerchant(merchantName, "", "Retail", null, new anchor.BN(rewardRate))
+        .accounts({ merchantAuthority: tierMerchant.publicKey, loyaltyProgram: tierProgramPda })
+        .signers([tierMerchant])
+        .rpc();
+
+      await program.methods
+        .approveMerchant()
+        .accounts({ authority: tierAuthority.publicKey, loyaltyProgram: tierProgramPda, merchant: tierMerchantPda })
+        .signers([tierAuthority])
+        .rpc();
+
+      await program.methods
+        .registerCustomer()
+        .accounts({ customerAuthority: tierCustomer.publicKey, loyaltyProgram: tierProgramPda })
+        .signers([tierCustomer])
+        .rpc();
+    });
+
+    it("Starts with the default Bronze to Platinum schedule", async () => {
+      const { program } = await ctx;
+
+      const tierConfig = await program.account.tierConfig.fetch(tierConfigPda);
+      assertPublicKeyEqual(tierConfig.loyaltyProgram, tierProgramPda);
+      assert.deepEqual(
+        tierConfig.tiers.map((t: any) => t.name),
+        ["Bronze", "Silver", "Gold", "Platinum"]
+      );
+      assert.deepEqual(
+        tierConfig.tiers.map((t: any) => t.threshold.toNumber()),
+        [0, 1_000, 10_000, 50_000]
+      );
+      assert.deepEqual(
+        tierConfig.tiers.map((t: any) => t.multiplier.toNumber()),
+        [100, 125, 150, 200]
+      );
+    });
+
+    it("Rejects schedules that are empty, too long or out of order", async () => {
+      await assertError(() => setTiers([]), "InvalidTierConfig");
+      await assertError(
+        () =>
+          setTiers([
+            tier("T0", 0, 100),
+            tier("T1", 10, 110),
+            tier("T2", 20, 120),
+            tier("T3", 30, 130),
+            tier("T4", 40, 140),
+          ]),
+        "InvalidTierConfig"
+      );
+      await assertError(() => setTiers([tier("Entry", 1, 100)]), "InvalidTierConfig");
+      await assertError(
+        () => setTiers([tier("Bronze", 0, 100), tier("Silver", 500, 150), tier("Gold", 500, 200)]),
+        "InvalidTierConfig"
+      );
+      await assertError(() => setTiers([tier("Bronze", 0, 99)]), "InvalidTierConfig");
+      await assertError(() => setTiers([tier("", 0, 100)]), "NameEmpty");
+      await assertError(() => setTiers([tier("A".repeat(17), 0, 100)]), "NameTooLong");
+    });
+
+    it("Rejects schedule changes from anyone but the authority", async () => {
+      await assertError(() => setTiers([tier("Bronze", 0, 1_000)], tierMerchant), "UnauthorizedAccess");
+    });
+
+    it("Applies the new schedule to tiers and multipliers on issuance", async () => {
+      const { program } = await ctx;
+      const bronzeReward = await issue();
+
+      const signature = await setTiers([tier("Member", 0, 100), tier("Insider", 1, 150)]);
+
+      const tierConfig = await program.account.tierConfig.fetch(tierConfigPda);
+      assert.deepEqual(
+        tierConfig.tiers.map((t: any) => t.name),
+        ["Member", "Insider"]
+      );
+      const event = (await getEvents(await ctx, signature)).find(
+        (e) => e.name === "tierConfigUpdatedEvent"
+      );
+      assert.ok(event, "TierConfigUpdatedEvent not emitted");
+      assert.equal(event!.data.tiers.length, 2);
+
+      // Already past the Insider threshold: upgraded on the next issuance
+      await issue();
+      assertTierEqual((await program.account.customer.fetch(tierCustomerPda)).tier, "silver");
+
+      const insiderReward = await issue();
+      assertBNEqual(insiderReward, bronzeReward.muln(3).divn(2));
+    });
+  });
+
+  describe("Issuance Fee Rules", () => {
+    const feeAuthority = Keypair.generate();
+    const feeMerchant = Keypair.generate();
+    const feeCustomer = Keypair.generate();
+    let feeProgramPda: PublicKey;
+    let feeMintPda: PublicKey;
+    let feeMerchantPda: PublicKey;
+    let feeCustomerPda: PublicKey;
+
+    const volumeTier = (minTotalIssued: anchor.BN | number, feePerToken: number) => ({
+      minTotalIssued: new anchor.BN(minTotalIssued),
+      feePerToken: new anchor.BN(feePerToken),
+    });
+
+    const setVolumeTiers = (tiers: ReturnType<typeof volumeTier>[]) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .setVolumeFeeTiers(tiers)
+          .accountsPartial({ authority: feeAuthority.publicKey, loyaltyProgram: feeProgramPda })
+          .signers([feeAuthority])
+          .rpc()
+      );
+
+    const setOverride = (fee: number | null, signer: Keypair = feeAuthority) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .setMerchantFeeOverride(fee === null ? null : new anchor.BN(fee))
+          .accountsPartial({
+            authority: signer.publicKey,
+            loyaltyProgram: feeProgramPda,
+            merchant: feeMerchantPda,
+          })
+          .signers([signer])
+          .rpc({ commitment: "confirmed" })
+      );
+
+    // Issue rewards and return the emitted RewardsIssuedEvent
+    const issue = async () => {
+      const { program } = await ctx;
+      const signature = await program.methods
+        .issueRewards(new anchor.BN(10_000))
+        .accountsPartial({
+          merchantAuthority: feeMerchant.publicKey,
+          merchant: feeMerchantPda,
+          customer: feeCustomerPda,
+          loyaltyProgram: feeProgramPda,
+          mint: feeMintPda,
+          customerTokenAccount: getAssociatedTokenAddressSync(
+            feeMintPda,
+            feeCustomer.publicKey,
+            false,
+            TOKEN_2022_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID
+          ),
+          rewardRule: SystemProgram.programId,
+        })
+        .signers([feeMerchant])
+        .rpc({ commitment: "confirmed" });
+      const event = (await getEvents(await ctx, signature)).find((e) => e.name === "rewardsIssuedEvent");
+      assert.ok(event, "RewardsIssuedEvent not emitted");
+      return event!.data;
+    };
+
+    before(async () => {
+      const { program, connection } = await ctx;
+      for (const keypair of [feeAuthority, feeMerchant, feeCustomer]) {
+        await airdrop(connection, keypair.publicKey);
+      }
+
+      [feeProgramPda] = findProgramAddress(
+        [SEEDS.LOYALTY_PROGRAM, feeAuthority.publicKey.toBuffer()],
+        program.programId
+      );
+      [feeMintPda] = findProgramAddress([SEEDS.MINT, feeProgramPda.toBuffer()], program.programId);
+      [feeMerchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, feeMerchant.publicKey.toBuffer(), feeProgramPda.toBuffer()],
+        program.programId
+      );
+      [feeCustomerPda] = findProgramAddress(
+        [SEEDS.CUSTOMER, feeCustomer.publicKey.toBuffer(), feeProgramPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initializeProgram("Fee Rules Program", tokenSymbol, tokenUri, interestRate)
+        .accounts({ authority: feeAuthority.publicKey })
+        .signers([feeAuthority])
+        .rpc();
+
+      await program.methods
+        .registerMerchant(merchantName, "", "Retail", null, new anchor.BN(rewardRate))
+        .accounts({ merchantAuthority: feeMerchant.publicKey, loyaltyProgram: feeProgramPda })
+        .signers([feeMerchant])
+        .rpc();
+
+      await program.methods
+        .approveMerchant()
+        .accounts({ authority: feeAuthority.publicKey, loyaltyProgram: feeProgramPda, merchant: feeMerchantPda })
+        .signers([feeAuthority])
+        .rpc();
+
+      await program.methods
+        .registerCustomer()
+        .accounts({ customerAuthority: feeCustomer.publicKey, loyaltyProgram: feeProgramPda })
+        .signers([feeCustomer])
+        .rpc();
+    });
+
+    it("Charges the base fee without tiers or an override", async () => {
+      const event = await issue();
+      assert.deepEqual(event.feeRule, { base: {} });
+      assertBNEqual(event.feePerToken, 100);
+      assertBNEqual(event.platformFee, event.finalReward.muln(100));
+    });
+
+    it("Rejects volume schedules that are too long or not strictly increasing", async () => {
+      await assertError(
+        () => setVolumeTiers([volumeTier(100, 50), volumeTier(100, 40)]),
+        "InvalidVolumeFeeTiers"
+      );
+      await assertError(
+        () => setVolumeTiers([volumeTier(200, 50), volumeTier(100, 40)]),
+        "InvalidVolumeFeeTiers"
+      );
+      await assertError(
+        () =>
+          setVolumeTiers([
+            volumeTier(1, 90),
+            volumeTier(2, 80),
+            volumeTier(3, 70),
+            volumeTier(4, 60),
+            volumeTier(5, 50),
+          ]),
+        "InvalidVolumeFeeTiers"
+      );
+    });
+
+    it("Applies the highest volume tier the merchant has reached", async () => {
+      const { program } = await ctx;
+      const merchant = await program.account.merchant.fetch(feeMerchantPda);
+
+      await setVolumeTiers([volumeTier(1, 80), volumeTier(merchant.totalIssued.muln(1_000), 20)]);
+      let event = await issue();
+      assert.deepEqual(event.feeRule, { volumeTier: { index: 0 } });
+      assertBNEqual(event.feePerToken, 80);
+      assertBNEqual(event.platformFee, event.finalReward.muln(80));
+
+      const issued = (await program.account.merchant.fetch(feeMerchantPda)).totalIssued;
+      await setVolumeTiers([volumeTier(1, 80), volumeTier(issued, 20)]);
+      event = await issue();
+      assert.deepEqual(event.feeRule, { volumeTier: { index: 1 } });
+      assertBNEqual(event.feePerToken, 20);
+      assertBNEqual(event.platformFee, event.finalReward.muln(20));
+    });
+
+    it("Lets a merchant override win over the volume tiers", async () => {
+      const { program } = await ctx;
+
+      const signature = await setOverride(5);
+      assertBNEqual((await program.account.merchant.fetch(feeMerchantPda)).issuanceFeeOverride!, 5);
+      const overrideEvent = (await getEvents(await ctx, signature)).find(
+        (e) => e.name === "merchantFeeOverrideEvent"
+      );
+      assert.ok(overrideEvent, "MerchantFeeOverrideEvent not emitted");
+      assert.isNull(overrideEvent!.data.oldFeeOverride);
+      assertBNEqual(overrideEvent!.data.newFeeOverride, 5);
+
+      let event = await issue();
+      assert.deepEqual(event.feeRule, { merchantOverride: {} });
+      assertBNEqual(event.feePerToken, 5);
+      assertBNEqual(event.platformFee, event.finalReward.muln(5));
+
+      // A zero override waives the fee entirely
+      await setOverride(0);
+      event = await issue();
+      assert.deepEqual(event.feeRule, { merchantOverride: {} });
+      assertBNEqual(event.platformFee, 0);
+
+      await setOverride(null);
+      assert.isNull((await program.account.merchant.fetch(feeMerchantPda)).issuanceFeeOverride);
+      event = await issue();
+      assert.deepEqual(event.feeRule, { volumeTier: { index: 1 } });
+    });
+
+    it("Only lets the authority set fee overrides", async () => {
+      await assertError(() => setOverride(0, feeMerchant), "UnauthorizedAccess");
+    });
+  });
+
+  describe("SPL Token Fees", () => {
+    const splAuthority = Keypair.generate();
+    const splMerchant = Keypair.generate();
+    const splCustomer = Keypair.generate();
+    let splProgramPda: PublicKey;
+    let splMintPda: PublicKey;
+    let splMerchantPda: PublicKey;
+    let splCustomerPda: PublicKey;
+    let treasuryPda: PublicKey;
+    let feeMint: PublicKey;
+    let merchantFeeAccount: PublicKey;
+    let treasuryFeeAccount: PublicKey;
+    let destinationFeeAccount: PublicKey;
+
+    // Fee amounts in the new currency: 25 tokens to register, 0.001 per point issued
+    const tokenRegistrationFee = 25_000_000;
+    const tokenIssuanceFee = 1_000;
+    const tokenVolumeTiers = [{ minTotalIssued: new anchor.BN(1_000_000_000), feePerToken: new anchor.BN(500) }];
+
+    const setFeeCurrency = (
+      mint: PublicKey | null,
+      mintAccount: PublicKey | null = mint,
+      registrationFee = tokenRegistrationFee,
+      issuanceFee = tokenIssuanceFee,
+      volumeTiers = tokenVolumeTiers
+    ) =>
+      ctx.then(({ program }) =>
+        program.methods
+          .setFeeCurrency(mint, new anchor.BN(registrationFee), new anchor.BN(issuanceFee), volumeTiers)
+          .accountsPartial({
+            authority: splAuthority.publicKey,
+            loyaltyProgram: splProgramPda,
+            feeMint: mintAccount,
+          })
+          .signers([splAuthority])
+          .rpc({ commitment: "confirmed" })
+      );
+
+    // Withdraw every collected fee, through the fee token accounts when in token mode
+    const withdrawAll = async (tokenAccounts: boolean) => {
+      const { program } = await ctx;
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(splProgramPda);
+      const amount = loyaltyProgram.totalFeesCollected.sub(loyaltyProgram.totalFeesWithdrawn);
+      await program.methods
+        .withdrawFees(amount)
+        .accountsPartial({
+          authority: splAuthority.publicKey,
+          loyaltyProgram: splProgramPda,
+          destination: splAuthority.publicKey,
+          ...(tokenAccounts
+            ? { feeMint, destinationFeeAccount, treasuryFeeAccount, feeTokenProgram: TOKEN_PROGRAM_ID }
+            : {}),
+        })
+        .signers([splAuthority])
+        .rpc();
+      return amount;
+    };
+
+    const issueAccounts = () => ({
+      merchantAuthority: splMerchant.publicKey,
+      merchant: splMerchantPda,
+      customer: splCustomerPda,
+      loyaltyProgram: splProgramPda,
+      mint: splMintPda,
+      customerTokenAccount: getAssociatedTokenAddressSync(
+        splMintPda,
+        splCustomer.publicKey,
+        false,
+        TOKEN_2022_PROGRAM_ID,
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      ),
+      rewardRule: SystemProgram.programId,
+    });
+
+    const tokenBalance = async (account: PublicKey) => {
+      const { connection } = await ctx;
+      return (await getAccount(connection, account, "confirmed", TOKEN_PROGRAM_ID)).amount;
+    };
+
+    before(async () => {
+      const { program, connection } = await ctx;
+      for (const keypair of [splAuthority, splMerchant, splCustomer]) {
+        await airdrop(connection, keypair.publicKey);
+      }
+
+      [splProgramPda] = findProgramAddress(
+        [SEEDS.LOYALTY_PROGRAM, splAuthority.publicKey.toBuffer()],
+        program.programId
+      );
+      [splMintPda] = findProgramAddress([SEEDS.MINT, splProgramPda.toBuffer()], program.programId);
+      [splMerchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, splMerchant.publicKey.toBuffer(), splProgramPda.toBuffer()],
+        program.programId
+      );
+      [splCustomerPda] = findProgramAddress(
+        [SEEDS.CUSTOMER, splCustomer.publicKey.toBuffer(), splProgramPda.toBuffer()],
+        program.programId
+      );
+      [treasuryPda] = findProgramAddress([Buffer.from("treasury"), splProgramPda.toBuffer()], program.programId);
+
+      await program.methods
+        .initializeProgram("Stablecoin Fees", tokenSymbol, tokenUri, interestRate)
+        .accounts({ authority: splAuthority.publicKey })
+        .signers([splAuthority])
+        .rpc();
+
+      await program.methods
+        .registerMerchant(merchantName, "", "Retail", null, new anchor.BN(rewardRate))
+        .accounts({ merchantAuthority: splMerchant.publicKey, loyaltyProgram: splProgramPda })
+        .signers([splMerchant])
+        .rpc();
+
+      await program.methods
+        .approveMerchant()
+        .accounts({ authority: splAuthority.publicKey, loyaltyProgram: splProgramPda, merchant: splMerchantPda })
+        .signers([splAuthority])
+        .rpc();
+
+      await program.methods
+        .registerCustomer()
+        .accounts({ customerAuthority: splCustomer.publicKey, loyaltyProgram: splProgramPda })
+        .signers([splCustomer])
+        .rpc();
+
+      // Stand-in stablecoin on the classic SPL Token program
+      feeMint = await createMint(connection, splAuthority, splAuthority.publicKey, null, 6);
+      merchantFeeAccount = await createAssociatedTokenAccount(connection, splAuthority, feeMint, splMerchant.publicKey);
+      treasuryFeeAccount = await createAssociatedTokenAccount(
+        connection,
+        splAuthority,
+        feeMint,
+        treasuryPda,
+        undefined,
+        TOKEN_PROGRAM_ID,
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        true
+      );
+      destinationFeeAccount = await createAssociatedTokenAccount(
+        connection,
+        splAuthority,
+        feeMint,
+        splAuthority.publicKey
+      );
+      await mintTo(connection, splAuthority, feeMint, merchantFeeAccount, splAuthority, 1_000_000_000_000);
+    });
+
+    it("Refuses to change the fee currency while fees are unsettled", async () => {
+      await assertError(() => setFeeCurrency(feeMint), "FeesOutstanding");
+    });
+
+    it("Switches to the SPL fee mint once lamport fees are withdrawn", async () => {
+      const { program } = await ctx;
+      await withdrawAll(false);
+
+      // A lamport-denominated override that must not carry over to the token
+      await program.methods
+        .setMerchantFeeOverride(new anchor.BN(2_000_000))
+        .accountsPartial({ authority: splAuthority.publicKey, loyaltyProgram: splProgramPda, merchant: splMerchantPda })
+        .signers([splAuthority])
+        .rpc();
+
+      await assertError(() => setFeeCurrency(feeMint, null), "MissingFeeTokenAccounts");
+      await assertError(() => setFeeCurrency(feeMint, splMintPda), "InvalidFeeMint");
+      await assertError(
+        () => setFeeCurrency(feeMint, feeMint, tokenRegistrationFee, tokenIssuanceFee, [...tokenVolumeTiers, ...tokenVolumeTiers]),
+        "InvalidVolumeFeeTiers"
+      );
+
+      const signature = await setFeeCurrency(feeMint);
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(splProgramPda);
+      assertPublicKeyEqual(loyaltyProgram.feeMint!, feeMint);
+      assertBNEqual(loyaltyProgram.merchantRegistrationFee, tokenRegistrationFee);
+      assertBNEqual(loyaltyProgram.issuanceFeePerToken, tokenIssuanceFee);
+      assert.lengthOf(loyaltyProgram.volumeFeeTiers, 1);
+      assertBNEqual(loyaltyProgram.volumeFeeTiers[0].feePerToken, 500);
+      assert.equal(loyaltyProgram.feeCurrencyEpoch, 1);
+
+      const event = (await getEvents(await ctx, signature)).find(
+        (e) => e.name === "feeCurrencyChangedEvent"
+      );
+      assert.ok(event, "FeeCurrencyChangedEvent not emitted");
+      assert.isNull(event!.data.oldFeeMint);
+      assertPublicKeyEqual(event!.data.newFeeMint, feeMint);
+      assertBNEqual(event!.data.issuanceFeePerToken, tokenIssuanceFee);
+      assert.equal(event!.data.feeCurrencyEpoch, 1);
+    });
+
+    it("Requires matching fee token accounts in token mode", async () => {
+      const { program, connection } = await ctx;
+
+      await assertError(
+        () =>
+          program.methods
+            .issueRewards(new anchor.BN(10_000))
+            .accountsPartial(issueAccounts())
+            .signers([splMerchant])
+            .rpc(),
+        "MissingFeeTokenAccounts"
+      );
+
+      // The merchant's fee account must hold the configured mint
+      const otherMint = await createMint(connection, splAuthority, splAuthority.publicKey, null, 6);
+      const otherAccount = await createAssociatedTokenAccount(connection, splAuthority, otherMint, splMerchant.publicKey);
+      await assertError(
+        () =>
+          program.methods
+            .issueRewards(new anchor.BN(10_000))
+            .accountsPartial({
+              ...issueAccounts(),
+              feeMint,
+              merchantFeeAccount: otherAccount,
+              treasuryFeeAccount,
+              feeTokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([splMerchant])
+            .rpc(),
+        "InvalidFeeMint"
+      );
+    });
+
+    it("Charges the new token fee right after the switch, ignoring the old override", async () => {
+      const { program, connection } = await ctx;
+      const merchantBefore = await tokenBalance(merchantFeeAccount);
+      const treasuryBefore = await tokenBalance(treasuryFeeAccount);
+      const lamportsBefore = await connection.getBalance(treasuryPda);
+
+      const signature = await program.methods
+        .issueRewards(new anchor.BN(10_000))
+        .accountsPartial({
+          ...issueAccounts(),
+          feeMint,
+          merchantFeeAccount,
+          treasuryFeeAccount,
+          feeTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([splMerchant])
+        .rpc({ commitment: "confirmed" });
+
+      const event = (await getEvents(await ctx, signature)).find((e) => e.name === "rewardsIssuedEvent");
+      assert.ok(event, "RewardsIssuedEvent not emitted");
+      assert.deepEqual(event!.data.feeRule, { base: {} });
+      assertBNEqual(event!.data.feePerToken, tokenIssuanceFee);
+      assertBNEqual(event!.data.platformFee, event!.data.finalReward.muln(tokenIssuanceFee));
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(splProgramPda);
+      const fee = BigInt(loyaltyProgram.totalFeesCollected.sub(loyaltyProgram.totalFeesWithdrawn).toString());
+      assert.equal(fee, BigInt(event!.data.platformFee.toString()));
+      assert.equal(await tokenBalance(merchantFeeAccount), merchantBefore - fee);
+      assert.equal(await tokenBalance(treasuryFeeAccount), treasuryBefore + fee);
+      assert.equal(await connection.getBalance(treasuryPda), lamportsBefore);
+    });
+
+    it("Withdraws token fees and only then switches back to lamports", async () => {
+      const { program } = await ctx;
+      await assertError(() => setFeeCurrency(null), "FeesOutstanding");
+
+      const destinationBefore = await tokenBalance(destinationFeeAccount);
+      const amount = await withdrawAll(true);
+      assert.equal(await tokenBalance(destinationFeeAccount), destinationBefore + BigInt(amount.toString()));
+      assert.equal(await tokenBalance(treasuryFeeAccount), BigInt(0));
+
+      await setFeeCurrency(null, null, 1_000_000, 1, []);
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(splProgramPda);
+      assert.isNull(loyaltyProgram.feeMint);
+      assertBNEqual(loyaltyProgram.issuanceFeePerToken, 1);
+      assert.lengthOf(loyaltyProgram.volumeFeeTiers, 0);
+    });
+  });
+
+  describe("Migrate Account", () => {
+    // Preloaded from tests/fixtures (original unversioned layout, see Anchor.toml)
+    const baselineAuthority = new PublicKey("5VqFyD9CUR9VWJ3Ffvvc34VZcZEQg1dgUZshV3uwJeM5");
+    const baselineLoyaltyProgram = new PublicKey("EZakvNWx11PTNwZvPhUgRx4FuQD3Ts3UcXyxSrDeCB5n");
+    const baselineMerchantPda = new PublicKey("ArbAVbYUxHPmuASEL39BVcKhe5ArRTq2rathmLLYSLNc");
+    const inactiveMerchantPda = new PublicKey("DZJofUxKR8q84hyZ2ntNmJvZc4ZYdqkzGjjzTngqQDgT");
+    const baselineCustomerPda = new PublicKey("HyE86CiYQnUSA6KWHBLTCn7qFfZhMugzXLBnBnvncMkv");
+
+    it("Requires the TierConfig and treasury accounts to migrate a loyalty program", async () => {
+      const { program, authority } = await ctx;
+
+      await assertError(
+        () =>
+          program.methods
+            .migrateAccount()
+            .accountsPartial({
+              payer: authority.publicKey,
+              account: baselineLoyaltyProgram,
+              tierConfig: null,
+              platformTreasury: null,
+            })
+            .rpc(),
+        "MissingMigrationAccounts"
+      );
+    });
+
+    it("Migrates an original loyalty program to a treasury PDA and TierConfig", async () => {
+      const { program, authority, connection } = await ctx;
+      const [treasuryPda, treasuryBump] = findProgramAddress(
+        [Buffer.from("treasury"), baselineLoyaltyProgram.toBuffer()],
+        program.programId
+      );
+      const [tierConfigPda] = findProgramAddress(
+        [Buffer.from("tier_config"), baselineLoyaltyProgram.toBuffer()],
+        program.programId
+      );
+
+      const before = await connection.getAccountInfo(baselineLoyaltyProgram);
+      assert.equal(before!.data.length, 191);
+
+      await program.methods
+        .migrateAccount()
+        .accountsPartial({
+          payer: authority.publicKey,
+          account: baselineLoyaltyProgram,
+          tierConfig: tierConfigPda,
+          platformTreasury: treasuryPda,
+        })
+        .rpc();
+
+      const after = await connection.getAccountInfo(baselineLoyaltyProgram);
+      assert.equal(after!.data.length, program.account.loyaltyProgram.size);
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(baselineLoyaltyProgram);
+      assert.equal(loyaltyProgram.version, 2);
+      assertPublicKeyEqual(loyaltyProgram.authority, baselineAuthority);
+      assertPublicKeyEqual(loyaltyProgram.seedAuthority, baselineAuthority);
+      assert.equal(loyaltyProgram.name, "Baseline Rewards");
+      assertBNEqual(loyaltyProgram.totalMerchants, 2);
+      assertBNEqual(loyaltyProgram.totalTokensIssued, 12_500);
+      assert.equal(loyaltyProgram.interestRate, 500);
+      assert.equal(loyaltyProgram.bump, 252);
+
+      // Fees paid to the old wallet treasury count as withdrawn; new fees go to the PDA
+      assertPublicKeyEqual(loyaltyProgram.treasury, treasuryPda);
+      assert.equal(loyaltyProgram.treasuryBump, treasuryBump);
+      assertBNEqual(loyaltyProgram.totalFeesCollected, 20_001_250);
+      assertBNEqual(loyaltyProgram.totalFeesWithdrawn, 20_001_250);
+      const rentExempt = await connection.getMinimumBalanceForRentExemption(0);
+      assert.isAtLeast(await connection.getBalance(treasuryPda), rentExempt);
+
+      // The original mint cannot burn expired points, so they keep never expiring
+      assertBNEqual(loyaltyProgram.pointsExpiryPeriod, 0);
+      assert.ok("transferable" in loyaltyProgram.transferMode);
+      assert.ok(loyaltyProgram.reserved.every((byte: number) => byte === 0));
+
+      const tierConfig = await program.account.tierConfig.fetch(tierConfigPda);
+      assertPublicKeyEqual(tierConfig.loyaltyProgram, baselineLoyaltyProgram);
+      assert.deepEqual(
+        tierConfig.tiers.map((tier: any) => [tier.name, tier.threshold.toNumber(), tier.multiplier.toNumber()]),
+        [
+          ["Bronze", 0, 100],
+          ["Silver", 1_000, 125],
+          ["Gold", 10_000, 150],
+          ["Platinum", 50_000, 200],
+        ]
+      );
+    });
+
+    it("Migrates an active original merchant as approved", async () => {
+      const { program, authority, connection } = await ctx;
+
+      const before = await connection.getAccountInfo(baselineMerchantPda);
+      assert.equal(before!.data.length, 698);
+
+      await program.methods
+        .migrateAccount()
+        .accounts({
+          payer: authority.publicKey,
+          account: baselineMerchantPda,
+        })
+        .rpc();
+
+      const after = await connection.getAccountInfo(baselineMerchantPda);
+      assert.equal(after!.data.length, program.account.merchant.size);
+
+      const merchant = await program.account.merchant.fetch(baselineMerchantPda);
+      assert.equal(merchant.version, 2);
+      assertPublicKeyEqual(
+        merchant.authority,
+        new PublicKey("wNJyitkU7LasrkBtXXmcMYLE1Ae5FhNkULQXFu46GSh")
+      );
+      assertPublicKeyEqual(merchant.loyaltyProgram, baselineLoyaltyProgram);
+      assert.equal(merchant.name, "Baseline Bakery");
+      assert.equal(merchant.category, "Food & Drink");
+      assertBNEqual(merchant.rewardRate, 10);
+      assertBNEqual(merchant.totalIssued, 12_500);
+      assertBNEqual(merchant.totalRedeemed, 2_000);
+      assert.ok("approved" in merchant.status);
+      assertBNEqual(merchant.registrationFeePaid, 0);
+      assert.isNull(merchant.issuanceFeeOverride);
+      assertBNEqual(merchant.outstandingPoints, 0);
+      assert.equal(merchant.bump, 255);
+      assertBNEqual(merchant.createdAt, 1_700_100_000);
+      assert.ok(merchant.reserved.every((byte: number) => byte === 0));
+    });
+
+    it("Migrates a deactivated original merchant as suspended", async () => {
+      const { program, authority } = await ctx;
+
+      await program.methods
+        .migrateAccount()
+        .accounts({
+          payer: authority.publicKey,
+          account: inactiveMerchantPda,
+        })
+        .rpc();
+
+      const merchant = await program.account.merchant.fetch(inactiveMerchantPda);
+      assert.equal(merchant.name, "Closed Cafe");
+      assert.ok("suspended" in merchant.status);
+    });
+
+    it("Migrates an original customer and keeps its streak running", async () => {
+      const { program, authority, connection } = await ctx;
+
+      const before = await connection.getAccountInfo(baselineCustomerPda);
+      assert.equal(before!.data.length, 116);
+
+      await program.methods
+        .migrateAccount()
+        .accounts({
+          payer: authority.publicKey,
+          account: baselineCustomerPda,
+        })
+        .rpc();
+
+      const after = await connection.getAccountInfo(baselineCustomerPda);
+      assert.equal(after!.data.length, program.account.customer.size);
+
+      const customer = await program.account.customer.fetch(baselineCustomerPda);
+      assert.equal(customer.version, 2);
+      assertPublicKeyEqual(
+        customer.wallet,
+        new PublicKey("44sTdZ7AT8deCXkDDhZeJ9cFmvgAQnzB52cLWsh284t3")
+      );
+      assertPublicKeyEqual(customer.loyaltyProgram, baselineLoyaltyProgram);
+      assertBNEqual(customer.totalEarned, 12_500);
+      assertBNEqual(customer.totalRedeemed, 2_000);
+      assertTierEqual(customer.tier, "gold");
+      assertBNEqual(customer.transactionCount, 7);
+      assert.equal(customer.streakDays, 3);
+      assert.equal(customer.longestStreak, 3);
+      assertBNEqual(customer.streakDay, Math.floor(1_760_000_000 / 86_400));
+      assert.equal(customer.bump, 250);
+      assertBNEqual(customer.joinedAt, 1_750_000_000);
+      assert.ok(customer.reserved.every((byte: number) => byte === 0));
+    });
+
+    it("Fails to migrate an account twice", async () => {
+      const { program, authority } = await ctx;
+
+      await assertError(
+        () =>
+          program.methods
+            .migrateAccount()
+            .accounts({
+              payer: authority.publicKey,
+              account: baselineCustomerPda,
+            })
+            .rpc(),
+        "AccountAlreadyMigrated"
+      );
+    });
+
+    it("Fails to migrate an account created with the current layout", async () => {
+      const { program, authority } = await ctx;
+
+      await assertError(
+        () =>
+          program.methods
+            .migrateAccount()
+            .accounts({
+              payer: authority.publicKey,
+              account: customerPda,
+            })
+            .rpc(),
+        "AccountAlreadyMigrated"
+      );
+    });
+  });
+
+  describe("Soulbound Mode", () => {
+    let soulbound: FundedProgram;
+    let alice: Keypair;
+    let bob: Keypair;
+
+    before(async () => {
+      soulbound = await createFundedProgram(await ctx, { soulbound: {} }, 2);
+      [alice, bob] = soulbound.customers;
+    });
+
+    it("Creates the mint with the NonTransferable extension", async () => {
+      const { program, connection } = await ctx;
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(soulbound.loyaltyProgram);
+      assert.deepEqual(loyaltyProgram.transferMode, { soulbound: {} });
+
+      const mint = await getMint(connection, soulbound.mint, "confirmed", TOKEN_2022_PROGRAM_ID);
+      assert.include(getExtensionTypes(mint.tlvData), ExtensionType.NonTransferable);
+    });
+
+    it("Fails to transfer points between two customers", async () => {
+      const { connection } = await ctx;
+
+      await assertError(
+        () =>
+          transferChecked(
+            connection,
+            alice,
+            soulbound.tokenAccountOf(alice.publicKey),
+            soulbound.mint,
+            soulbound.tokenAccountOf(bob.publicKey),
+            alice,
+            100,
+            6,
+            [],
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+          ),
+        "0x25" // TokenError::NonTransferable
+      );
+
+      const bobAccount = await getAccount(
+        connection,
+        soulbound.tokenAccountOf(bob.publicKey),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(bobAccount.amount, BigInt(0));
+    });
+
+    it("Still allows soulbound points to be burned", async () => {
+      const { connection } = await ctx;
+      const aliceTokenAccount = soulbound.tokenAccountOf(alice.publicKey);
+      const before = await getAccount(connection, aliceTokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID);
+
+      await burnChecked(
+        connection,
+        alice,
+        aliceTokenAccount,
+        soulbound.mint,
+        alice,
+        100,
+        6,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      const after = await getAccount(connection, aliceTokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID);
+      assert.equal(after.amount, before.amount - BigInt(100));
+    });
+  });
+
+  describe("Registered Customers Only Mode", () => {
+    const hookProgramId = new PublicKey("5WthPonX3iRCfi1C7UECQ3hy4CExxvzRRYxhYBf8Rjy4");
+    let funded: FundedProgram;
+    let alice: Keypair;
+    let bob: Keypair;
+    let transferStatsPda: PublicKey;
+
+    before(async () => {
+      const { program } = await ctx;
+      const authority = Keypair.generate();
+      // The hook PDAs derive from the mint, so derive it up front from a known authority
+      const [loyaltyProgram] = findProgramAddress(
+        [SEEDS.LOYALTY_PROGRAM, authority.publicKey.toBuffer()],
+        program.programId
+      );
+      const [mint] = findProgramAddress([SEEDS.MINT, loyaltyProgram.toBuffer()], program.programId);
+      const [extraAccountMetaList] = findProgramAddress(
+        [Buffer.from("extra-account-metas"), mint.toBuffer()],
+        hookProgramId
+      );
+      [transferStatsPda] = findProgramAddress([Buffer.from("transfer_stats"), mint.toBuffer()], hookProgramId);
+
+      funded = await createFundedProgram(
+        await ctx,
+        { registeredCustomersOnly: {} },
+        2,
+        { extraAccountMetaList, transferStats: transferStatsPda, transferHookProgram: hookProgramId },
+        authority
+      );
+      [alice, bob] = funded.customers;
+    });
+
+    it("Creates the mint with the TransferHook extension", async () => {
+      const { connection } = await ctx;
+      const mint = await getMint(connection, funded.mint, "confirmed", TOKEN_2022_PROGRAM_ID);
+      assert.include(getExtensionTypes(mint.tlvData), ExtensionType.TransferHook);
+    });
+
+    it("Allows transfers to a registered customer", async () => {
+      const { connection } = await ctx;
+      await createAssociatedTokenAccount(
+        connection,
+        bob,
+        funded.mint,
+        bob.publicKey,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      await transferCheckedWithTransferHook(
+        connection,
+        alice,
+        funded.tokenAccountOf(alice.publicKey),
+        funded.mint,
+        funded.tokenAccountOf(bob.publicKey),
+        alice,
+        BigInt(100),
+        6,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      const bobAccount = await getAccount(
+        connection,
+        funded.tokenAccountOf(bob.publicKey),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(bobAccount.amount, BigInt(100));
+    });
+
+    it("Fails to transfer to an unregistered wallet", async () => {
+      const { connection } = await ctx;
+      const stranger = Keypair.generate();
+      await airdrop(connection, stranger.publicKey);
+      await createAssociatedTokenAccount(
+        connection,
+        stranger,
+        funded.mint,
+        stranger.publicKey,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      await assertError(
+        () =>
+          transferCheckedWithTransferHook(
+            connection,
+            alice,
+            funded.tokenAccountOf(alice.publicKey),
+            funded.mint,
+            funded.tokenAccountOf(stranger.publicKey),
+            alice,
+            BigInt(100),
+            6,
+            [],
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+          ),
+        "DestinationNotRegistered"
+      );
+    });
+
+    it("Tracks transfer stats in the hook program", async () => {
+      const { connection } = await ctx;
+      const info = await connection.getAccountInfo(transferStatsPda);
+      assert.isNotNull(info);
+      // 8-byte discriminator, then mint (32), then total_transfers (u64)
+      assert.equal(info!.data.readBigUInt64LE(8 + 32), BigInt(1));
+    });
+  });
+
+  describe("Point Expiry", () => {
+    let funded: FundedProgram;
+    let alice: Keypair;
+    let alicePda: PublicKey;
+    let pointLotsPda: PublicKey;
+
+    before(async () => {
+      const { program } = await ctx;
+      funded = await createFundedProgram(await ctx, { transferable: {} }, 1);
+      [alice] = funded.customers;
+      [alicePda] = findProgramAddress(
+        [SEEDS.CUSTOMER, alice.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      [pointLotsPda] = findProgramAddress([Buffer.from("point_lots"), alicePda.toBuffer()], program.programId);
+    });
+
+    it("Creates the mint with the loyalty program as permanent delegate", async () => {
+      const { connection } = await ctx;
+      const mint = await getMint(connection, funded.mint, "confirmed", TOKEN_2022_PROGRAM_ID);
+      assert.include(getExtensionTypes(mint.tlvData), ExtensionType.PermanentDelegate);
+    });
+
+    it("Tracks issued points as a lot expiring after the default period", async () => {
+      const { program } = await ctx;
+      const customer = await program.account.customer.fetch(alicePda);
+      const pointLots = await program.account.pointLots.fetch(pointLotsPda);
+
+      assert.equal(pointLots.lots.length, 1);
+      assertBNEqual(pointLots.lots[0].amount, customer.totalEarned.toNumber());
+      assertBNEqual(customer.nextExpiryAmount, customer.totalEarned.toNumber());
+
+      const oneYear = 365 * 86_400;
+      assert.isAtLeast(customer.nextExpiryAt.toNumber(), customer.joinedAt.toNumber() + oneYear);
+      assert.equal(customer.nextExpiryAt.toNumber() % 86_400, 0); // rounded to a day boundary
+    });
+
+    it("Leaves unexpired points untouched when the crank runs", async () => {
+      const { program, connection } = await ctx;
+      const cranker = Keypair.generate();
+      const before = await getAccount(
+        connection,
+        funded.tokenAccountOf(alice.publicKey),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      await program.methods
+        .expirePoints()
+        .accountsPartial({
+          cranker: cranker.publicKey,
+          loyaltyProgram: funded.loyaltyProgram,
+          customer: alicePda,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+        })
+        .signers([cranker])
+        .rpc();
+
+      const after = await getAccount(
+        connection,
+        funded.tokenAccountOf(alice.publicKey),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(after.amount, before.amount);
+
+      const customer = await program.account.customer.fetch(alicePda);
+      assertBNEqual(customer.totalExpired, 0);
+      assert.equal((await program.account.pointLots.fetch(pointLotsPda)).lots.length, 1);
+    });
+
+    it("Creates non-expiring lots once the expiry period is disabled", async () => {
+      const { program } = await ctx;
+      const [merchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, funded.merchant.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .updateProgramConfig(null, null, new anchor.BN(0))
+        .accountsPartial({ authority: funded.authority.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.authority])
+        .rpc();
+
+      const before = await program.account.customer.fetch(alicePda);
+
+      await program.methods
+        .issueRewards(new anchor.BN(5_000))
+        .accountsPartial({
+          merchantAuthority: funded.merchant.publicKey,
+          merchant: merchantPda,
+          customer: alicePda,
+          loyaltyProgram: funded.loyaltyProgram,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+          rewardRule: SystemProgram.programId,
+        })
+        .signers([funded.merchant])
+        .rpc();
+
+      const pointLots = await program.account.pointLots.fetch(pointLotsPda);
+      assert.equal(pointLots.lots.length, 2);
+      assert.equal(pointLots.lots[1].expiresAt.toString(), "9223372036854775807"); // i64::MAX
+
+      // The earliest expiring lot is unchanged
+      const after = await program.account.customer.fetch(alicePda);
+      assertBNEqual(after.nextExpiryAt, before.nextExpiryAt.toNumber());
+      assertBNEqual(after.nextExpiryAmount, before.nextExpiryAmount.toNumber());
+    });
+
+    it("Keeps crediting after a customer fills every lot slot", async () => {
+      const { program, connection } = await ctx;
+      const busy = await createFundedProgram(await ctx, { transferable: {} }, 1);
+      const [bob] = busy.customers;
+      const [bobPda] = findProgramAddress(
+        [SEEDS.CUSTOMER, bob.publicKey.toBuffer(), busy.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      const [bobLotsPda] = findProgramAddress([Buffer.from("point_lots"), bobPda.toBuffer()], program.programId);
+      const [busyMerchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, busy.merchant.publicKey.toBuffer(), busy.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+
+      // A second merchant with no lots yet
+      const newcomer = Keypair.generate();
+      await airdrop(connection, newcomer.publicKey);
+      const [newcomerPda] = findProgramAddress(
+        [SEEDS.MERCHANT, newcomer.publicKey.toBuffer(), busy.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .registerMerchant("Newcomer", "", "Retail", null, new anchor.BN(10))
+        .accounts({ merchantAuthority: newcomer.publicKey, loyaltyProgram: busy.loyaltyProgram })
+        .signers([newcomer])
+        .rpc();
+      await program.methods
+        .approveMerchant()
+        .accounts({ authority: busy.authority.publicKey, loyaltyProgram: busy.loyaltyProgram, merchant: newcomerPda })
+        .signers([busy.authority])
+        .rpc();
+
+      // Each issuance lands on its own expiry day
+      const issueExpiringIn = async (days: number, merchant: Keypair, merchantPda: PublicKey) => {
+        await program.methods
+          .updateProgramConfig(null, null, new anchor.BN(days * 86_400))
+          .accountsPartial({ authority: busy.authority.publicKey, loyaltyProgram: busy.loyaltyProgram })
+          .signers([busy.authority])
+          .rpc();
+        await program.methods
+          .issueRewards(new anchor.BN(1_000))
+          .accountsPartial({
+            merchantAuthority: merchant.publicKey,
+            merchant: merchantPda,
+            customer: bobPda,
+            loyaltyProgram: busy.loyaltyProgram,
+            mint: busy.mint,
+            customerTokenAccount: busy.tokenAccountOf(bob.publicKey),
+            rewardRule: SystemProgram.programId,
+          })
+          .signers([merchant])
+          .rpc();
+      };
+
+      const assertLotsMatchBalance = async () => {
+        const { lots } = await program.account.pointLots.fetch(bobLotsPda);
+        const balance = await getAccount(
+          connection,
+          busy.tokenAccountOf(bob.publicKey),
+          "confirmed",
+          TOKEN_2022_PROGRAM_ID
+        );
+        const total = lots.reduce((sum, lot) => sum.add(lot.amount), new anchor.BN(0));
+        assert.equal(total.toString(), balance.amount.toString());
+        for (let i = 1; i < lots.length; i++) {
+          assert.isTrue(lots[i - 1].expiresAt.lte(lots[i].expiresAt), "lots stay ordered by expiry");
+        }
+        return lots;
+      };
+
+      // The funded issuance holds one lot; 33 more days overflow the 32 lot slots
+      for (let days = 1; days <= 33; days++) {
+        await issueExpiringIn(days, busy.merchant, busyMerchantPda);
+      }
+      let lots = await assertLotsMatchBalance();
+      assert.equal(lots.length, 32);
+      assert.isTrue(lots.every((lot) => lot.merchant.equals(busyMerchantPda)));
+
+      // The newcomer has no lot to join, so the two oldest lots merge to make room
+      const oldest = lots[0];
+      await issueExpiringIn(34, newcomer, newcomerPda);
+      lots = await assertLotsMatchBalance();
+      assert.equal(lots.length, 32);
+      assert.isTrue(lots[0].expiresAt.gt(oldest.expiresAt), "merged points keep the later expiry");
+      assert.equal(lots.filter((lot) => lot.merchant.equals(newcomerPda)).length, 1);
+    });
+  });
+
+  describe("Freeze Customer", () => {
+    let funded: FundedProgram;
+    let alice: Keypair;
+    let alicePda: PublicKey;
+    let merchantPda: PublicKey;
+
+    const issueToAlice = async () => {
+      const { program } = await ctx;
+      return program.methods
+        .issueRewards(new anchor.BN(1_000))
+        .accountsPartial({
+          merchantAuthority: funded.merchant.publicKey,
+          merchant: merchantPda,
+          customer: alicePda,
+          loyaltyProgram: funded.loyaltyProgram,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+          rewardRule: SystemProgram.programId,
+        })
+        .signers([funded.merchant])
+        .rpc();
+    };
+
+    before(async () => {
+      const { program } = await ctx;
+      funded = await createFundedProgram(await ctx, { transferable: {} }, 1);
+      [alice] = funded.customers;
+      [alicePda] = findProgramAddress(
+        [SEEDS.CUSTOMER, alice.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      [merchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, funded.merchant.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("Fails when a non-authority tries to freeze a customer", async () => {
+      const { program } = await ctx;
+
+      await assertError(
+        () =>
+          program.methods
+            .freezeCustomer(1)
+            .accountsPartial({
+              authority: alice.publicKey,
+              loyaltyProgram: funded.loyaltyProgram,
+              customer: alicePda,
+              mint: funded.mint,
+              customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+            })
+            .signers([alice])
+            .rpc(),
+        "UnauthorizedAccess"
+      );
+    });
+
+    it("Freezes the customer's token account with a reason code", async () => {
+      const { program, connection } = await ctx;
+
+      await program.methods
+        .freezeCustomer(3)
+        .accountsPartial({
+          authority: funded.authority.publicKey,
+          loyaltyProgram: funded.loyaltyProgram,
+          customer: alicePda,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+        })
+        .signers([funded.authority])
+        .rpc();
+
+      const customer = await program.account.customer.fetch(alicePda);
+      assert.isTrue(customer.isFrozen);
+      assert.equal(customer.freezeReason, 3);
+
+      const tokenAccount = await getAccount(
+        connection,
+        funded.tokenAccountOf(alice.publicKey),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.isTrue(tokenAccount.isFrozen);
+    });
+
+    it("Blocks issuance while the customer is frozen", async () => {
+      await assertError(issueToAlice, "CustomerFrozen");
+    });
+
+    it("Thaws the customer and allows issuance again", async () => {
+      const { program, connection } = await ctx;
+
+      await program.methods
+        .thawCustomer()
+        .accountsPartial({
+          authority: funded.authority.publicKey,
+          loyaltyProgram: funded.loyaltyProgram,
+          customer: alicePda,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+        })
+        .signers([funded.authority])
+        .rpc();
+
+      const customer = await program.account.customer.fetch(alicePda);
+      assert.isFalse(customer.isFrozen);
+      assert.equal(customer.freezeReason, 0);
+
+      const tokenAccount = await getAccount(
+        connection,
+        funded.tokenAccountOf(alice.publicKey),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.isFalse(tokenAccount.isFrozen);
+
+      await issueToAlice();
+    });
+  });
+
+  describe("Gift Points", () => {
+    let funded: FundedProgram;
+    let alice: Keypair;
+    let bob: Keypair;
+    let alicePda: PublicKey;
+    let bobPda: PublicKey;
+
+    const transactionRecordOf = async (wallet: PublicKey, customerPda: PublicKey) => {
+      const { program } = await ctx;
+      const customer = await program.account.customer.fetch(customerPda);
+      return findProgramAddress(
+        [Buffer.from("transaction"), wallet.toBuffer(), customer.transactionCount.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    };
+
+    const gift = async (amount: number, recipientPda: PublicKey) => {
+      const { program } = await ctx;
+      const recipient = await program.account.customer.fetch(recipientPda);
+      return program.methods
+        .giftPoints(new anchor.BN(amount))
+        .accountsPartial({
+          sender: alice.publicKey,
+          loyaltyProgram: funded.loyaltyProgram,
+          senderCustomer: alicePda,
+          recipientCustomer: recipientPda,
+          mint: funded.mint,
+          senderTokenAccount: funded.tokenAccountOf(alice.publicKey),
+          recipientTokenAccount: funded.tokenAccountOf(recipient.wallet),
+          senderTransactionRecord: await transactionRecordOf(alice.publicKey, alicePda),
+          recipientTransactionRecord: await transactionRecordOf(recipient.wallet, recipientPda),
+        })
+        .signers([alice])
+        .rpc();
+    };
+
+    before(async () => {
+      const { program } = await ctx;
+      funded = await createFundedProgram(await ctx, { transferable: {} }, 2);
+      [alice, bob] = funded.customers;
+      [alicePda] = findProgramAddress(
+        [SEEDS.CUSTOMER, alice.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      [bobPda] = findProgramAddress(
+        [SEEDS.CUSTOMER, bob.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("Gifts points to another registered customer with a record on each side", async () => {
+      const { program, connection } = await ctx;
+      const aliceRecord = await transactionRecordOf(alice.publicKey, alicePda);
+      const bobRecord = await transactionRecordOf(bob.publicKey, bobPda);
+
+      await gift(5, bobPda);
+
+      const bobAccount = await getAccount(
+        connection,
+        funded.tokenAccountOf(bob.publicKey),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(bobAccount.amount, BigInt(5));
+
+      const sent = await program.account.transactionRecord.fetch(aliceRecord);
+      assert.equal(sent.transactionType, 2);
+      assert.isTrue(sent.outgoing);
+      assertPublicKeyEqual(sent.counterparty, bob.publicKey);
+      assertPublicKeyEqual(sent.merchant, PublicKey.default);
+      assertBNEqual(sent.amount, 5);
+
+      const received = await program.account.transactionRecord.fetch(bobRecord);
+      assert.equal(received.transactionType, 2);
+      assert.isFalse(received.outgoing);
+      assertPublicKeyEqual(received.counterparty, alice.publicKey);
+      assertPublicKeyEqual(received.merchant, PublicKey.default);
+
+      // Gifted points keep their original expiry
+      const bobCustomer = await program.account.customer.fetch(bobPda);
+      const aliceCustomer = await program.account.customer.fetch(alicePda);
+      assertBNEqual(bobCustomer.nextExpiryAmount, 5);
+      assertBNEqual(bobCustomer.nextExpiryAt, aliceCustomer.nextExpiryAt.toNumber());
+      assertBNEqual(aliceCustomer.giftedToday, 5);
+    });
+
+    it("Fails to gift points to yourself", async () => {
+      await assertError(() => gift(1, alicePda), "CannotGiftSelf");
+    });
+
+    it("Enforces the sender's per-tier daily limit", async () => {
+      const { program } = await ctx;
+
+      await program.methods
+        .setGiftLimits([new anchor.BN(6)]) // Bronze only
+        .accountsPartial({ authority: funded.authority.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.authority])
+        .rpc();
+
+      await assertError(() => gift(2, bobPda), "GiftLimitExceeded");
+      await gift(1, bobPda);
+    });
+  });
+
+  describe("Coalition Settlement", () => {
+    const pointValue = 1_000; // lamports per point
+    const offerCost = 4;
+    let funded: FundedProgram;
+    let alice: Keypair;
+    let alicePda: PublicKey;
+    let merchantB: Keypair;
+    let issuerPda: PublicKey;
+    let redeemerPda: PublicKey;
+    let ledgerPda: PublicKey;
+    let merchantAKey: PublicKey;
+    let merchantBKey: PublicKey;
+
+    const escrowOf = (merchantPda: PublicKey, programId: PublicKey) =>
+      findProgramAddress([Buffer.from("merchant_escrow"), merchantPda.toBuffer()], programId)[0];
+
+    before(async () => {
+      const { program, connection } = await ctx;
+      funded = await createFundedProgram(await ctx, { transferable: {} }, 1);
+      [alice] = funded.customers;
+      [alicePda] = findProgramAddress(
+        [SEEDS.CUSTOMER, alice.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      [issuerPda] = findProgramAddress(
+        [SEEDS.MERCHANT, funded.merchant.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+
+      // Second coalition brand where alice redeems
+      merchantB = Keypair.generate();
+      await airdrop(connection, merchantB.publicKey);
+      [redeemerPda] = findProgramAddress(
+        [SEEDS.MERCHANT, merchantB.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .registerMerchant("Coalition Partner", "", "Retail", null, new anchor.BN(10))
+        .accounts({ merchantAuthority: merchantB.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([merchantB])
+        .rpc();
+      await program.methods
+        .approveMerchant()
+        .accounts({ authority: funded.authority.publicKey, loyaltyProgram: funded.loyaltyProgram, merchant: redeemerPda })
+        .signers([funded.authority])
+        .rpc();
+
+      await program.methods
+        .setCollateralConfig(new anchor.BN(pointValue), null)
+        .accountsPartial({ authority: funded.authority.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.authority])
+        .rpc();
+
+      for (const merchant of [funded.merchant, merchantB]) {
+        await program.methods
+          .depositCollateral(new anchor.BN(100_000))
+          .accountsPartial({ merchantAuthority: merchant.publicKey, loyaltyProgram: funded.loyaltyProgram })
+          .signers([merchant])
+          .rpc();
+      }
+
+      await program.methods
+        .createRedemptionOffer("Partner Coffee", "Free coffee", "coffee", new anchor.BN(offerCost), { discount: { percentage: 100 } }, null, null)
+        .accountsPartial({ merchantAuthority: merchantB.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([merchantB])
+        .rpc();
+
+      [merchantAKey, merchantBKey] =
+        Buffer.compare(issuerPda.toBuffer(), redeemerPda.toBuffer()) < 0 ? [issuerPda, redeemerPda] : [redeemerPda, issuerPda];
+      [ledgerPda] = findProgramAddress(
+        [Buffer.from("settlement"), merchantAKey.toBuffer(), merchantBKey.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("Pre-funds the ledger address before the ledger exists", async () => {
+      const { program, provider, connection } = await ctx;
+      // Anyone can send lamports to the PDA; ledger creation below must still succeed
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          SystemProgram.transfer({ fromPubkey: funded.authority.publicKey, toPubkey: ledgerPda, lamports: 1_000_000 })
+        ),
+        [funded.authority]
+      );
+
+      const info = await connection.getAccountInfo(ledgerPda);
+      assertPublicKeyEqual(info!.owner, SystemProgram.programId);
+      assert.isNull(await program.account.settlementLedger.fetchNullable(ledgerPda));
+    });
+
+    it("Records points issued at one merchant and redeemed at another", async () => {
+      const { program } = await ctx;
+      const voucherSeed = new anchor.BN(42);
+      const customer = await program.account.customer.fetch(alicePda);
+      const [offerPda] = findProgramAddress(
+        [Buffer.from("redemption_offer"), redeemerPda.toBuffer(), Buffer.from("Partner Coffee")],
+        program.programId
+      );
+      const [transactionRecord] = findProgramAddress(
+        [Buffer.from("transaction"), alice.publicKey.toBuffer(), customer.transactionCount.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+
+      await program.methods
+        .redeemRewards(voucherSeed)
+        .accountsPartial({
+          customerAuthority: alice.publicKey,
+          customer: alicePda,
+          merchant: redeemerPda,
+          loyaltyProgram: funded.loyaltyProgram,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+          redemptionOffer: offerPda,
+          transactionRecord,
+        })
+        .remainingAccounts([{ pubkey: ledgerPda, isSigner: false, isWritable: true }])
+        .signers([alice])
+        .rpc();
+
+      const ledger = await program.account.settlementLedger.fetch(ledgerPda);
+      assertPublicKeyEqual(ledger.merchantA, merchantAKey);
+      const [issuerOwes, redeemerOwes] = merchantAKey.equals(issuerPda)
+        ? [ledger.aOwesB, ledger.bOwesA]
+        : [ledger.bOwesA, ledger.aOwesB];
+      assertBNEqual(issuerOwes, offerCost);
+      assertBNEqual(redeemerOwes, 0);
+    });
+
+    it("Settles the net amount from the issuer's escrow to the redeemer's", async () => {
+      const { program, connection } = await ctx;
+      const issuerEscrow = escrowOf(issuerPda, program.programId);
+      const redeemerEscrow = escrowOf(redeemerPda, program.programId);
+      const issuerBefore = await connection.getBalance(issuerEscrow);
+      const redeemerBefore = await connection.getBalance(redeemerEscrow);
+
+      await program.methods
+        .settle()
+        .accountsPartial({
+          payer: funded.authority.publicKey,
+          loyaltyProgram: funded.loyaltyProgram,
+          settlementLedger: ledgerPda,
+          merchantA: merchantAKey,
+          merchantB: merchantBKey,
+        })
+        .signers([funded.authority])
+        .rpc();
+
+      const value = offerCost * pointValue;
+      assert.equal(await connection.getBalance(issuerEscrow), issuerBefore - value);
+      assert.equal(await connection.getBalance(redeemerEscrow), redeemerBefore + value);
+
+      const issuer = await program.account.merchant.fetch(issuerPda);
+      const redeemer = await program.account.merchant.fetch(redeemerPda);
+      assertBNEqual(issuer.collateralBalance, 100_000 - value);
+      assertBNEqual(redeemer.collateralBalance, 100_000 + value);
+
+      const ledger = await program.account.settlementLedger.fetch(ledgerPda);
+      assertBNEqual(ledger.aOwesB, 0);
+      assertBNEqual(ledger.bOwesA, 0);
+      assertBNEqual(ledger.totalSettledPoints, offerCost);
+      assertBNEqual(ledger.totalSettledValue, value);
+    });
+
+    it("Opens an empty creditor escrow for a settlement below its rent-exempt minimum", async () => {
+      const { program, connection } = await ctx;
+
+      // A partner that never deposited collateral, redeeming a single point (1,000 lamports)
+      const partner = Keypair.generate();
+      await airdrop(connection, partner.publicKey);
+      const [partnerPda] = findProgramAddress(
+        [SEEDS.MERCHANT, partner.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .registerMerchant("Empty Escrow Partner", "", "Retail", null, new anchor.BN(10))
+        .accounts({ merchantAuthority: partner.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([partner])
+        .rpc();
+      await program.methods
+        .approveMerchant()
+        .accounts({ authority: funded.authority.publicKey, loyaltyProgram: funded.loyaltyProgram, merchant: partnerPda })
+        .signers([funded.authority])
+        .rpc();
+      await program.methods
+        .createRedemptionOffer("Sticker", "One sticker", "sticker", new anchor.BN(1), { discount: { percentage: 100 } }, null, null)
+        .accountsPartial({ merchantAuthority: partner.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([partner])
+        .rpc();
+
+      const [firstKey, secondKey] =
+        Buffer.compare(issuerPda.toBuffer(), partnerPda.toBuffer()) < 0 ? [issuerPda, partnerPda] : [partnerPda, issuerPda];
+      const [partnerLedger] = findProgramAddress(
+        [Buffer.from("settlement"), firstKey.toBuffer(), secondKey.toBuffer()],
+        program.programId
+      );
+      const customer = await program.account.customer.fetch(alicePda);
+      await program.methods
+        .redeemRewards(new anchor.BN(43))
+        .accountsPartial({
+          customerAuthority: alice.publicKey,
+          customer: alicePda,
+          merchant: partnerPda,
+          loyaltyProgram: funded.loyaltyProgram,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+          redemptionOffer: findProgramAddress(
+            [Buffer.from("redemption_offer"), partnerPda.toBuffer(), Buffer.from("Sticker")],
+            program.programId
+          )[0],
+          transactionRecord: findProgramAddress(
+            [Buffer.from("transaction"), alice.publicKey.toBuffer(), customer.transactionCount.toArrayLike(Buffer, "le", 8)],
+            program.programId
+          )[0],
+        })
+        .remainingAccounts([{ pubkey: partnerLedger, isSigner: false, isWritable: true }])
+        .signers([alice])
+        .rpc();
+
+      const partnerEscrow = escrowOf(partnerPda, program.programId);
+      assert.equal(await connection.getBalance(partnerEscrow), 0);
+
+      await program.methods
+        .settle()
+        .accountsPartial({
+          payer: funded.authority.publicKey,
+          loyaltyProgram: funded.loyaltyProgram,
+          settlementLedger: partnerLedger,
+          merchantA: firstKey,
+          merchantB: secondKey,
+        })
+        .signers([funded.authority])
+        .rpc();
+
+      // The settler pays the rent; only the settled value counts as the partner's collateral
+      const rent = await connection.getMinimumBalanceForRentExemption(0);
+      assert.equal(await connection.getBalance(partnerEscrow), rent + pointValue);
+      assertBNEqual((await program.account.merchant.fetch(partnerPda)).collateralBalance, pointValue);
+    });
+  });
+
+  describe("Merchant Collateral", () => {
+    const pointValue = 1_000; // lamports per point
+    let funded: FundedProgram;
+    let alice: Keypair;
+    let alicePda: PublicKey;
+    let merchantPda: PublicKey;
+
+    const issueToAlice = async () => {
+      const { program } = await ctx;
+      return program.methods
+        .issueRewards(new anchor.BN(10_000))
+        .accountsPartial({
+          merchantAuthority: funded.merchant.publicKey,
+          merchant: merchantPda,
+          customer: alicePda,
+          loyaltyProgram: funded.loyaltyProgram,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+          rewardRule: SystemProgram.programId,
+        })
+        .signers([funded.merchant])
+        .rpc();
+    };
+
+    const withdraw = async (amount: number) => {
+      const { program } = await ctx;
+      return program.methods
+        .withdrawCollateral(new anchor.BN(amount))
+        .accountsPartial({ merchantAuthority: funded.merchant.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.merchant])
+        .rpc();
+    };
+
+    before(async () => {
+      const { program } = await ctx;
+      funded = await createFundedProgram(await ctx, { transferable: {} }, 1);
+      [alice] = funded.customers;
+      [alicePda] = findProgramAddress(
+        [SEEDS.CUSTOMER, alice.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      [merchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, funded.merchant.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .setCollateralConfig(new anchor.BN(pointValue), null)
+        .accountsPartial({ authority: funded.authority.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.authority])
+        .rpc();
+    });
+
+    it("Rejects issuance that is not backed by escrowed collateral", async () => {
+      await assertError(() => issueToAlice(), "InsufficientCollateral");
+    });
+
+    it("Issues once the merchant has deposited enough collateral", async () => {
+      const { program } = await ctx;
+      const before = await program.account.merchant.fetch(merchantPda);
+
+      // Points issued before the point value was set are also backed by the deposit
+      const required = (before.outstandingPoints.toNumber() + 10) * pointValue;
+      await program.methods
+        .depositCollateral(new anchor.BN(required))
+        .accountsPartial({ merchantAuthority: funded.merchant.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.merchant])
+        .rpc();
+
+      await issueToAlice();
+
+      const after = await program.account.merchant.fetch(merchantPda);
+      assertBNEqual(after.outstandingPoints, before.outstandingPoints.toNumber() + 10);
+      assertBNEqual(after.collateralBalance, required);
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(funded.loyaltyProgram);
+      assertBNEqual(loyaltyProgram.totalCollateral, required);
+    });
+
+    it("Blocks withdrawals that would leave outstanding points unbacked", async () => {
+      await assertError(() => withdraw(1), "InsufficientCollateral");
+    });
+
+    it("Allows withdrawing collateral above the outstanding liability", async () => {
+      const { program, connection } = await ctx;
+      const excess = 5_000;
+
+      await program.methods
+        .depositCollateral(new anchor.BN(excess))
+        .accountsPartial({ merchantAuthority: funded.merchant.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.merchant])
+        .rpc();
+
+      const escrow = findProgramAddress([Buffer.from("merchant_escrow"), merchantPda.toBuffer()], program.programId)[0];
+      const escrowBefore = await connection.getBalance(escrow);
+      const merchantBefore = await program.account.merchant.fetch(merchantPda);
+
+      await withdraw(excess);
+
+      assert.equal(await connection.getBalance(escrow), escrowBefore - excess);
+      const merchant = await program.account.merchant.fetch(merchantPda);
+      assertBNEqual(merchant.collateralBalance, merchantBefore.collateralBalance.toNumber() - excess);
+    });
+
+    it("Refuses to close a merchant with collateral in escrow", async () => {
+      const { program } = await ctx;
+
+      await assertError(
+        () =>
+          program.methods
+            .closeMerchant()
+            .accountsPartial({ merchantAuthority: funded.merchant.publicKey, merchant: merchantPda, loyaltyProgram: funded.loyaltyProgram })
+            .signers([funded.merchant])
+            .rpc(),
+        "CollateralOutstanding"
+      );
+    });
+  });
+
+  describe("Issuance Caps", () => {
+    let funded: FundedProgram;
+    let alice: Keypair;
+    let alicePda: PublicKey;
+    let merchantPda: PublicKey;
+
+    // 10_000 cents at the default reward rate issues 10 points
+    const issueToAlice = async (purchaseAmount: number) => {
+      const { program } = await ctx;
+      return program.methods
+        .issueRewards(new anchor.BN(purchaseAmount))
+        .accountsPartial({
+          merchantAuthority: funded.merchant.publicKey,
+          merchant: merchantPda,
+          customer: alicePda,
+          loyaltyProgram: funded.loyaltyProgram,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+          rewardRule: SystemProgram.programId,
+        })
+        .signers([funded.merchant])
+        .rpc();
+    };
+
+    const caps = (perTransaction: number, perDay: number, lifetime: number) => ({
+      perTransaction: new anchor.BN(perTransaction),
+      perDay: new anchor.BN(perDay),
+      lifetime: new anchor.BN(lifetime),
+    });
+
+    before(async () => {
+      const { program } = await ctx;
+      funded = await createFundedProgram(await ctx, { transferable: {} }, 1);
+      [alice] = funded.customers;
+      [alicePda] = findProgramAddress(
+        [SEEDS.CUSTOMER, alice.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      [merchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, funded.merchant.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("Enforces the merchant's per-transaction cap", async () => {
+      const { program } = await ctx;
+
+      await program.methods
+        .setIssuanceCaps(caps(20, 0, 0))
+        .accountsPartial({ merchantAuthority: funded.merchant.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.merchant])
+        .rpc();
+
+      await assertError(() => issueToAlice(30_000), "TransactionIssuanceCapExceeded");
+      await issueToAlice(10_000);
+
+      const merchant = await program.account.merchant.fetch(merchantPda);
+      assertBNEqual(merchant.issuanceCaps.perTransaction, 20);
+    });
+
+    it("Fails when a non-authority sets an issuance ceiling", async () => {
+      const { program } = await ctx;
+
+      await assertError(
+        () =>
+          program.methods
+            .setIssuanceCeiling(caps(0, 1, 0))
+            .accountsPartial({ authority: funded.merchant.publicKey, loyaltyProgram: funded.loyaltyProgram, merchant: merchantPda })
+            .signers([funded.merchant])
+            .rpc(),
+        "UnauthorizedAccess"
+      );
+    });
+
+    it("Applies the authority's lower daily ceiling over the merchant's caps", async () => {
+      const { program } = await ctx;
+
+      // The merchant allows 1000/day, the authority only 25/day
+      await program.methods
+        .setIssuanceCaps(caps(20, 1_000, 0))
+        .accountsPartial({ merchantAuthority: funded.merchant.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.merchant])
+        .rpc();
+      await program.methods
+        .setIssuanceCeiling(caps(0, 25, 0))
+        .accountsPartial({ authority: funded.authority.publicKey, loyaltyProgram: funded.loyaltyProgram, merchant: merchantPda })
+        .signers([funded.authority])
+        .rpc();
+
+      // 10 points from setup + 10 above are already in today's window
+      const before = await program.account.merchant.fetch(merchantPda);
+      assertBNEqual(before.issuedInWindow, 20);
+
+      await assertError(() => issueToAlice(10_000), "DailyIssuanceCapExceeded");
+      await issueToAlice(5_000);
+
+      const after = await program.account.merchant.fetch(merchantPda);
+      assertBNEqual(after.issuedInWindow, 25);
+    });
+
+    it("Stops issuance once the lifetime budget is spent", async () => {
+      const { program } = await ctx;
+      const merchant = await program.account.merchant.fetch(merchantPda);
+
+      await program.methods
+        .setIssuanceCeiling(caps(0, 0, merchant.totalIssued.toNumber()))
+        .accountsPartial({ authority: funded.authority.publicKey, loyaltyProgram: funded.loyaltyProgram, merchant: merchantPda })
+        .signers([funded.authority])
+        .rpc();
+
+      await assertError(() => issueToAlice(1_000), "IssuanceBudgetExhausted");
+    });
+  });
+
+  describe("Stacked Reward Rules", () => {
+    let funded: FundedProgram;
+    let alice: Keypair;
+    let alicePda: PublicKey;
+    let merchantPda: PublicKey;
+    let weekendRule: PublicKey;
+    let loyalRule: PublicKey;
+    let bigBasketRule: PublicKey;
+
+    const ruleAddress = (ruleId: number, programId: PublicKey) =>
+      findProgramAddress(
+        [SEEDS.REWARD_RULE, merchantPda.toBuffer(), new anchor.BN(ruleId).toArrayLike(Buffer, "le", 8)],
+        programId
+      )[0];
+
+    // 10_000 cents issues 10 points before rules; returns the points credited
+    const issueWithRules = async (rules: PublicKey[]) => {
+      const { program } = await ctx;
+      const before = await program.account.customer.fetch(alicePda);
+      await program.methods
+        .issueRewards(new anchor.BN(10_000))
+        .accountsPartial({
+          merchantAuthority: funded.merchant.publicKey,
+          merchant: merchantPda,
+          customer: alicePda,
+          loyaltyProgram: funded.loyaltyProgram,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+          rewardRule: SystemProgram.programId,
+        })
+        .remainingAccounts(rules.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+        .signers([funded.merchant])
+        .rpc();
+      const after = await program.account.customer.fetch(alicePda);
+      return after.totalEarned.sub(before.totalEarned).toNumber();
+    };
+
+    const setCombination = async (ruleCombination: object) => {
+      const { program } = await ctx;
+      await program.methods
+        .setRuleCombination(ruleCombination as any)
+        .accountsPartial({ merchantAuthority: funded.merchant.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.merchant])
+        .rpc();
+    };
+
+    before(async () => {
+      const { program } = await ctx;
+      funded = await createFundedProgram(await ctx, { transferable: {} }, 1);
+      [alice] = funded.customers;
+      [alicePda] = findProgramAddress(
+        [SEEDS.CUSTOMER, alice.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      [merchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, funded.merchant.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+
+      const rules: [number, string, number, number][] = [
+        [1, "Weekend 2x", 200, 0],
+        [2, "Loyal Bonus", 150, 0],
+        [3, "Big Basket", 300, 1_000_000], // never met by these purchases
+      ];
+      for (const [ruleId, name, multiplier, minPurchase] of rules) {
+        await program.methods
+          .setRewardRule(
+            new anchor.BN(ruleId),
+            name,
+            { bonusMultiplier: {} },
+            new anchor.BN(multiplier),
+            new anchor.BN(minPurchase),
+            new anchor.BN(0),
+            new anchor.BN(0),
+            0,
+            0,
+            { multiplier: {} },
+            new anchor.BN(0),
+            new anchor.BN(0)
+          )
+          .accountsPartial({
+            merchantAuthority: funded.merchant.publicKey,
+            loyaltyProgram: funded.loyaltyProgram,
+            merchant: merchantPda,
+            rewardRule: ruleAddress(ruleId, program.programId),
+          })
+          .signers([funded.merchant])
+          .rpc();
+      }
+      [weekendRule, loyalRule, bigBasketRule] = [1, 2, 3].map((id) => ruleAddress(id, program.programId));
+    });
+
+    it("Applies only the highest multiplier by default", async () => {
+      const { program } = await ctx;
+      const merchant = await program.account.merchant.fetch(merchantPda);
+      assert.deepEqual(merchant.ruleCombination, { highestOnly: {} });
+
+      assert.equal(await issueWithRules([loyalRule, weekendRule, bigBasketRule]), 20);
+    });
+
+    it("Compounds multipliers under the multiplicative policy", async () => {
+      await setCombination({ multiplicative: {} });
+      assert.equal(await issueWithRules([weekendRule, loyalRule, bigBasketRule]), 30);
+    });
+
+    it("Adds bonuses under the additive policy", async () => {
+      await setCombination({ additive: {} });
+      assert.equal(await issueWithRules([weekendRule, loyalRule, bigBasketRule]), 25);
+    });
+
+    it("Stops at an exclusive rule under the priority policy", async () => {
+      const { program } = await ctx;
+      await setCombination({ priority: {} });
+      await program.methods
+        .setRewardRulePriority(new anchor.BN(2), 10, true)
+        .accountsPartial({
+          merchantAuthority: funded.merchant.publicKey,
+          loyaltyProgram: funded.loyaltyProgram,
+          merchant: merchantPda,
+          rewardRule: loyalRule,
+        })
+        .signers([funded.merchant])
+        .rpc();
+
+      assert.equal(await issueWithRules([weekendRule, loyalRule]), 15);
+    });
+
+    it("Rejects a rule passed twice", async () => {
+      await assertError(() => issueWithRules([weekendRule, weekendRule]), "DuplicateRewardRule");
+    });
+
+    it("Rejects accounts that are not this merchant's reward rules", async () => {
+      await assertError(() => issueWithRules([funded.loyaltyProgram]), "InvalidRewardRule");
+    });
+  });
+
+  describe("Rule Types", () => {
+    let funded: FundedProgram;
+    let alice: Keypair; // already earned at this merchant during setup
+    let bob: Keypair;
+    let merchantPda: PublicKey;
+    let rules: PublicKey[];
+
+    const customerPda = (customer: Keypair, programId: PublicKey) =>
+      findProgramAddress(
+        [SEEDS.CUSTOMER, customer.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        programId
+      )[0];
+
+    const ruleAddress = (ruleId: number, programId: PublicKey) =>
+      findProgramAddress(
+        [SEEDS.REWARD_RULE, merchantPda.toBuffer(), new anchor.BN(ruleId).toArrayLike(Buffer, "le", 8)],
+        programId
+      )[0];
+
+    const createRule = async (ruleId: number, ruleType: object, minStreakDays: number, eligibleTiers: number) => {
+      const { program } = await ctx;
+      return program.methods
+        .setRewardRule(
+          new anchor.BN(ruleId),
+          `Rule ${ruleId}`,
+          ruleType as any,
+          new anchor.BN(200),
+          new anchor.BN(0),
+          new anchor.BN(0),
+          new anchor.BN(0),
+          minStreakDays,
+          eligibleTiers,
+          { multiplier: {} },
+          new anchor.BN(0),
+          new anchor.BN(0)
+        )
+        .accountsPartial({
+          merchantAuthority: funded.merchant.publicKey,
+          loyaltyProgram: funded.loyaltyProgram,
+          merchant: merchantPda,
+          rewardRule: ruleAddress(ruleId, program.programId),
+        })
+        .signers([funded.merchant])
+        .rpc();
+    };
+
+    // 10_000 cents issues 10 points before rules; returns the points credited
+    const issueTo = async (customer: Keypair) => {
+      const { program } = await ctx;
+      const pda = customerPda(customer, program.programId);
+      const before = await program.account.customer.fetch(pda);
+      await program.methods
+        .issueRewards(new anchor.BN(10_000))
+        .accountsPartial({
+          merchantAuthority: funded.merchant.publicKey,
+          merchant: merchantPda,
+          customer: pda,
+          loyaltyProgram: funded.loyaltyProgram,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(customer.publicKey),
+          rewardRule: SystemProgram.programId,
+        })
+        .remainingAccounts(rules.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+        .signers([funded.merchant])
+        .rpc();
+      const after = await program.account.customer.fetch(pda);
+      return after.totalEarned.sub(before.totalEarned).toNumber();
+    };
+
+    before(async () => {
+      const { program } = await ctx;
+      funded = await createFundedProgram(await ctx, { transferable: {} }, 2);
+      [alice, bob] = funded.customers;
+      [merchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, funded.merchant.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+
+      await createRule(1, { firstPurchaseBonus: {} }, 0, 0);
+      await createRule(2, { tierBonus: {} }, 0, 0b0010); // Silver only
+      await createRule(3, { streakBonus: {} }, 30, 0);
+      await createRule(4, { referralBonus: {} }, 0, 0);
+      rules = [1, 2, 3, 4].map((id) => ruleAddress(id, program.programId));
+
+      await program.methods
+        .setRuleCombination({ multiplicative: {} })
+        .accountsPartial({ merchantAuthority: funded.merchant.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.merchant])
+        .rpc();
+    });
+
+    it("Validates the parameters each rule type needs", async () => {
+      await assertError(() => createRule(10, { streakBonus: {} }, 0, 0), "InvalidRuleParameters");
+      await assertError(() => createRule(11, { tierBonus: {} }, 0, 0), "InvalidRuleParameters");
+      await assertError(() => createRule(12, { tierBonus: {} }, 0, 0b10000), "InvalidRuleParameters");
+      await assertError(() => createRule(13, { bonusMultiplier: {} }, 7, 0), "InvalidRuleParameters");
+    });
+
+    it("Applies a first-purchase bonus only on the customer's first purchase at the merchant", async () => {
+      assert.equal(await issueTo(bob), 20);
+      assert.equal(await issueTo(bob), 10);
+    });
+
+    it("Skips tier, streak and referral rules the customer does not qualify for", async () => {
+      // Bronze, short streak, not referred, and already known to the merchant
+      assert.equal(await issueTo(alice), 10);
+    });
+  });
+
+  describe("Daily Streaks", () => {
+    let funded: FundedProgram;
+    let alice: Keypair;
+    let alicePda: PublicKey;
+    let merchantPda: PublicKey;
+
+    const setMilestones = async (milestones: number[]) => {
+      const { program } = await ctx;
+      return program.methods
+        .setStreakMilestones(milestones)
+        .accountsPartial({ authority: funded.authority.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.authority])
+        .rpc();
+    };
+
+    before(async () => {
+      const { program } = await ctx;
+      funded = await createFundedProgram(await ctx, { transferable: {} }, 1);
+      [alice] = funded.customers;
+      [alicePda] = findProgramAddress(
+        [SEEDS.CUSTOMER, alice.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      [merchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, funded.merchant.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("Starts a streak on the customer's first activity", async () => {
+      const { program } = await ctx;
+      const customer = await program.account.customer.fetch(alicePda);
+      assert.equal(customer.streakDays, 1);
+      assert.equal(customer.longestStreak, 1);
+      assertBNEqual(customer.streakDay, Math.floor(customer.lastActivity.toNumber() / 86_400));
+    });
+
+    it("Leaves the streak unchanged for more activity on the same day", async () => {
+      const { program } = await ctx;
+
+      await program.methods
+        .issueRewards(new anchor.BN(1_000))
+        .accountsPartial({
+          merchantAuthority: funded.merchant.publicKey,
+          merchant: merchantPda,
+          customer: alicePda,
+          loyaltyProgram: funded.loyaltyProgram,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+          rewardRule: SystemProgram.programId,
+        })
+        .signers([funded.merchant])
+        .rpc();
+
+      const customer = await program.account.customer.fetch(alicePda);
+      assert.equal(customer.streakDays, 1);
+      assert.equal(customer.longestStreak, 1);
+    });
+
+    it("Celebrates 7- and 30-day streaks by default", async () => {
+      const { program } = await ctx;
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(funded.loyaltyProgram);
+      assert.deepEqual(loyaltyProgram.streakMilestones, [7, 30, 0, 0]);
+    });
+
+    it("Rejects milestones that are zero or out of order", async () => {
+      await assertError(() => setMilestones([30, 7]), "InvalidStreakMilestones");
+      await assertError(() => setMilestones([0, 7]), "InvalidStreakMilestones");
+      await assertError(() => setMilestones([3, 7, 14, 30, 60]), "InvalidStreakMilestones");
+    });
+
+    it("Updates the milestone lengths", async () => {
+      const { program } = await ctx;
+      await setMilestones([3, 7, 14]);
+
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(funded.loyaltyProgram);
+      assert.deepEqual(loyaltyProgram.streakMilestones, [3, 7, 14, 0]);
+    });
+  });
+
+  describe("Referrals", () => {
+    let funded: FundedProgram;
+    let alice: Keypair;
+    let alicePda: PublicKey;
+    let merchantPda: PublicKey;
+    const carol = Keypair.generate();
+    const dave = Keypair.generate();
+
+    const customerPda = (wallet: PublicKey) =>
+      findProgramAddress(
+        [SEEDS.CUSTOMER, wallet.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        anchor.workspace.SolcityProtocol.programId
+      )[0];
+    const referralPda = (customer: PublicKey) =>
+      findProgramAddress([Buffer.from("referral"), customer.toBuffer()], anchor.workspace.SolcityProtocol.programId)[0];
+    const pointLotsPda = (customer: PublicKey) =>
+      findProgramAddress([Buffer.from("point_lots"), customer.toBuffer()], anchor.workspace.SolcityProtocol.programId)[0];
+
+    const registerReferred = async (wallet: Keypair, referrer: PublicKey) => {
+      const { program } = await ctx;
+      return program.methods
+        .registerCustomer()
+        .accountsPartial({
+          customerAuthority: wallet.publicKey,
+          loyaltyProgram: funded.loyaltyProgram,
+          referrer,
+          referral: referralPda(customerPda(wallet.publicKey)),
+        })
+        .signers([wallet])
+        .rpc();
+    };
+
+    const issueTo = async (wallet: PublicKey, purchaseAmount: number, withReferral: boolean) => {
+      const { program } = await ctx;
+      const referee = customerPda(wallet);
+      return program.methods
+        .issueRewards(new anchor.BN(purchaseAmount))
+        .accountsPartial({
+          merchantAuthority: funded.merchant.publicKey,
+          merchant: merchantPda,
+          customer: referee,
+          loyaltyProgram: funded.loyaltyProgram,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(wallet),
+          rewardRule: SystemProgram.programId,
+          referral: withReferral ? referralPda(referee) : null,
+          referrerCustomer: withReferral ? alicePda : null,
+          referrerTokenAccount: withReferral ? funded.tokenAccountOf(alice.publicKey) : null,
+          referrerPointLots: withReferral ? pointLotsPda(alicePda) : null,
+        })
+        .signers([funded.merchant])
+        .rpc();
+    };
+
+    before(async () => {
+      const { program, connection } = await ctx;
+      funded = await createFundedProgram(await ctx, { transferable: {} }, 1);
+      [alice] = funded.customers;
+      alicePda = customerPda(alice.publicKey);
+      [merchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, funded.merchant.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      await airdrop(connection, carol.publicKey);
+      await airdrop(connection, dave.publicKey);
+    });
+
+    it("Uses the default referral settings", async () => {
+      const { program } = await ctx;
+      const loyaltyProgram = await program.account.loyaltyProgram.fetch(funded.loyaltyProgram);
+      assertBNEqual(loyaltyProgram.referrerBonus, new anchor.BN(100));
+      assertBNEqual(loyaltyProgram.refereeBonus, new anchor.BN(50));
+      assert.equal(loyaltyProgram.maxReferralsPerReferrer, 50);
+    });
+
+    it("Records a referral when a customer registers with a referrer", async () => {
+      const { program } = await ctx;
+      await registerReferred(carol, alicePda);
+
+      const carolPda = customerPda(carol.publicKey);
+      const referee = await program.account.customer.fetch(carolPda);
+      assertPublicKeyEqual(referee.referrer, alicePda);
+      assert.isTrue(referee.referralPending);
+
+      const referrer = await program.account.customer.fetch(alicePda);
+      assert.equal(referrer.referralCount, 1);
+
+      const referral = await program.account.referral.fetch(referralPda(carolPda));
+      assertPublicKeyEqual(referral.referrer, alicePda);
+      assertPublicKeyEqual(referral.referee, carolPda);
+      assertBNEqual(referral.rewardedAt, new anchor.BN(0));
+    });
+
+    it("Enforces the per-referrer limit", async () => {
+      const { program } = await ctx;
+      await program.methods
+        .setReferralConfig(new anchor.BN(100), new anchor.BN(50), new anchor.BN(5_000), 1)
+        .accountsPartial({ authority: funded.authority.publicKey, loyaltyProgram: funded.loyaltyProgram })
+        .signers([funded.authority])
+        .rpc();
+
+      await assertError(() => registerReferred(dave, alicePda), "ReferralLimitReached");
+    });
+
+    it("Rejects a customer registering as their own referrer", async () => {
+      const eve = Keypair.generate();
+      const { connection } = await ctx;
+      await airdrop(connection, eve.publicKey);
+
+      // The referrer must already be a registered customer, which the registering wallet is not
+      await assertError(() => registerReferred(eve, customerPda(eve.publicKey)), "AccountNotInitialized");
+    });
+
+    it("Lets a referred customer refer others without forming a loop", async () => {
+      const { program } = await ctx;
+      const carolPda = customerPda(carol.publicKey);
+      await registerReferred(dave, carolPda);
+
+      const davePda = customerPda(dave.publicKey);
+      const referee = await program.account.customer.fetch(davePda);
+      assertPublicKeyEqual(referee.referrer, carolPda);
+
+      // carol's referrer is alice, so the chain alice -> carol -> dave never returns to dave
+      const referrer = await program.account.customer.fetch(carolPda);
+      assertPublicKeyEqual(referrer.referrer, alicePda);
+      assert.equal(referrer.referralCount, 1);
+    });
+
+    it("Requires the referral accounts on the first qualifying purchase", async () => {
+      await assertError(() => issueTo(carol.publicKey, 10_000, false), "MissingReferralAccounts");
+    });
+
+    it("Keeps the referral pending below the minimum purchase", async () => {
+      const { program } = await ctx;
+      await issueTo(carol.publicKey, 1_000, false);
+
+      const referee = await program.account.customer.fetch(customerPda(carol.publicKey));
+      assert.isTrue(referee.referralPending);
+    });
+
+    it("Pays both bonuses on the first qualifying purchase", async () => {
+      const { program } = await ctx;
+      const carolPda = customerPda(carol.publicKey);
+      const refereeBefore = await program.account.customer.fetch(carolPda);
+      const referrerBefore = await program.account.customer.fetch(alicePda);
+
+      await issueTo(carol.publicKey, 10_000, true);
+
+      const referee = await program.account.customer.fetch(carolPda);
+      assert.isFalse(referee.referralPending);
+      // 10 points for the purchase plus the 50 point referee bonus
+      assertBNEqual(referee.totalEarned, refereeBefore.totalEarned.add(new anchor.BN(60)));
+
+      const referrer = await program.account.customer.fetch(alicePda);
+      assertBNEqual(referrer.totalEarned, referrerBefore.totalEarned.add(new anchor.BN(100)));
+      assertBNEqual(referrer.referralEarnings, new anchor.BN(100));
+
+      const referral = await program.account.referral.fetch(referralPda(carolPda));
+      assertBNEqual(referral.referrerBonus, new anchor.BN(100));
+      assertBNEqual(referral.refereeBonus, new anchor.BN(50));
+      assertBNGreaterThan(referral.rewardedAt, new anchor.BN(0));
+    });
+
+    it("Pays the bonuses only once", async () => {
+      const { program } = await ctx;
+      await issueTo(carol.publicKey, 10_000, false);
+
+      const referrer = await program.account.customer.fetch(alicePda);
+      assertBNEqual(referrer.referralEarnings, new anchor.BN(100));
+    });
+  });
+
+  describe("Flat Bonus Rules", () => {
+    let funded: FundedProgram;
+    let alice: Keypair;
+    let alicePda: PublicKey;
+    let merchantPda: PublicKey;
+
+    const ruleAddress = (ruleId: number, programId: PublicKey) =>
+      findProgramAddress(
+        [SEEDS.REWARD_RULE, merchantPda.toBuffer(), new anchor.BN(ruleId).toArrayLike(Buffer, "le", 8)],
+        programId
+      )[0];
+
+    const createRule = async (
+      ruleId: number,
+      rewardKind: object,
+      multiplier: number,
+      flatBonus: number,
+      maxBonus: number,
+      minPurchase = 0
+    ) => {
+      const { program } = await ctx;
+      return program.methods
+        .setRewardRule(
+          new anchor.BN(ruleId),
+          `Rule ${ruleId}`,
+          { bonusMultiplier: {} },
+          new anchor.BN(multiplier),
+          new anchor.BN(minPurchase),
+          new anchor.BN(0),
+          new anchor.BN(0),
+          0,
+          0,
+          rewardKind as any,
+          new anchor.BN(flatBonus),
+          new anchor.BN(maxBonus)
+        )
+        .accountsPartial({
+          merchantAuthority: funded.merchant.publicKey,
+          loyaltyProgram: funded.loyaltyProgram,
+          merchant: merchantPda,
+          rewardRule: ruleAddress(ruleId, program.programId),
+        })
+        .signers([funded.merchant])
+        .rpc();
+    };
+
+    // Returns the points credited for the purchase
+    const issueWithRule = async (ruleId: number, purchaseAmount: number) => {
+      const { program } = await ctx;
+      const before = await program.account.customer.fetch(alicePda);
+      await program.methods
+        .issueRewards(new anchor.BN(purchaseAmount))
+        .accountsPartial({
+          merchantAuthority: funded.merchant.publicKey,
+          merchant: merchantPda,
+          customer: alicePda,
+          loyaltyProgram: funded.loyaltyProgram,
+          mint: funded.mint,
+          customerTokenAccount: funded.tokenAccountOf(alice.publicKey),
+          rewardRule: ruleAddress(ruleId, program.programId),
+        })
+        .signers([funded.merchant])
+        .rpc();
+      const after = await program.account.customer.fetch(alicePda);
+      return after.totalEarned.sub(before.totalEarned).toNumber();
+    };
+
+    before(async () => {
+      const { program } = await ctx;
+      funded = await createFundedProgram(await ctx, { transferable: {} }, 1);
+      [alice] = funded.customers;
+      [alicePda] = findProgramAddress(
+        [SEEDS.CUSTOMER, alice.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+      [merchantPda] = findProgramAddress(
+        [SEEDS.MERCHANT, funded.merchant.publicKey.toBuffer(), funded.loyaltyProgram.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("Validates the multiplier and flat bonus for each reward kind", async () => {
+      await assertError(() => createRule(10, { multiplier: {} }, 200, 50, 0), "InvalidRewardAmount");
+      await assertError(() => createRule(11, { flatBonus: {} }, 200, 50, 0), "InvalidRewardAmount");
+      await assertError(() => createRule(12, { flatBonus: {} }, 0, 0, 0), "InvalidRewardAmount");
+      await assertError(() => createRule(13, { multiplierAndBonus: {} }, 50, 50, 0), "InvalidRewardAmount");
+    });
+
+    it("Adds a flat bonus on orders over the minimum", async () => {
+      // +50 points on orders over $20
+      await createRule(1, { flatBonus: {} }, 0, 50, 0, 2_000);
+      assert.equal(await issueWithRule(1, 10_000), 60);
+      assert.equal(await issueWithRule(1, 1_000), 1);
+    });
+
+    it("Caps a multiplier rule's bonus at max_bonus", async () => {
+      // 3x capped at 5 bonus points: 10 points + min(20, 5)
+      await createRule(2, { multiplier: {} }, 300, 0, 5);
+      assert.equal(await issueWithRule(2, 10_000), 15);
+    });
+
+    it("Combines a multiplier and a flat bonus under one cap", async () => {
+      // 2x plus 20 points, capped at 25: 10 points + min(10 + 20, 25)
+      await createRule(3, { multiplierAndBonus: {} }, 200, 20, 25);
+      assert.equal(await issueWithRule(3, 10_000), 35);
+
+      const { program } = await ctx;
+      const rule = await program.account.rewardRule.fetch(ruleAddress(3, program.programId));
+      assert.deepEqual(rule.rewardKind, { multiplierAndBonus: {} });
+      assertBNEqual(rule.flatBonus, new anchor.BN(20));
+      assertBNEqual(rule.maxBonus, new anchor.BN(25));
+    });
+  });
 });
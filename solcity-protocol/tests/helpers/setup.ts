@@ -1,8 +1,12 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, AnchorProvider } from "@coral-xyz/anchor";
 import { SolcityProtocol } from "../../target/types/solcity_protocol";
-import { PublicKey, Keypair, Connection } from "@solana/web3.js";
-import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { PublicKey, Keypair, Connection, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
 
 export interface TestContext {
   provider: AnchorProvider;
@@ -60,6 +64,21 @@ export function findProgramAddress(
   return PublicKey.findProgramAddressSync(seeds, programId);
 }
 
+/**
+ * Decode the Anchor events emitted by a confirmed transaction.
+ */
+export async function getEvents(
+  { program, connection }: TestContext,
+  signature: string
+): Promise<anchor.Event[]> {
+  const tx = await connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(program.programId, program.coder);
+  return Array.from(parser.parseLogs(tx!.meta!.logMessages!));
+}
+
 export const SEEDS = {
   LOYALTY_PROGRAM: Buffer.from("loyalty_program"),
   MINT: Buffer.from("mint"),
@@ -67,3 +86,87 @@ export const SEEDS = {
   CUSTOMER: Buffer.from("customer"),
   REWARD_RULE: Buffer.from("reward_rule"),
 };
+
+export interface FundedProgram {
+  authority: Keypair;
+  loyaltyProgram: PublicKey;
+  mint: PublicKey;
+  merchant: Keypair;
+  customers: Keypair[];
+  tokenAccountOf: (owner: PublicKey) => PublicKey;
+}
+
+/**
+ * Create a fresh loyalty program with an approved merchant and registered customers.
+ * The first customer is issued rewards for a $100 purchase.
+ */
+export async function createFundedProgram(
+  { program, connection }: TestContext,
+  transferMode: object,
+  customerCount: number,
+  extraAccounts: Record<string, PublicKey> = {},
+  authority: Keypair = Keypair.generate()
+): Promise<FundedProgram> {
+  const merchant = Keypair.generate();
+  const customers = Array.from({ length: customerCount }, () => Keypair.generate());
+  for (const keypair of [authority, merchant, ...customers]) {
+    await airdrop(connection, keypair.publicKey);
+  }
+
+  const [loyaltyProgram] = findProgramAddress(
+    [SEEDS.LOYALTY_PROGRAM, authority.publicKey.toBuffer()],
+    program.programId
+  );
+  const [mint] = findProgramAddress([SEEDS.MINT, loyaltyProgram.toBuffer()], program.programId);
+  const [merchantPda] = findProgramAddress(
+    [SEEDS.MERCHANT, merchant.publicKey.toBuffer(), loyaltyProgram.toBuffer()],
+    program.programId
+  );
+  const tokenAccountOf = (owner: PublicKey) =>
+    getAssociatedTokenAddressSync(mint, owner, false, TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID);
+
+  await program.methods
+    .initializeProgram("Funded Program", "FUND", "https://solcity.example/token.json", 500, transferMode as any)
+    .accountsPartial({ authority: authority.publicKey, ...extraAccounts })
+    .signers([authority])
+    .rpc();
+
+  await program.methods
+    .registerMerchant("Funded Merchant", "", "Retail", null, new anchor.BN(10))
+    .accounts({ merchantAuthority: merchant.publicKey, loyaltyProgram })
+    .signers([merchant])
+    .rpc();
+
+  await program.methods
+    .approveMerchant()
+    .accounts({ authority: authority.publicKey, loyaltyProgram, merchant: merchantPda })
+    .signers([authority])
+    .rpc();
+
+  for (const customer of customers) {
+    await program.methods
+      .registerCustomer()
+      .accounts({ customerAuthority: customer.publicKey, loyaltyProgram })
+      .signers([customer])
+      .rpc();
+  }
+
+  const [firstCustomerPda] = findProgramAddress(
+    [SEEDS.CUSTOMER, customers[0].publicKey.toBuffer(), loyaltyProgram.toBuffer()],
+    program.programId
+  );
+  await program.methods
+    .issueRewards(new anchor.BN(10_000))
+    .accountsPartial({
+      merchantAuthority: merchant.publicKey,
+      customer: firstCustomerPda,
+      loyaltyProgram,
+      mint,
+      customerTokenAccount: tokenAccountOf(customers[0].publicKey),
+      rewardRule: SystemProgram.programId,
+    })
+    .signers([merchant])
+    .rpc();
+
+  return { authority, loyaltyProgram, mint, merchant, customers, tokenAccountOf };
+}